@@ -14,6 +14,10 @@ pub struct Repository {
     /// URL to objects source.
     /// FIXME: Can't use Url type directly here with `url_serde`, since it's not seen as optional.
     pub objects: Option<String>,
+    /// Bearer token to authenticate with the objects source.
+    pub token: Option<String>,
+    /// Path to the key used to sign published deployments.
+    pub signing_key: Option<PathBuf>,
 }
 
 impl Default for Repository {
@@ -21,6 +25,8 @@ impl Default for Repository {
         Repository {
             index: None,
             objects: None,
+            token: None,
+            signing_key: None,
         }
     }
 }
@@ -24,6 +24,7 @@ error_chain! {
         Toml(::toml::de::Error);
         UrlParseError(::url::ParseError);
         FromUtf8Error(::std::string::FromUtf8Error);
+        Json(::serde_json::Error);
     }
 
     errors {
@@ -0,0 +1,735 @@
+//! Canonical formatting of reproto specifications.
+//!
+//! Specifications are parsed into an `ast::File` and printed back. Regular comments are not part
+//! of the AST, so they are collected separately from the lexer and emitted before the first
+//! element that follows them, or at the end of the line they trail.
+
+use ast::{Channel, Decl, EnumBody, Endpoint, Field, File, InterfaceBody, Member, Name,
+          OptionDecl, ServiceBody, ServiceMember, SubType, TupleBody, Type, TypeBody, UseDecl,
+          Value};
+use core::{Loc, Object, RpModifier};
+use lexer::{self, Token};
+use parser;
+use std::rc::Rc;
+
+/// String used for each level of indentation.
+const INDENT: &str = "  ";
+
+/// Format the given specification, returning the canonical form of it.
+pub fn format(object: Rc<Box<Object>>, input: &str) -> parser::errors::Result<String> {
+    let file = parser::parse_string(object, input)?;
+
+    let mut comments = Vec::new();
+
+    // the input has already been lexed successfully by the parser.
+    for token in lexer::lex(input).with_comments() {
+        if let Ok((start, Token::Comment(comment), end)) = token {
+            comments.push((start, comment, end));
+        }
+    }
+
+    let mut formatter = Formatter {
+        source: input,
+        comments: comments,
+        next_comment: 0,
+        lines: Vec::new(),
+        indent: 0,
+    };
+
+    formatter.file(&file);
+    Ok(formatter.finish())
+}
+
+struct Formatter<'a> {
+    /// Source that is being formatted.
+    source: &'a str,
+    /// All comments in the source, in order of appearance.
+    comments: Vec<(usize, &'a str, usize)>,
+    /// Index of the next comment which has not been emitted.
+    next_comment: usize,
+    /// Lines that have been emitted.
+    lines: Vec<String>,
+    /// Current level of indentation.
+    indent: usize,
+}
+
+impl<'a> Formatter<'a> {
+    fn finish(mut self) -> String {
+        let end = self.source.len();
+        self.comments_before(end);
+
+        while self.lines.last().map(String::is_empty).unwrap_or(false) {
+            self.lines.pop();
+        }
+
+        let mut out = self.lines.join("\n");
+        out.push('\n');
+        out
+    }
+
+    /// Emit a single line at the current level of indentation.
+    fn line<S: AsRef<str>>(&mut self, line: S) {
+        let line = line.as_ref();
+
+        if line.is_empty() {
+            self.lines.push(String::new());
+            return;
+        }
+
+        let mut out = String::new();
+
+        for _ in 0..self.indent {
+            out.push_str(INDENT);
+        }
+
+        out.push_str(line);
+        self.lines.push(out);
+    }
+
+    /// Emit an empty line, unless one was just emitted or a block was just opened.
+    fn blank(&mut self) {
+        match self.lines.last() {
+            Some(last) if !last.is_empty() && !last.ends_with('{') => {}
+            _ => return,
+        }
+
+        self.lines.push(String::new());
+    }
+
+    /// Take all comments that start before the given position, without emitting them.
+    fn take_comments_before(&mut self, pos: usize) -> Vec<(usize, &'a str, usize)> {
+        let mut out = Vec::new();
+
+        while let Some(&comment) = self.comments.get(self.next_comment) {
+            if comment.0 >= pos {
+                break;
+            }
+
+            self.next_comment += 1;
+            out.push(comment);
+        }
+
+        out
+    }
+
+    /// Emit the given comments.
+    fn emit_comments(&mut self, comments: &[(usize, &str, usize)]) {
+        for &(_, comment, _) in comments {
+            let mut lines = comment.lines();
+
+            if let Some(first) = lines.next() {
+                self.line(first.trim_right());
+            }
+
+            // continuation lines of block comments are kept verbatim.
+            for line in lines {
+                self.lines.push(line.trim_right().to_owned());
+            }
+        }
+    }
+
+    /// Emit all comments that start before the given position.
+    fn comments_before(&mut self, pos: usize) {
+        let comments = self.take_comments_before(pos);
+        self.emit_comments(&comments);
+    }
+
+    /// Take a comment trailing on the same line as an element that ends at the given position.
+    fn take_trailing_comment(&mut self, end: usize) -> Option<&'a str> {
+        let (start, comment, _) = match self.comments.get(self.next_comment) {
+            Some(&comment) => comment,
+            None => return None,
+        };
+
+        if start < end || self.source[end..start].contains('\n') || comment.contains('\n') {
+            return None;
+        }
+
+        self.next_comment += 1;
+        Some(comment)
+    }
+
+    /// Append a trailing comment to the last emitted line.
+    fn emit_trailing_comment(&mut self, comment: &str) {
+        if let Some(last) = self.lines.last_mut() {
+            last.push(' ');
+            last.push_str(comment.trim_right());
+        }
+    }
+
+    /// Emit a comment trailing on the same line as an element that ends at the given position.
+    fn trailing_comment(&mut self, end: usize) {
+        if let Some(comment) = self.take_trailing_comment(end) {
+            self.emit_trailing_comment(comment);
+        }
+    }
+
+    /// Check if there is an empty line in the source between the two positions.
+    fn has_blank_line(&self, start: usize, end: usize) -> bool {
+        if start >= end {
+            return false;
+        }
+
+        let lines: Vec<&str> = self.source[start..end].split('\n').collect();
+
+        if lines.len() < 3 {
+            return false;
+        }
+
+        lines[1..lines.len() - 1].iter().any(|line| line.trim().is_empty())
+    }
+
+    /// Emit documentation comments.
+    fn doc_comment(&mut self, prefix: &str, comment: &[&str]) {
+        for line in comment {
+            self.line(format!("{}{}", prefix, line).trim_right());
+        }
+    }
+
+    /// Open a block, emitting all comments that precede it.
+    fn open<S: AsRef<str>>(&mut self, pos: usize, comment: &[&str], head: S) {
+        self.comments_before(pos);
+        self.doc_comment("///", comment);
+        self.line(format!("{} {{", head.as_ref()));
+        self.indent += 1;
+    }
+
+    /// Close a block which ends at the given position.
+    fn close(&mut self, end: usize) {
+        self.comments_before(end);
+        self.indent -= 1;
+        self.line("}");
+        self.trailing_comment(end);
+    }
+
+    fn file(&mut self, file: &File) {
+        self.doc_comment("//!", &file.comment);
+
+        if !file.comment.is_empty() {
+            self.blank();
+        }
+
+        // comments travel with the use they precede, so that sorting doesn't detach them.
+        let mut uses = Vec::new();
+
+        for u in &file.uses {
+            let mut leading = self.take_comments_before(u.pos().start);
+
+            // comments separated from the first use by an empty line belong to the file.
+            if uses.is_empty() {
+                let start = u.pos().start;
+
+                let header = leading
+                    .iter()
+                    .rposition(|&(_, _, end)| self.has_blank_line(end, start))
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+
+                let rest = leading.split_off(header);
+                self.emit_comments(&leading);
+
+                if !leading.is_empty() {
+                    self.blank();
+                }
+
+                leading = rest;
+            }
+
+            let trailing = self.take_trailing_comment(u.pos().end);
+            uses.push((u, leading, trailing));
+        }
+
+        uses.sort_by_key(|&(u, _, _)| {
+            let alias = u.alias.as_ref().map(|a| a.value().to_string());
+            (u.package.value().parts.clone(), alias)
+        });
+
+        for (u, leading, trailing) in uses {
+            self.emit_comments(&leading);
+            let line = self.use_decl(u);
+            self.line(line);
+
+            if let Some(trailing) = trailing {
+                self.emit_trailing_comment(trailing);
+            }
+        }
+
+        self.blank();
+
+        for option in &file.options {
+            self.comments_before(option.pos().start);
+            let line = self.option_decl(option);
+            self.line(line);
+            self.trailing_comment(option.pos().end);
+        }
+
+        self.blank();
+
+        for decl in &file.decls {
+            self.blank();
+            self.decl(decl.value(), decl.pos().start);
+        }
+    }
+
+    fn use_decl(&self, u: &UseDecl) -> String {
+        let mut out = format!("use {}", u.package.value());
+
+        if let Some(ref version_req) = u.version_req {
+            out.push_str(&format!(" {}", string(version_req.value())));
+        }
+
+        if let Some(ref alias) = u.alias {
+            out.push_str(&format!(" as {}", alias.value()));
+        }
+
+        out.push(';');
+        out
+    }
+
+    fn option_decl(&self, option: &OptionDecl) -> String {
+        format!("option {} = {};", option.name, value(option.value.value()))
+    }
+
+    fn decl(&mut self, decl: &Decl, start: usize) {
+        match *decl {
+            Decl::Type(ref body) => self.type_body(body, start),
+            Decl::Tuple(ref body) => self.tuple_body(body, start),
+            Decl::Interface(ref body) => self.interface_body(body, start),
+            Decl::Enum(ref body) => self.enum_body(body, start),
+            Decl::Service(ref body) => self.service_body(body, start),
+        }
+    }
+
+    fn type_body(&mut self, body: &Loc<TypeBody>, start: usize) {
+        let head = format!(
+            "type {}{}",
+            body.name,
+            type_parameters(&body.type_parameters)
+        );
+
+        self.open(start, &body.comment, head);
+        self.members(&body.members);
+        self.close(body.pos().end);
+    }
+
+    fn tuple_body(&mut self, body: &Loc<TupleBody>, start: usize) {
+        let head = format!(
+            "tuple {}{}",
+            body.name,
+            type_parameters(&body.type_parameters)
+        );
+
+        self.open(start, &body.comment, head);
+        self.members(&body.members);
+        self.close(body.pos().end);
+    }
+
+    fn interface_body(&mut self, body: &Loc<InterfaceBody>, start: usize) {
+        let head = format!(
+            "interface {}{}",
+            body.name,
+            type_parameters(&body.type_parameters)
+        );
+
+        self.open(start, &body.comment, head);
+        self.members(&body.members);
+
+        for sub_type in &body.sub_types {
+            self.blank();
+            self.sub_type(sub_type);
+        }
+
+        self.close(body.pos().end);
+    }
+
+    fn sub_type(&mut self, sub_type: &Loc<SubType>) {
+        let mut head = sub_type.name.value().to_string();
+
+        if let Some(ref alias) = sub_type.alias {
+            head.push_str(&format!(" as {}", value(alias.value())));
+        }
+
+        if sub_type.members.is_empty() {
+            self.comments_before(sub_type.pos().start);
+            self.doc_comment("///", &sub_type.comment);
+            self.line(format!("{};", head));
+            self.trailing_comment(sub_type.pos().end);
+            return;
+        }
+
+        self.open(sub_type.pos().start, &sub_type.comment, head);
+        self.members(&sub_type.members);
+        self.close(sub_type.pos().end);
+    }
+
+    fn enum_body(&mut self, body: &Loc<EnumBody>, start: usize) {
+        let mut head = format!("enum {}", body.name);
+
+        if let Some(ref ty) = body.ty {
+            head.push_str(&format!(" as {}", type_spec(ty.value())));
+        }
+
+        self.open(start, &body.comment, head);
+
+        for variant in &body.variants {
+            let mut head = variant.name.value().to_string();
+
+            if let Some(ref argument) = variant.argument {
+                head.push_str(&format!(" as {}", value(argument.value())));
+            }
+
+            if variant.options.is_empty() {
+                self.comments_before(variant.pos().start);
+                self.doc_comment("///", &variant.comment);
+                self.line(format!("{};", head));
+                self.trailing_comment(variant.pos().end);
+                continue;
+            }
+
+            self.open(variant.pos().start, &variant.comment, head);
+            self.options(&variant.options);
+            self.close(variant.pos().end);
+        }
+
+        if !body.variants.is_empty() {
+            self.blank();
+        }
+
+        self.members(&body.members);
+        self.close(body.pos().end);
+    }
+
+    fn service_body(&mut self, body: &Loc<ServiceBody>, start: usize) {
+        self.open(start, &body.comment, format!("service {}", body.name));
+
+        let mut previous: Option<usize> = None;
+
+        for member in &body.members {
+            let (pos, is_decl) = match *member {
+                ServiceMember::Endpoint(ref endpoint) => (endpoint.pos(), false),
+                ServiceMember::Option(ref option) => (option.pos(), false),
+                ServiceMember::InnerDecl(ref decl) => (decl.pos(), true),
+            };
+
+            if let Some(end) = previous {
+                if is_decl || self.has_blank_line(end, pos.start) {
+                    self.blank();
+                }
+            }
+
+            match *member {
+                ServiceMember::Endpoint(ref endpoint) => self.endpoint(endpoint),
+                ServiceMember::Option(ref option) => {
+                    self.comments_before(pos.start);
+                    let line = self.option_decl(option);
+                    self.line(line);
+                    self.trailing_comment(pos.end);
+                }
+                ServiceMember::InnerDecl(ref decl) => self.decl(decl.value(), pos.start),
+            }
+
+            previous = Some(pos.end);
+        }
+
+        self.close(body.pos().end);
+    }
+
+    fn endpoint(&mut self, endpoint: &Loc<Endpoint>) {
+        let mut head = format!("{}(", endpoint.id.value());
+
+        if let Some(ref request) = endpoint.request {
+            head.push_str(&channel(request.value()));
+        }
+
+        head.push(')');
+
+        if let Some(ref response) = endpoint.response {
+            head.push_str(&format!(" -> {}", channel(response.value())));
+        }
+
+        if let Some(ref alias) = endpoint.alias {
+            head.push_str(&format!(" as {}", alias_name(alias)));
+        }
+
+        if endpoint.options.is_empty() {
+            self.comments_before(endpoint.pos().start);
+            self.doc_comment("///", &endpoint.comment);
+            self.line(format!("{};", head));
+            self.trailing_comment(endpoint.pos().end);
+            return;
+        }
+
+        self.open(endpoint.pos().start, &endpoint.comment, head);
+        self.options(&endpoint.options);
+        self.close(endpoint.pos().end);
+    }
+
+    fn options(&mut self, options: &[Loc<OptionDecl>]) {
+        for option in options {
+            self.comments_before(option.pos().start);
+            let line = self.option_decl(option);
+            self.line(line);
+            self.trailing_comment(option.pos().end);
+        }
+    }
+
+    fn members(&mut self, members: &[Loc<Member>]) {
+        let mut previous: Option<(usize, bool)> = None;
+
+        for member in members {
+            let pos = member.pos();
+
+            let is_block = match *member.value() {
+                Member::Code(..) | Member::InnerDecl(..) => true,
+                _ => false,
+            };
+
+            if let Some((end, previous_block)) = previous {
+                if is_block || previous_block || self.has_blank_line(end, pos.start) {
+                    self.blank();
+                }
+            }
+
+            match *member.value() {
+                Member::Field(ref field) => self.field(field, pos.start, pos.end),
+                Member::Code(context, ref lines) => {
+                    self.comments_before(pos.start);
+                    self.line(format!("{} {{{{", context));
+                    self.indent += 1;
+
+                    for line in lines {
+                        self.line(line.trim_right());
+                    }
+
+                    self.indent -= 1;
+                    self.line("}}");
+                    self.trailing_comment(pos.end);
+                }
+                Member::Option(ref option) => {
+                    self.comments_before(pos.start);
+                    let line = self.option_decl(option);
+                    self.line(line);
+                    self.trailing_comment(pos.end);
+                }
+                Member::InnerDecl(ref decl) => self.decl(decl, pos.start),
+            }
+
+            previous = Some((pos.end, is_block));
+        }
+    }
+
+    fn field(&mut self, field: &Field, start: usize, end: usize) {
+        let modifier = match field.modifier {
+            RpModifier::Optional => "?",
+            _ => "",
+        };
+
+        let mut head = format!("{}{}: {}", field.name, modifier, type_spec(&field.ty));
+
+        if let Some(ref field_as) = field.field_as {
+            head.push_str(&format!(" as {}", alias_name(field_as)));
+        }
+
+        if let Some(ref default) = field.default {
+            head.push_str(&format!(" = {}", value(default.value())));
+        }
+
+        if field.options.is_empty() {
+            self.comments_before(start);
+            self.doc_comment("///", &field.comment);
+            self.line(format!("{};", head));
+            self.trailing_comment(end);
+            return;
+        }
+
+        self.open(start, &field.comment, head);
+        self.options(&field.options);
+        self.close(end);
+    }
+}
+
+/// Format a list of type parameters, like `<T, U>`.
+fn type_parameters(parameters: &[Loc<&str>]) -> String {
+    if parameters.is_empty() {
+        return String::new();
+    }
+
+    let parameters: Vec<&str> = parameters.iter().map(|p| *p.value()).collect();
+    format!("<{}>", parameters.join(", "))
+}
+
+fn channel(channel: &Channel) -> String {
+    match *channel {
+        Channel::Unary { ref ty } => type_spec(ty),
+        Channel::Streaming { ref ty } => format!("stream {}", type_spec(ty)),
+    }
+}
+
+fn type_spec(ty: &Type) -> String {
+    match *ty {
+        Type::Double => "double".to_string(),
+        Type::Float => "float".to_string(),
+        Type::Signed { ref size } => format!("i{}", size),
+        Type::Unsigned { ref size } => format!("u{}", size),
+        Type::Boolean => "boolean".to_string(),
+        Type::String => "string".to_string(),
+        Type::Bytes => "bytes".to_string(),
+        Type::Any => "any".to_string(),
+        Type::DateTime => "datetime".to_string(),
+        Type::Name {
+            ref name,
+            ref arguments,
+        } => {
+            let mut out = match *name {
+                Name::Relative { ref parts } => format!("::{}", parts.join("::")),
+                Name::Absolute {
+                    prefix: Some(ref prefix),
+                    ref parts,
+                } => format!("{}::{}", prefix, parts.join("::")),
+                Name::Absolute { ref parts, .. } => parts.join("::"),
+            };
+
+            if !arguments.is_empty() {
+                let arguments: Vec<_> = arguments.iter().map(type_spec).collect();
+                out.push_str(&format!("<{}>", arguments.join(", ")));
+            }
+
+            out
+        }
+        Type::Array { ref inner } => format!("[{}]", type_spec(inner)),
+        Type::Map { ref key, ref value } => {
+            format!("{{{}: {}}}", type_spec(key), type_spec(value))
+        }
+    }
+}
+
+fn value(value: &Value) -> String {
+    match *value {
+        Value::String(ref s) => string(s),
+        Value::Number(ref number) => number.to_string(),
+        Value::Boolean(ref boolean) => boolean.to_string(),
+        Value::Identifier(identifier) => identifier.to_string(),
+        Value::Array(ref values) => {
+            let values: Vec<_> = values.iter().map(|v| self::value(v.value())).collect();
+            format!("[{}]", values.join(", "))
+        }
+    }
+}
+
+/// Quote a string literal, escaping characters which can't be represented as-is.
+///
+/// The lexer has no escapes for quotes or backslashes, so they are escaped as unicode sequences
+/// like other control characters.
+fn string(input: &str) -> String {
+    let mut out = String::new();
+    out.push('"');
+
+    for c in input.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == '"' || c == '\\' || c.is_control() => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Format a field or endpoint alias.
+///
+/// Aliases are kept as plain identifiers, unless they would be lexed as something else.
+fn alias_name(alias: &str) -> String {
+    let tokens: Vec<_> = lexer::lex(alias).collect();
+
+    if tokens.len() == 1 {
+        if let Some(&Ok((_, Token::Identifier(identifier), _))) = tokens.first() {
+            if identifier == alias {
+                return alias.to_string();
+            }
+        }
+    }
+
+    string(alias)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::BytesObject;
+    use std::sync::Arc;
+
+    fn object(input: &str) -> Rc<Box<Object>> {
+        let bytes = Arc::new(input.as_bytes().to_vec());
+        Rc::new(Box::new(BytesObject::new("test".to_string(), bytes)))
+    }
+
+    fn fmt(input: &str) -> String {
+        format(object(input), input).expect("failed to format")
+    }
+
+    /// Parse the value of the first option of a file, which has to be a string.
+    fn option_string(input: &str) -> String {
+        let file = parser::parse_string(object(input), input).expect("failed to parse");
+
+        match *file.options[0].value.value() {
+            Value::String(ref string) => string.to_string(),
+            _ => panic!("expected string"),
+        }
+    }
+
+    #[test]
+    fn test_idempotent() {
+        let input = "use  b;\nuse a as x;\noption x = 1;\n\
+                     /// Docs.\ntype Foo{a:string; // trailing\n\n// leading\nb?:[u32];}\n\
+                     enum E as string{A as \"a\";B as \"b\"{option x = 1;}}\n\
+                     service S{get()->Foo;}";
+
+        let formatted = fmt(input);
+        assert_eq!(formatted, fmt(&formatted));
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = "// before\ntype Foo {\n  a: string; // after a\n  // before b\n  b: u32;\n}\n";
+        assert_eq!(input, fmt(input));
+    }
+
+    #[test]
+    fn test_sorted_uses() {
+        let input = "use c;\nuse b as x;\nuse a;\n";
+        assert_eq!("use a;\nuse b as x;\nuse c;\n", fmt(input));
+    }
+
+    #[test]
+    fn test_sorted_uses_keep_comments() {
+        let input = "// about c\nuse c; // trailing c\n// about a\nuse a;\n";
+        let expected = "// about a\nuse a;\n// about c\nuse c; // trailing c\n";
+        assert_eq!(expected, fmt(input));
+    }
+
+    #[test]
+    fn test_sorted_uses_keep_header() {
+        let input = "// header\n\n// about b\nuse b;\nuse a;\n";
+        let expected = "// header\n\nuse a;\n// about b\nuse b;\n";
+        assert_eq!(expected, fmt(input));
+    }
+
+    #[test]
+    fn test_doc_comment_code_block() {
+        let input = "/// Example:\n///\n/// ```\n///   indented;\n/// ```\ntype Foo {\n}\n";
+        assert_eq!(input, fmt(input));
+    }
+
+    #[test]
+    fn test_string_round_trip() {
+        let input = "option x = \"quote \\u0022, backslash \\u005c, bell \\u0007, tab \\t\";\n";
+        let formatted = fmt(input);
+        assert_eq!(input, formatted);
+        assert_eq!("quote \", backslash \\, bell \u{7}, tab \t", option_string(&formatted));
+    }
+}
@@ -0,0 +1,992 @@
+//! Language server for reproto specifications.
+//!
+//! Speaks the language server protocol over stdio, and resolves packages exclusively through the
+//! configured paths so that it works without access to any repository.
+
+mod protocol;
+
+use self::protocol::*;
+use backend::Environment;
+use core::{self, ErrorPos, Loc, Object, RpDecl, RpField, RpName, RpPackage, RpReg, RpType,
+           RpVersionedPackage, Version};
+use errors::*;
+use lexer::{self, Token};
+use parser;
+use repository::Paths;
+use serde_json::{self, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use url::Url;
+
+/// Source used for all published diagnostics.
+const SOURCE: &str = "reproto";
+
+/// Built-in types which are always available for completion.
+const BUILT_IN_TYPES: &[&str] = &[
+    "any",
+    "boolean",
+    "bytes",
+    "datetime",
+    "double",
+    "float",
+    "i32",
+    "i64",
+    "string",
+    "u32",
+    "u64",
+];
+
+/// An object for a document that is being edited, whose content might not have been saved.
+#[derive(Debug)]
+struct DocumentObject {
+    path: Arc<PathBuf>,
+    content: Arc<Vec<u8>>,
+}
+
+impl Object for DocumentObject {
+    fn path(&self) -> Option<&Path> {
+        Some(self.path.as_ref())
+    }
+
+    fn read<'a>(&'a self) -> core::errors::Result<Box<Read + 'a>> {
+        Ok(Box::new(Cursor::new(self.content.as_ref())))
+    }
+
+    fn clone_object(&self) -> Box<Object> {
+        Box::new(DocumentObject {
+            path: self.path.clone(),
+            content: self.content.clone(),
+        })
+    }
+
+    fn with_name(&self, _: String) -> Box<Object> {
+        self.clone_object()
+    }
+}
+
+impl fmt::Display for DocumentObject {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.path.display())
+    }
+}
+
+/// A document which is open in the editor.
+struct Document {
+    /// Path of the document.
+    path: PathBuf,
+    /// Current content of the document.
+    text: String,
+    /// Package that the document belongs to.
+    package: RpVersionedPackage,
+    /// Environment of the last successful check, and the content it was checked with.
+    checked: Option<(Environment, String)>,
+    /// Aliases for imported packages, as of the last successful parse.
+    prefixes: Vec<(String, RpPackage)>,
+}
+
+impl Document {
+    /// Environment of the last successful check, if it was made against the current content.
+    ///
+    /// Positions from the client always refer to the current content, so they can't be mapped
+    /// against a check of an older version.
+    fn current(&self) -> Option<&Environment> {
+        match self.checked {
+            Some((ref env, ref text)) if *text == self.text => Some(env),
+            _ => None,
+        }
+    }
+}
+
+/// What is found at a position in a document.
+enum Target<'a> {
+    /// A reference to a declaration.
+    Name(&'a RpName, (usize, usize)),
+    /// A field.
+    Field(&'a RpField, (usize, usize)),
+}
+
+struct Server {
+    /// Paths to resolve packages from.
+    paths: Vec<PathBuf>,
+    /// Open documents, by URI.
+    documents: HashMap<String, Document>,
+}
+
+impl Server {
+    /// Determine the package of a file, based on where it is located in the paths.
+    fn package_for(&self, path: &Path) -> RpVersionedPackage {
+        for root in &self.paths {
+            let relative = match path.strip_prefix(root) {
+                Ok(relative) => relative,
+                Err(_) => continue,
+            };
+
+            let mut parts: Vec<String> = relative
+                .parent()
+                .into_iter()
+                .flat_map(|p| p.components())
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+
+            let stem = relative
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let mut it = stem.splitn(2, '-');
+
+            let (base, version) = match (it.next(), it.next()) {
+                (Some(base), Some(version)) => (base.to_owned(), Version::parse(version).ok()),
+                _ => (stem.clone(), None),
+            };
+
+            parts.push(base);
+            return RpVersionedPackage::new(RpPackage::new(parts), version);
+        }
+
+        // files outside of the paths are treated like files given with `--file`.
+        RpVersionedPackage::new(RpPackage::empty(), None)
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Option<Value>> {
+        let result = match method {
+            "initialize" => {
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": SYNC_FULL,
+                        "hoverProvider": true,
+                        "definitionProvider": true,
+                        "completionProvider": {
+                            "triggerCharacters": [":"],
+                        },
+                    },
+                })
+            }
+            "shutdown" => Value::Null,
+            "textDocument/hover" => {
+                serde_json::to_value(self.hover(serde_json::from_value(params)?))?
+            }
+            "textDocument/definition" => {
+                serde_json::to_value(self.definition(serde_json::from_value(params)?)?)?
+            }
+            "textDocument/completion" => {
+                serde_json::to_value(self.completion(serde_json::from_value(params)?))?
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(result))
+    }
+
+    fn notification(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> Result<Option<PublishDiagnosticsParams>> {
+        let uri = match method {
+            "textDocument/didOpen" => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(params)?;
+                let item = params.text_document;
+
+                let path = Url::parse(&item.uri)?.to_file_path().map_err(|_| {
+                    format!("not a file: {}", item.uri)
+                })?;
+
+                let document = Document {
+                    package: self.package_for(&path),
+                    path: path,
+                    text: item.text,
+                    checked: None,
+                    prefixes: Vec::new(),
+                };
+
+                self.documents.insert(item.uri.clone(), document);
+                item.uri
+            }
+            "textDocument/didChange" => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(params)?;
+                let uri = params.text_document.uri;
+
+                if let Some(document) = self.documents.get_mut(&uri) {
+                    if let Some(change) = params.content_changes.into_iter().last() {
+                        document.text = change.text;
+                    }
+                }
+
+                uri
+            }
+            "textDocument/didSave" => {
+                let params: DidSaveTextDocumentParams = serde_json::from_value(params)?;
+                let uri = params.text_document.uri;
+
+                if let (Some(document), Some(text)) = (self.documents.get_mut(&uri), params.text) {
+                    document.text = text;
+                }
+
+                uri
+            }
+            "textDocument/didClose" => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(params)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+
+                return Ok(Some(PublishDiagnosticsParams {
+                    uri: uri,
+                    diagnostics: vec![],
+                }));
+            }
+            _ => return Ok(None),
+        };
+
+        let paths = self.paths.clone();
+
+        let document = match self.documents.get_mut(&uri) {
+            Some(document) => document,
+            None => return Ok(None),
+        };
+
+        Ok(Some(PublishDiagnosticsParams {
+            uri: uri,
+            diagnostics: check(paths, document),
+        }))
+    }
+
+    fn hover(&self, params: TextDocumentPositionParams) -> Option<Hover> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let env = document.current()?;
+        let offset = offset(&document.text, params.position);
+
+        let (value, (start, end)) = match target_at(document, env, offset)? {
+            Target::Name(name, range) => {
+                let reg = env.lookup(name).ok()?;
+                (describe(&reg.to_string(), comment(reg)), range)
+            }
+            Target::Field(field, range) => {
+                let modifier = if field.is_optional() { "?" } else { "" };
+                let signature = format!("{}{}: {}", field.name(), modifier, field.ty);
+                (describe(&signature, &field.comment), range)
+            }
+        };
+
+        Some(Hover {
+            contents: MarkupContent {
+                kind: "markdown",
+                value: value,
+            },
+            range: Range {
+                start: position(&document.text, start),
+                end: position(&document.text, end),
+            },
+        })
+    }
+
+    fn definition(&self, params: TextDocumentPositionParams) -> Result<Option<Location>> {
+        let document = match self.documents.get(&params.text_document.uri) {
+            Some(document) => document,
+            None => return Ok(None),
+        };
+
+        let env = match document.current() {
+            Some(env) => env,
+            None => return Ok(None),
+        };
+
+        let offset = offset(&document.text, params.position);
+
+        let name = match target_at(document, env, offset) {
+            Some(Target::Name(name, _)) => name,
+            _ => return Ok(None),
+        };
+
+        let pos = match env.lookup(name) {
+            Ok(reg) => reg.pos(),
+            Err(_) => return Ok(None),
+        };
+
+        let path = match pos.object.path() {
+            Some(path) => path.to_owned(),
+            None => return Ok(None),
+        };
+
+        // only the document itself is checked from memory, every other file is read from disk.
+        let text = if path == document.path {
+            document.text.clone()
+        } else {
+            let mut text = String::new();
+            File::open(&path)?.read_to_string(&mut text)?;
+            text
+        };
+
+        let uri = Url::from_file_path(&path).map_err(|_| {
+            format!("not a valid path: {}", path.display())
+        })?;
+
+        Ok(Some(Location {
+            uri: uri.to_string(),
+            range: Range {
+                start: position(&text, pos.start),
+                end: position(&text, pos.end),
+            },
+        }))
+    }
+
+    fn completion(&self, params: TextDocumentPositionParams) -> Vec<CompletionItem> {
+        let mut items = Vec::new();
+
+        for ty in BUILT_IN_TYPES {
+            items.push(CompletionItem {
+                label: ty.to_string(),
+                kind: COMPLETION_KEYWORD,
+                detail: None,
+            });
+        }
+
+        let document = match self.documents.get(&params.text_document.uri) {
+            Some(document) => document,
+            None => return items,
+        };
+
+        for &(ref alias, ref package) in &document.prefixes {
+            items.push(CompletionItem {
+                label: alias.clone(),
+                kind: COMPLETION_MODULE,
+                detail: Some(package.to_string()),
+            });
+        }
+
+        let env = match document.checked {
+            Some((ref env, _)) => env,
+            None => return items,
+        };
+
+        for decl in env.decl_iter() {
+            let name = decl.name();
+            let local = name.parts.join("::");
+
+            let label = if name.package == document.package {
+                local
+            } else {
+                let prefix = document.prefixes.iter().find(|&&(_, ref package)| {
+                    name.package.package == *package
+                });
+
+                match prefix {
+                    Some(&(ref alias, _)) => format!("{}::{}", alias, local),
+                    None => continue,
+                }
+            };
+
+            items.push(CompletionItem {
+                label: label,
+                kind: COMPLETION_CLASS,
+                detail: Some(format!("{} {}", decl.kind(), name)),
+            });
+        }
+
+        items
+    }
+}
+
+/// Check the given document, returning all diagnostics for it.
+fn check(paths: Vec<PathBuf>, document: &mut Document) -> Vec<Diagnostic> {
+    let object = DocumentObject {
+        path: Arc::new(document.path.clone()),
+        content: Arc::new(document.text.clone().into_bytes()),
+    };
+
+    if let Ok(file) = parser::parse_string(Rc::new(object.clone_object()), &document.text) {
+        document.prefixes = file.uses
+            .iter()
+            .flat_map(|u| {
+                let package = u.package.value().clone();
+                let alias = u.alias.as_ref().map(|a| a.value().to_string());
+                let alias = alias.or_else(|| package.parts.iter().last().cloned());
+                alias.map(|alias| (alias, package))
+            })
+            .collect();
+    }
+
+    let resolver = Box::new(Paths::new(paths, HashMap::new()));
+    let mut env = Environment::new(None, resolver);
+
+    let result = env.import_object(object, Some(document.package.clone()))
+        .and_then(|_| env.verify());
+
+    let error = match result {
+        Ok(()) => {
+            document.checked = Some((env, document.text.clone()));
+            return vec![];
+        }
+        Err(error) => error,
+    };
+
+    let mut errors = Vec::new();
+    error_positions(&error, &mut errors);
+
+    let mut diagnostics = Vec::new();
+
+    for (message, pos) in errors {
+        let (start, end) = match pos {
+            Some(pos) if pos.object.path() == Some(document.path.as_path()) => (pos.start, pos.end),
+            Some(pos) => {
+                // errors in other files are reported at the beginning of the document.
+                diagnostics.push(diagnostic(
+                    &document.text,
+                    (0, 0),
+                    format!("{}: {}", pos.object, message),
+                ));
+                continue;
+            }
+            None => (0, 0),
+        };
+
+        diagnostics.push(diagnostic(&document.text, (start, end), message));
+    }
+
+    diagnostics
+}
+
+fn diagnostic(text: &str, (start, end): (usize, usize), message: String) -> Diagnostic {
+    Diagnostic {
+        range: Range {
+            start: position(text, start),
+            end: position(text, end),
+        },
+        severity: SEVERITY_ERROR,
+        source: SOURCE,
+        message: message,
+    }
+}
+
+/// Collect all messages and their positions from the given error.
+fn error_positions<'a>(
+    e: &'a backend::errors::Error,
+    out: &mut Vec<(String, Option<&'a ErrorPos>)>,
+) {
+    use backend::errors::ErrorKind::*;
+
+    match *e.kind() {
+        Pos(ref m, ref p) => out.push((m.to_string(), Some(p))),
+        Core(ref e) => core_error_positions(e, out),
+        Parser(ref e) => parser_error_positions(e, out),
+        Errors(ref errors) => {
+            for e in errors {
+                error_positions(e, out);
+            }
+        }
+        MissingRequired(ref names, ref p, _) => {
+            out.push((format!("missing required fields: {}", names.join(", ")), Some(p)));
+        }
+        FieldConflict(ref name, ref p, _) => {
+            out.push((format!("conflict in field `{}`", name), Some(p)));
+        }
+        EnumVariantConflict(ref p, _) => out.push(("conflicting name".to_string(), Some(p))),
+        EnumOrdinalConflict(ref p, _) => out.push(("conflicting ordinal".to_string(), Some(p))),
+        EndpointConflict(ref p, _) => {
+            out.push(("conflicting id of endpoint".to_string(), Some(p)));
+        }
+        EndpointNameConflict(ref p, _) => {
+            out.push(("conflicting name of endpoint".to_string(), Some(p)));
+        }
+        RegisteredTypeConflict(ref name, _, ref p) => {
+            out.push((format!("conflicts with existing declaration `{}`", name), Some(p)));
+        }
+        _ => out.push((e.to_string(), None)),
+    }
+}
+
+fn core_error_positions<'a>(
+    e: &'a core::errors::ErrorKind,
+    out: &mut Vec<(String, Option<&'a ErrorPos>)>,
+) {
+    use core::errors::ErrorKind::*;
+
+    match *e {
+        Pos(ref m, ref p) => out.push((m.to_string(), Some(p))),
+        DeclMerge(ref m, ref p, _) => out.push((m.to_string(), Some(p))),
+        FieldConflict(ref name, ref p, _) => {
+            out.push((format!("conflict in field `{}`", name), Some(p)));
+        }
+        ExtendEnum(ref m, ref p, _) => out.push((m.to_string(), Some(p))),
+        ReservedField(ref p, _) => out.push(("field reserved".to_string(), Some(p))),
+        MatchConflict(ref p, _) => {
+            out.push(("conflicts with existing clause".to_string(), Some(p)));
+        }
+        ref e => out.push((e.to_string(), None)),
+    }
+}
+
+fn parser_error_positions<'a>(
+    e: &'a parser::errors::ErrorKind,
+    out: &mut Vec<(String, Option<&'a ErrorPos>)>,
+) {
+    use parser::errors::ErrorKind::*;
+
+    match *e {
+        Pos(ref m, ref p) => out.push((m.to_string(), Some(p))),
+        Core(ref e) => core_error_positions(e, out),
+        Syntax(ref p, ref expected) => {
+            let m = if !expected.is_empty() {
+                format!("unexpected token, expected one of: {}", expected.join(", "))
+            } else {
+                String::from("syntax error")
+            };
+
+            out.push((m, p.as_ref()));
+        }
+        Parse(ref m, ref p) => out.push((m.to_string(), Some(p))),
+        ref e => out.push((e.to_string(), None)),
+    }
+}
+
+/// Find what is at the given offset of a document, which `env` is a check of.
+fn target_at<'a>(
+    document: &'a Document,
+    env: &'a Environment,
+    offset: usize,
+) -> Option<Target<'a>> {
+    let (_, file) = env.for_each_file().find(|&(package, _)| *package == document.package)?;
+
+    // the type name under the cursor, if any.
+    let word = lexer::lex(&document.text)
+        .flat_map(|token| token.ok())
+        .find(|&(start, _, end)| start <= offset && offset <= end)
+        .and_then(|(start, token, end)| match token {
+            Token::TypeIdentifier(word) => Some((word, (start, end))),
+            _ => None,
+        });
+
+    // find the innermost field or channel which contains the cursor.
+    let mut found: Option<(usize, usize, &RpType, Option<&RpField>)> = None;
+
+    {
+        let mut candidate = |start: usize, end: usize, ty, field| {
+            if start > offset || offset > end {
+                return;
+            }
+
+            if let Some((s, e, _, _)) = found {
+                if end - start >= e - s {
+                    return;
+                }
+            }
+
+            found = Some((start, end, ty, field));
+        };
+
+        for decl in file.for_each_decl() {
+            let mut fields: Vec<&Loc<RpField>> = Vec::new();
+
+            match ***decl {
+                RpDecl::Type(ref body) => fields.extend(body.fields.iter()),
+                RpDecl::Tuple(ref body) => fields.extend(body.fields.iter()),
+                RpDecl::Interface(ref body) => {
+                    fields.extend(body.fields.iter());
+
+                    for sub_type in body.sub_types.values() {
+                        fields.extend(sub_type.fields.iter());
+                    }
+                }
+                RpDecl::Service(ref body) => {
+                    for endpoint in body.endpoints.values() {
+                        let channels = endpoint.request.iter().chain(endpoint.response.iter());
+
+                        for channel in channels {
+                            let pos = channel.pos();
+                            candidate(pos.start, pos.end, channel.ty(), None);
+                        }
+                    }
+                }
+                RpDecl::Enum(_) => {}
+            }
+
+            for field in fields {
+                let pos = field.pos();
+                candidate(pos.start, pos.end, &field.ty, Some(field.value()));
+            }
+        }
+    }
+
+    let (start, end, ty, field) = found?;
+
+    if let Some((word, range)) = word {
+        let mut names = Vec::new();
+        names_in(ty, &mut names);
+
+        let name = names.into_iter().find(|n| {
+            n.parts.last().map(String::as_str) == Some(word)
+        });
+
+        if let Some(name) = name {
+            return Some(Target::Name(name, range));
+        }
+    }
+
+    field.map(|field| Target::Field(field, (start, end)))
+}
+
+/// Collect all names referenced by the given type.
+fn names_in<'a>(ty: &'a RpType, out: &mut Vec<&'a RpName>) {
+    match *ty {
+        RpType::Name {
+            ref name,
+            ref arguments,
+        } => {
+            out.push(name);
+
+            for argument in arguments {
+                names_in(argument, out);
+            }
+        }
+        RpType::Array { ref inner } => names_in(inner, out),
+        RpType::Map { ref key, ref value } => {
+            names_in(key, out);
+            names_in(value, out);
+        }
+        _ => {}
+    }
+}
+
+/// Get the documentation of a registered declaration.
+fn comment(reg: &RpReg) -> &[String] {
+    match *reg {
+        RpReg::Type(ref body) => &body.comment,
+        RpReg::Tuple(ref body) => &body.comment,
+        RpReg::Interface(ref body) => &body.comment,
+        RpReg::Enum(ref body) => &body.comment,
+        RpReg::Service(ref body) => &body.comment,
+        RpReg::SubType(_, ref sub_type) => &sub_type.comment,
+        RpReg::EnumVariant(_, ref variant) => &variant.comment,
+    }
+}
+
+/// Build markdown describing a signature and its documentation.
+fn describe(signature: &str, comment: &[String]) -> String {
+    let mut out = format!("```reproto\n{}\n```", signature);
+
+    if !comment.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(&comment.join("\n"));
+    }
+
+    out
+}
+
+/// Convert a byte offset into a position.
+fn position(text: &str, offset: usize) -> Position {
+    let mut line = 0;
+    let mut character = 0;
+
+    for (i, c) in text.char_indices() {
+        if i >= offset {
+            break;
+        }
+
+        if c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += c.len_utf16() as u64;
+        }
+    }
+
+    Position {
+        line: line,
+        character: character,
+    }
+}
+
+/// Convert a position into a byte offset.
+fn offset(text: &str, position: Position) -> usize {
+    let mut line = 0;
+    let mut character = 0;
+
+    for (i, c) in text.char_indices() {
+        if line == position.line && character >= position.character {
+            return i;
+        }
+
+        if c == '\n' {
+            if line == position.line {
+                return i;
+            }
+
+            line += 1;
+            character = 0;
+        } else {
+            character += c.len_utf16() as u64;
+        }
+    }
+
+    text.len()
+}
+
+/// Run the language server until the client asks it to exit.
+pub fn run(paths: Vec<PathBuf>) -> Result<()> {
+    let paths = paths
+        .into_iter()
+        .map(|p| fs::canonicalize(&p).unwrap_or(p))
+        .collect();
+
+    let mut server = Server {
+        paths: paths,
+        documents: HashMap::new(),
+    };
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    while let Some(message) = read_message(&mut input)? {
+        let method = match message.method {
+            Some(method) => method,
+            // responses to requests, which are never sent by the server.
+            None => continue,
+        };
+
+        let id = match message.id {
+            Some(id) => id,
+            None => {
+                if method == "exit" {
+                    return Ok(());
+                }
+
+                match server.notification(&method, message.params) {
+                    Ok(Some(params)) => {
+                        write_message(
+                            &mut output,
+                            &Notification {
+                                jsonrpc: "2.0",
+                                method: "textDocument/publishDiagnostics",
+                                params: params,
+                            },
+                        )?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("{}: {}", method, e),
+                }
+
+                continue;
+            }
+        };
+
+        let (result, error) = match server.request(&method, message.params) {
+            Ok(Some(result)) => (Some(result), None),
+            Ok(None) => {
+                let error = ResponseError {
+                    code: METHOD_NOT_FOUND,
+                    message: format!("method not supported: {}", method),
+                };
+
+                (None, Some(error))
+            }
+            Err(e) => {
+                let error = ResponseError {
+                    code: INTERNAL_ERROR,
+                    message: e.to_string(),
+                };
+
+                (None, Some(error))
+            }
+        };
+
+        write_message(
+            &mut output,
+            &Response {
+                jsonrpc: "2.0",
+                id: id,
+                result: result,
+                error: error,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use std::io::Write;
+
+    const COMMON: &str = "/// A shared type.\ntype Shared {\n  value: string;\n}\n";
+    const MAIN: &str = "use common;\n\ntype Entry {\n  shared: common::Shared;\n}\n";
+
+    /// Build a server for a temporary directory, where only `common` exists on disk.
+    fn server() -> (TempDir, Server) {
+        let dir = TempDir::new("reproto-language-server").unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+
+        File::create(root.join("common.reproto"))
+            .unwrap()
+            .write_all(COMMON.as_bytes())
+            .unwrap();
+
+        let server = Server {
+            paths: vec![root],
+            documents: HashMap::new(),
+        };
+
+        (dir, server)
+    }
+
+    fn file_uri(server: &Server, name: &str) -> String {
+        Url::from_file_path(server.paths[0].join(name)).unwrap().to_string()
+    }
+
+    fn open(server: &mut Server, uri: &str, text: &str) -> Vec<Diagnostic> {
+        let params = json!({"textDocument": {"uri": uri, "text": text}});
+        let params = server.notification("textDocument/didOpen", params).unwrap();
+        params.unwrap().diagnostics
+    }
+
+    fn change(server: &mut Server, uri: &str, text: &str) -> Vec<Diagnostic> {
+        let params = json!({"textDocument": {"uri": uri}, "contentChanges": [{"text": text}]});
+        let params = server.notification("textDocument/didChange", params).unwrap();
+        params.unwrap().diagnostics
+    }
+
+    fn request(server: &mut Server, method: &str, uri: &str, line: u64, character: u64) -> Value {
+        let params = json!({
+            "textDocument": {"uri": uri},
+            "position": {"line": line, "character": character},
+        });
+
+        server.request(method, params).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let (_dir, mut server) = server();
+        let uri = file_uri(&server, "main.reproto");
+
+        assert!(open(&mut server, &uri, MAIN).is_empty());
+        assert!(!change(&mut server, &uri, "type Entry {\n  shared: Missing;\n}\n").is_empty());
+        assert!(change(&mut server, &uri, MAIN).is_empty());
+    }
+
+    #[test]
+    fn test_definition() {
+        let (_dir, mut server) = server();
+        let uri = file_uri(&server, "main.reproto");
+        open(&mut server, &uri, MAIN);
+
+        let location = request(&mut server, "textDocument/definition", &uri, 3, 20);
+
+        let expected = json!({
+            "uri": file_uri(&server, "common.reproto"),
+            "range": {
+                "start": {"line": 1, "character": 0},
+                "end": {"line": 3, "character": 1},
+            },
+        });
+
+        assert_eq!(expected, location);
+    }
+
+    #[test]
+    fn test_hover() {
+        let (_dir, mut server) = server();
+        let uri = file_uri(&server, "main.reproto");
+        open(&mut server, &uri, MAIN);
+
+        let hover = request(&mut server, "textDocument/hover", &uri, 3, 20);
+
+        let range = json!({
+            "start": {"line": 3, "character": 18},
+            "end": {"line": 3, "character": 24},
+        });
+
+        assert_eq!(range, hover["range"]);
+
+        let value = hover["contents"]["value"].as_str().unwrap();
+        assert!(value.contains("A shared type."), "{}", value);
+    }
+
+    #[test]
+    fn test_stale_positions() {
+        let (_dir, mut server) = server();
+        let uri = file_uri(&server, "main.reproto");
+        open(&mut server, &uri, MAIN);
+
+        // the check fails, so positions can't be mapped against the last successful one.
+        let broken = format!("type {{\n{}", MAIN);
+        assert!(!change(&mut server, &uri, &broken).is_empty());
+
+        assert_eq!(Value::Null, request(&mut server, "textDocument/hover", &uri, 4, 20));
+        assert_eq!(Value::Null, request(&mut server, "textDocument/definition", &uri, 4, 20));
+    }
+
+    #[test]
+    fn test_completion() {
+        let (_dir, mut server) = server();
+        let uri = file_uri(&server, "main.reproto");
+        open(&mut server, &uri, MAIN);
+
+        let items = request(&mut server, "textDocument/completion", &uri, 3, 10);
+
+        let labels: Vec<&str> = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+
+        for expected in &["string", "common", "Entry", "common::Shared"] {
+            assert!(labels.contains(expected), "{:?}", labels);
+        }
+    }
+
+    /// Text with characters that are one, two and four bytes in UTF-8, where the last one is two
+    /// code units in UTF-16.
+    const TEXT: &str = "a\u{e9}\u{1f600}b\nc";
+
+    fn pos(line: u64, character: u64) -> Position {
+        Position {
+            line: line,
+            character: character,
+        }
+    }
+
+    #[test]
+    fn test_position() {
+        assert_eq!(pos(0, 0), position(TEXT, 0));
+        assert_eq!(pos(0, 2), position(TEXT, 3));
+        assert_eq!(pos(0, 4), position(TEXT, 7));
+        assert_eq!(pos(0, 5), position(TEXT, 8));
+        assert_eq!(pos(1, 0), position(TEXT, 9));
+        assert_eq!(pos(1, 1), position(TEXT, TEXT.len()));
+    }
+
+    #[test]
+    fn test_offset() {
+        assert_eq!(0, offset(TEXT, pos(0, 0)));
+        assert_eq!(3, offset(TEXT, pos(0, 2)));
+        assert_eq!(7, offset(TEXT, pos(0, 4)));
+        assert_eq!(9, offset(TEXT, pos(1, 0)));
+        assert_eq!(TEXT.len(), offset(TEXT, pos(1, 1)));
+    }
+
+    #[test]
+    fn test_offset_out_of_range() {
+        // characters past the end of a line are clamped to the end of that line.
+        assert_eq!(8, offset(TEXT, pos(0, 100)));
+        assert_eq!(TEXT.len(), offset(TEXT, pos(5, 0)));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (i, _) in TEXT.char_indices() {
+            assert_eq!(i, offset(TEXT, position(TEXT, i)));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! The subset of the language server protocol which is used by the language server.
+//!
+//! Messages are JSON-RPC 2.0, framed by a `Content-Length` header.
+//!
+//! See: https://microsoft.github.io/language-server-protocol/specification
+
+use errors::*;
+use serde_json::{self, Value};
+use std::io::{BufRead, Write};
+
+/// Error code for a method which is not supported.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Error code for a request which failed to be handled.
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Diagnostic severity for errors.
+pub const SEVERITY_ERROR: u32 = 1;
+
+/// Completion item kind for classes.
+pub const COMPLETION_CLASS: u32 = 7;
+
+/// Completion item kind for keywords.
+pub const COMPLETION_KEYWORD: u32 = 14;
+
+/// Completion item kind for modules.
+pub const COMPLETION_MODULE: u32 = 9;
+
+/// Synchronize documents by sending their full content on every change.
+pub const SYNC_FULL: u32 = 1;
+
+/// An incoming message, either a request (with an id) or a notification (without one).
+#[derive(Debug, Deserialize)]
+pub struct Message {
+    pub id: Option<Value>,
+    pub method: Option<String>,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ResponseError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Notification<T> {
+    pub jsonrpc: &'static str,
+    pub method: &'static str,
+    pub params: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u64,
+    pub character: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: u32,
+    pub source: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublishDiagnosticsParams {
+    pub uri: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkupContent {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hover {
+    pub contents: MarkupContent,
+    pub range: Range,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentItem {
+    pub uri: String,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidOpenTextDocumentParams {
+    pub text_document: TextDocumentItem,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextDocumentContentChangeEvent {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidChangeTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    pub content_changes: Vec<TextDocumentContentChangeEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidSaveTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+    /// Content of the document when it was saved, if the client is configured to include it.
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidCloseTextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDocumentPositionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+}
+
+/// Read a single message.
+///
+/// Returns `None` when the input has been closed.
+pub fn read_message<R: BufRead>(input: &mut R) -> Result<Option<Message>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_right();
+
+        // an empty line terminates the headers.
+        if line.is_empty() {
+            break;
+        }
+
+        let mut parts = line.splitn(2, ':');
+
+        if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+            if name.trim().to_lowercase() == "content-length" {
+                let value = value.trim();
+
+                content_length = Some(value.parse::<usize>().map_err(|_| {
+                    format!("bad Content-Length: {}", value)
+                })?);
+            }
+        }
+    }
+
+    let content_length = content_length.ok_or_else(|| "missing Content-Length header")?;
+
+    let mut content = vec![0u8; content_length];
+    input.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Write a single message.
+pub fn write_message<W: Write, T: ::serde::Serialize>(output: &mut W, message: &T) -> Result<()> {
+    let content = serde_json::to_vec(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n", content.len())?;
+    output.write_all(&content)?;
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read(input: &str) -> Result<Option<Message>> {
+        read_message(&mut Cursor::new(input.as_bytes()))
+    }
+
+    #[test]
+    fn test_read_message() {
+        let message = read("Content-Length: 16\r\n\r\n{\"method\":\"foo\"}")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(Some("foo".to_string()), message.method);
+        assert_eq!(None, message.id);
+    }
+
+    #[test]
+    fn test_read_message_header_case() {
+        let input = "content-length: 2\r\nContent-Type: utf-8\r\n\r\n{}";
+        assert!(read(input).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_read_message_eof() {
+        assert!(read("").unwrap().is_none());
+        assert!(read("Content-Length: 2\r\n").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_message_missing_content_length() {
+        assert!(read("Content-Type: utf-8\r\n\r\n{}").is_err());
+    }
+
+    #[test]
+    fn test_read_message_bad_content_length() {
+        assert!(read("Content-Length: nope\r\n\r\n{}").is_err());
+    }
+
+    #[test]
+    fn test_read_message_truncated() {
+        assert!(read("Content-Length: 10\r\n\r\n{}").is_err());
+    }
+
+    #[test]
+    fn test_write_read_message() {
+        let mut out = Vec::new();
+
+        write_message(
+            &mut out,
+            &Notification {
+                jsonrpc: "2.0",
+                method: "foo",
+                params: (),
+            },
+        ).unwrap();
+
+        let message = read_message(&mut Cursor::new(out)).unwrap().unwrap();
+        assert_eq!(Some("foo".to_string()), message.method);
+    }
+}
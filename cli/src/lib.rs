@@ -9,14 +9,19 @@ extern crate log;
 
 extern crate ansi_term;
 extern crate clap;
+extern crate reproto_ast as ast;
 extern crate reproto_backend as backend;
 extern crate reproto_backend_doc as doc;
+extern crate reproto_backend_go as go;
 extern crate reproto_backend_java as java;
 extern crate reproto_backend_js as js;
 extern crate reproto_backend_json as json;
+extern crate reproto_backend_proto as proto;
 extern crate reproto_backend_python as python;
 extern crate reproto_backend_rust as rust;
+extern crate reproto_backend_typescript as typescript;
 extern crate reproto_core as core;
+extern crate reproto_lexer as lexer;
 extern crate reproto_semck as semck;
 extern crate reproto_manifest as manifest;
 extern crate reproto_parser as parser;
@@ -24,8 +29,13 @@ extern crate reproto_repository as repository;
 extern crate toml;
 extern crate url;
 extern crate relative_path;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
 
 pub mod ops;
 pub mod config;
 pub mod errors;
+pub mod format;
+pub mod language_server;
 pub mod output;
@@ -46,7 +46,7 @@ fn setup_logger(matches: &clap::ArgMatches, output: &output::Output) -> Result<(
 
 fn entry(matches: ArgMatches, output: &output::Output) -> Result<()> {
     setup_logger(&matches, output)?;
-    ops::entry(&matches)?;
+    ops::entry(&matches, output)?;
     Ok(())
 }
 
@@ -70,7 +70,10 @@ fn main() {
     let colored = matches.is_present("color") ||
         !matches.is_present("no-color") && atty::is(atty::Stream::Stdout);
 
-    let mut output: Box<output::Output> = if colored {
+    // the language server communicates over stdout, so all other output goes to stderr.
+    let mut output: Box<output::Output> = if matches.subcommand_name() == Some("language-server") {
+        Box::new(output::NonColored::new(io::stderr()))
+    } else if colored {
         Box::new(output::Colored::new(io::stdout()))
     } else {
         Box::new(output::NonColored::new(io::stdout()))
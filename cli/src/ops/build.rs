@@ -13,7 +13,7 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
     out
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
     use manifest::Language::*;
 
     let preamble = manifest_preamble(matches)?;
@@ -28,11 +28,30 @@ pub fn entry(matches: &ArgMatches) -> Result<()> {
         })?;
 
     match language {
-        Java => manifest_compile::<::java::JavaLang, _>(matches, preamble, ::java::compile),
-        Js => manifest_compile::<::js::JsLang, _>(matches, preamble, ::js::compile),
-        Json => manifest_compile::<::json::JsonLang, _>(matches, preamble, ::json::compile),
-        Python => manifest_compile::<::python::PythonLang, _>(matches, preamble, ::python::compile),
-        Rust => manifest_compile::<::rust::RustLang, _>(matches, preamble, ::rust::compile),
+        Go => manifest_compile::<::go::GoLang, _>(matches, preamble, output, ::go::compile),
+        Java => manifest_compile::<::java::JavaLang, _>(matches, preamble, output, ::java::compile),
+        Js => manifest_compile::<::js::JsLang, _>(matches, preamble, output, ::js::compile),
+        Json => manifest_compile::<::json::JsonLang, _>(matches, preamble, output, ::json::compile),
+        Proto => {
+            manifest_compile::<::proto::ProtoLang, _>(matches, preamble, output, ::proto::compile)
+        }
+        Python => {
+            manifest_compile::<::python::PythonLang, _>(
+                matches,
+                preamble,
+                output,
+                ::python::compile,
+            )
+        }
+        Rust => manifest_compile::<::rust::RustLang, _>(matches, preamble, output, ::rust::compile),
+        TypeScript => {
+            manifest_compile::<::typescript::TsLang, _>(
+                matches,
+                preamble,
+                output,
+                ::typescript::compile,
+            )
+        }
     }?;
 
     Ok(())
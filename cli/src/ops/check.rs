@@ -18,15 +18,15 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
     out
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
     let preamble = manifest_preamble(matches)?;
-    return do_manifest_use!(matches, preamble, inner);
+    return do_manifest_use!(matches, preamble, output, inner);
 
-    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>, output: &Output) -> Result<()>
     where
         L: Lang,
     {
-        let mut env = setup_environment(&manifest)?;
+        let mut env = setup_environment(&manifest, output)?;
 
         let mut manifest_resolver = setup_path_resolver(&manifest)?.ok_or_else(|| {
             "could not setup manifest resolver"
@@ -11,6 +11,9 @@ pub struct ConfigEnv {
     pub cache_dir: PathBuf,
     pub index: Option<String>,
     pub objects: Option<String>,
+    pub token: Option<String>,
+    /// Path to the key used to sign published deployments.
+    pub signing_key: PathBuf,
 }
 
 impl ConfigEnv {
@@ -23,6 +26,8 @@ impl ConfigEnv {
             let mut cache_dir = reproto_dir.join("cache");
             let mut index = None;
             let mut objects = None;
+            let mut token = None;
+            let mut signing_key = reproto_dir.join("signing.key");
 
             if config.is_file() {
                 let config = read_config(&config)?;
@@ -31,6 +36,11 @@ impl ConfigEnv {
                     // set values from configuration (if not already set).
                     index = index.or(repository.index);
                     objects = objects.or(repository.objects);
+                    token = token.or(repository.token);
+
+                    if let Some(out) = repository.signing_key {
+                        signing_key = out;
+                    }
                 }
 
                 if let Some(out) = config.cache_dir {
@@ -48,6 +58,8 @@ impl ConfigEnv {
                 cache_dir: cache_dir,
                 index: index,
                 objects: objects,
+                token: token,
+                signing_key: signing_key,
             }));
         }
 
@@ -2,25 +2,81 @@
 
 use super::{setup_compiler_options, setup_environment};
 use super::imports::*;
+use core::{RpPackage, RpVersionedPackage, VersionReq};
+use std::collections::BTreeSet;
 use manifest::{Lang, Manifest};
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     ::doc::shared_options(SubCommand::with_name("doc").about("Generate documentation"))
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
     let preamble = manifest_preamble(matches)?;
 
-    return do_manifest_use!(matches, preamble, inner);
+    return do_manifest_use!(matches, preamble, output, inner);
 
-    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>, output: &Output) -> Result<()>
     where
         L: Lang,
     {
-        let env = setup_environment(&manifest)?;
+        ::doc::check_options(matches)?;
+
+        let mut env = setup_environment(&manifest, output)?;
+
+        if matches.is_present("changelog") {
+            import_history(&manifest, &mut env)?;
+        }
+
         let options = setup_options(&manifest)?;
         let compiler_options = setup_compiler_options(&manifest, matches)?;
 
         ::doc::compile(env, options, compiler_options, matches, manifest).map_err(Into::into)
     }
 }
+
+/// Import all published versions of the packages in the manifest, to build their changelogs.
+///
+/// Versions which are already part of the environment are left as they are, and versions which
+/// can't be imported, like unsigned ones when signatures are required, are left out with a
+/// warning.
+fn import_history<L>(manifest: &Manifest<L>, env: &mut Environment) -> Result<()>
+where
+    L: Lang,
+{
+    let repository = setup_repository(manifest)?;
+
+    let mut packages: Vec<RpPackage> = manifest
+        .packages
+        .iter()
+        .map(|required| required.package.clone())
+        .collect();
+
+    packages.extend(manifest.files.iter().filter_map(|f| f.package.clone()));
+    packages.sort();
+    packages.dedup();
+
+    let loaded: BTreeSet<RpVersionedPackage> =
+        env.for_each_file().map(|(package, _)| package.clone()).collect();
+
+    for package in packages {
+        for deployment in repository.all(&package)? {
+            let versioned =
+                RpVersionedPackage::new(package.clone(), Some(deployment.version.clone()));
+
+            if loaded.contains(&versioned) {
+                continue;
+            }
+
+            let required =
+                RpRequiredPackage::new(package.clone(), VersionReq::exact(&deployment.version));
+
+            debug!("history: {}", required);
+
+            if let Err(e) = env.import(&required) {
+                warn!("{}: left out of changelog: {}", versioned, e);
+            }
+        }
+    }
+
+    Ok(())
+}
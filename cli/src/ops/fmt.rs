@@ -0,0 +1,158 @@
+//! Format action that rewrites specifications in their canonical form.
+
+use core::{Object, PathObject};
+use format;
+use manifest::{Lang, Manifest};
+use ops::imports::*;
+use std::collections::LinkedList;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+const EXT: &str = "reproto";
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("fmt").about("Format specifications");
+
+    let out = out.arg(Arg::with_name("check").long("check").help(
+        "Don't write any files, fail if any file is not formatted",
+    ));
+
+    let out = out.arg(Arg::with_name("file").multiple(true).help(
+        "Files to format, defaults to all files in the configured paths",
+    ));
+
+    out
+}
+
+/// Find all specifications in the given directory, recursively.
+fn find_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut queue = LinkedList::new();
+    queue.push_back(path.to_owned());
+
+    while let Some(path) = queue.pop_front() {
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                queue.push_back(path);
+                continue;
+            }
+
+            if path.is_file() && path.extension().map(|e| e == EXT).unwrap_or(false) {
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Format the given files in place.
+///
+/// If `check` is set, no files are written and an error is raised if any file is not formatted.
+fn format_files(files: &[PathBuf], check: bool) -> Result<()> {
+    let mut unformatted = 0usize;
+
+    for path in files {
+        let mut content = String::new();
+        File::open(path)?.read_to_string(&mut content)?;
+
+        let object: Box<Object> = Box::new(PathObject::new(None, path));
+        let formatted = format::format(Rc::new(object), &content)?;
+
+        if formatted == content {
+            continue;
+        }
+
+        if check {
+            warn!("not formatted: {}", path.display());
+            unformatted += 1;
+            continue;
+        }
+
+        info!("formatting: {}", path.display());
+        File::create(path)?.write_all(formatted.as_bytes())?;
+    }
+
+    if unformatted > 0 {
+        return Err(format!("{} file(s) are not formatted", unformatted).into());
+    }
+
+    Ok(())
+}
+
+pub fn entry(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let check = matches.is_present("check");
+
+        let mut files: Vec<PathBuf> = manifest.files.iter().map(|f| f.path.clone()).collect();
+
+        if files.is_empty() {
+            for path in &manifest.paths {
+                if path.is_dir() {
+                    find_files(path, &mut files)?;
+                }
+            }
+        }
+
+        files.sort();
+        files.dedup();
+
+        format_files(&files, check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+
+    const FORMATTED: &str = "type Foo {\n  a: string;\n}\n";
+
+    fn write(path: &Path, content: &str) {
+        File::create(path).unwrap().write_all(content.as_bytes()).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        let mut content = String::new();
+        File::open(path).unwrap().read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = TempDir::new("reproto-fmt").unwrap();
+        let good = dir.path().join("good.reproto");
+        let bad = dir.path().join("bad.reproto");
+
+        write(&good, FORMATTED);
+        write(&bad, "type   Foo{a:string;}");
+
+        assert!(format_files(&[good.clone()], true).is_ok());
+        assert!(format_files(&[good.clone(), bad.clone()], true).is_err());
+        // checking never modifies files.
+        assert_eq!("type   Foo{a:string;}", read(&bad));
+    }
+
+    #[test]
+    fn test_format_in_place() {
+        let dir = TempDir::new("reproto-fmt").unwrap();
+        let bad = dir.path().join("bad.reproto");
+
+        write(&bad, "type   Foo{a:string;}");
+
+        format_files(&[bad.clone()], false).unwrap();
+        assert_eq!(FORMATTED, read(&bad));
+        assert!(format_files(&[bad.clone()], true).is_ok());
+    }
+}
@@ -4,3 +4,4 @@ pub(crate) use backend::{CompilerOptions, Environment, Options};
 pub(crate) use clap::{App, Arg, ArgMatches, SubCommand};
 pub(crate) use core::RpRequiredPackage;
 pub(crate) use errors::*;
+pub(crate) use output::Output;
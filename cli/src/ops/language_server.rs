@@ -0,0 +1,23 @@
+//! Action that runs a language server over stdio.
+
+use language_server;
+use manifest::{Lang, Manifest};
+use ops::imports::*;
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("language-server").about(
+        "Run a language server, communicating over stdio",
+    )
+}
+
+pub fn entry(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(_matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        language_server::run(manifest.paths.clone())
+    }
+}
@@ -6,27 +6,37 @@
 ///
 /// If no language is specified, the special NoLang target will be used which prevents illegal
 /// modules from being specified.
+///
+/// If an output is given, it is passed on to the function after the manifest.
 macro_rules! do_manifest_use {
-    ($matches:expr, $preamble:expr, $fn:expr) => {{
+    (@lang $preamble:expr, $use:ident($($arg:expr),*)) => {{
         let language = $preamble.language.as_ref().cloned();
 
         match language {
-            Some(::manifest::Language::Java) => {
-                ::ops::manifest_use::<::java::JavaLang, _>($matches, $preamble, $fn)
-            }
-            Some(::manifest::Language::Js) => {
-                ::ops::manifest_use::<::js::JsLang, _>($matches, $preamble, $fn)
-            }
-            Some(::manifest::Language::Json) => {
-                ::ops::manifest_use::<::json::JsonLang, _>($matches, $preamble, $fn)
-            }
+            Some(::manifest::Language::Go) => ::ops::$use::<::go::GoLang, _>($($arg),*),
+            Some(::manifest::Language::Java) => ::ops::$use::<::java::JavaLang, _>($($arg),*),
+            Some(::manifest::Language::Js) => ::ops::$use::<::js::JsLang, _>($($arg),*),
+            Some(::manifest::Language::Json) => ::ops::$use::<::json::JsonLang, _>($($arg),*),
+            Some(::manifest::Language::Proto) => ::ops::$use::<::proto::ProtoLang, _>($($arg),*),
             Some(::manifest::Language::Python) => {
-                ::ops::manifest_use::<::python::PythonLang, _>($matches, $preamble, $fn)
+                ::ops::$use::<::python::PythonLang, _>($($arg),*)
             }
-            Some(::manifest::Language::Rust) => {
-                ::ops::manifest_use::<::rust::RustLang, _>($matches, $preamble, $fn)
+            Some(::manifest::Language::Rust) => ::ops::$use::<::rust::RustLang, _>($($arg),*),
+            Some(::manifest::Language::TypeScript) => {
+                ::ops::$use::<::typescript::TsLang, _>($($arg),*)
             }
-            None => ::ops::manifest_use::<::manifest::NoLang, _>($matches, $preamble, $fn),
+            None => ::ops::$use::<::manifest::NoLang, _>($($arg),*),
         }
     }};
+
+    ($matches:expr, $preamble:expr, $output:expr, $fn:expr) => {
+        do_manifest_use!(
+            @lang $preamble,
+            manifest_use_with_output($matches, $preamble, $output, $fn)
+        )
+    };
+
+    ($matches:expr, $preamble:expr, $fn:expr) => {
+        do_manifest_use!(@lang $preamble, manifest_use($matches, $preamble, $fn))
+    };
 }
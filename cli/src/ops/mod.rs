@@ -3,10 +3,13 @@ mod macros;
 mod build;
 mod doc;
 mod config_env;
+mod fmt;
 mod imports;
+mod language_server;
 mod publish;
 mod repo;
 mod update;
+mod vendor;
 mod check;
 
 use self::config_env::ConfigEnv;
@@ -17,19 +20,23 @@ use core::{Object, RpPackage, RpPackageFormat, RpVersionedPackage, Version};
 use manifest::{Lang, Manifest, ManifestFile, ManifestPreamble, Publish, TryFromToml,
                read_manifest, read_manifest_preamble, self as m};
 use relative_path::RelativePath;
-use repository::{Index, IndexConfig, NoIndex, NoObjects, Objects, ObjectsConfig, Paths,
-                 Repository, Resolved, ResolvedByPrefix, Resolver, Resolvers, index_from_path,
-                 index_from_url, objects_from_path, objects_from_url};
+use repository::{Index, IndexConfig, Lock, NoIndex, NoObjects, Objects, ObjectsConfig, Paths,
+                 Repository, Resolved, ResolvedByPrefix, Resolver, Resolvers, SigningKey,
+                 index_from_path, index_from_url, objects_from_path, objects_from_url,
+                 parse_public_key, read_key, read_lock};
 use semck;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::time::Duration;
 use toml;
 use url;
 
 pub const DEFAULT_INDEX: &'static str = "git+https://github.com/reproto/reproto-index";
 pub const MANIFEST_NAME: &'static str = "reproto.toml";
+pub const LOCK_NAME: &'static str = "reproto.lock";
 
 fn parse_id_converter(input: &str) -> Result<Box<Naming>> {
     let mut parts = input.split(":");
@@ -276,6 +283,7 @@ where
 
     let mut repo_dir = None;
     let mut cache_dir = None;
+    let mut token = None;
     let mut index = repository.index.clone();
     let mut objects = repository.objects.clone();
 
@@ -284,12 +292,16 @@ where
         cache_dir = Some(config_env.cache_dir);
         index = index.or(config_env.index.clone());
         objects = objects.or(config_env.objects.clone());
+        token = config_env.token.clone();
     }
 
     let repo_dir = repo_dir.ok_or_else(|| "repo_dir: must be specified")?;
 
     let index_url = index.unwrap_or_else(|| DEFAULT_INDEX.to_owned());
-    let index_config = IndexConfig { repo_dir: repo_dir.clone() };
+    let index_config = IndexConfig {
+        repo_dir: repo_dir.clone(),
+        token: token.clone(),
+    };
 
     let index = load_index(base, index_url.as_str(), index_config)?;
 
@@ -297,10 +309,44 @@ where
         repo_dir: repo_dir,
         cache_dir: cache_dir,
         missing_cache_time: Some(Duration::new(60, 0)),
+        token: token,
     };
 
     let objects = load_objects(index.as_ref(), index_url.as_str(), objects, objects_config)?;
-    Ok(Repository::new(index, objects))
+    let mut out = Repository::new(index, objects);
+
+    if repository.require_signatures {
+        if repository.trusted_keys.is_empty() {
+            return Err("require_signatures: no trusted_keys are configured".into());
+        }
+
+        for key in &repository.trusted_keys {
+            parse_public_key(key).map_err(|e| format!("trusted_keys: {}: {}", key, e))?;
+        }
+
+        out = out.with_required_signatures(repository.trusted_keys.clone());
+    }
+
+    Ok(out)
+}
+
+/// Path to the key used to sign published deployments.
+pub fn signing_key_path() -> Result<Option<PathBuf>> {
+    Ok(ConfigEnv::new()?.map(|config_env| config_env.signing_key))
+}
+
+/// Load the key used to sign published deployments, if one has been generated.
+pub fn setup_signing_key() -> Result<Option<SigningKey>> {
+    let path = match signing_key_path()? {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    Ok(Some(read_key(&path)?))
 }
 
 pub fn setup_path_resolver<L>(manifest: &Manifest<L>) -> Result<Option<Box<Resolver>>>
@@ -322,13 +368,25 @@ where
     ))
 }
 
-pub fn setup_resolvers<L>(manifest: &Manifest<L>) -> Result<Box<Resolver>>
+/// Setup all resolvers.
+///
+/// If a lock is provided, packages from the repository are resolved through it.
+pub fn setup_resolvers<L>(
+    manifest: &Manifest<L>,
+    lock: Option<Rc<RefCell<Lock>>>,
+) -> Result<Box<Resolver>>
 where
     L: Lang,
 {
     let mut resolvers: Vec<Box<Resolver>> = Vec::new();
 
-    resolvers.push(Box::new(setup_repository(manifest)?));
+    let mut repository = setup_repository(manifest)?;
+
+    if let Some(lock) = lock {
+        repository = repository.with_lock(lock);
+    }
+
+    resolvers.push(Box::new(repository));
 
     if let Some(resolver) = setup_path_resolver(manifest)? {
         resolvers.push(resolver);
@@ -386,12 +444,72 @@ where
     Ok(manifest)
 }
 
+/// Path to the lockfile of the manifest.
+///
+/// Projects without a manifest file have no lockfile.
+pub fn lock_path<L>(manifest: &Manifest<L>) -> Option<PathBuf>
+where
+    L: Lang,
+{
+    if !manifest.path.is_file() {
+        return None;
+    }
+
+    manifest.path.parent().map(|parent| parent.join(LOCK_NAME))
+}
+
 /// Setup environment.
-pub fn setup_environment<L>(manifest: &Manifest<L>) -> Result<Environment>
+///
+/// Packages are resolved through the lockfile, which is updated if anything new was resolved.
+pub fn setup_environment<L>(manifest: &Manifest<L>, output: &Output) -> Result<Environment>
+where
+    L: Lang,
+{
+    let path = lock_path(manifest);
+
+    let lock = match path {
+        Some(ref path) => Some(Rc::new(RefCell::new(read_lock(path)?))),
+        None => None,
+    };
+
+    let env = setup_locked_environment(manifest, lock.clone(), output)?;
+
+    if let (Some(path), Some(lock)) = (path, lock) {
+        let lock = lock.borrow();
+
+        if lock.is_changed() {
+            debug!("writing: {}", path.display());
+            lock.write(&path)?;
+        }
+    }
+
+    Ok(env)
+}
+
+/// Setup environment, resolving packages through the given lock.
+pub fn setup_locked_environment<L>(
+    manifest: &Manifest<L>,
+    lock: Option<Rc<RefCell<Lock>>>,
+    output: &Output,
+) -> Result<Environment>
+where
+    L: Lang,
+{
+    let resolvers = setup_resolvers(manifest, lock)?;
+    setup_environment_with(manifest, resolvers, output)
+}
+
+/// Setup environment, resolving packages through the given resolver.
+///
+/// Imported packages which have been deprecated or yanked are reported as warnings to `output`.
+pub fn setup_environment_with<L>(
+    manifest: &Manifest<L>,
+    resolvers: Box<Resolver>,
+    output: &Output,
+) -> Result<Environment>
 where
     L: Lang,
 {
-    let resolvers = setup_resolvers(manifest)?;
     let package_prefix = manifest.package_prefix.clone();
 
     let mut env = Environment::new(package_prefix, resolvers);
@@ -417,6 +535,10 @@ where
         }
     }
 
+    for &(ref package, ref message) in env.deprecations() {
+        output.print_warning(&format!("{}: {}", package, message))?;
+    }
+
     if let Err(e) = env.verify() {
         errors.push(e.into());
     }
@@ -432,8 +554,11 @@ pub fn options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
     let out = out.subcommand(build_args(build::options()));
     let out = out.subcommand(build_args(doc::options()));
     let out = out.subcommand(base_args(check::options()));
+    let out = out.subcommand(base_args(fmt::options()));
+    let out = out.subcommand(base_args(language_server::options()));
     let out = out.subcommand(base_args(publish::options()));
     let out = out.subcommand(base_args(update::options()));
+    let out = out.subcommand(base_args(vendor::options()));
     let out = out.subcommand(base_args(repo::options()));
     out
 }
@@ -575,7 +700,7 @@ where
             return Err("more than one matching package found".into());
         }
 
-        let Resolved { version, object } = first;
+        let Resolved { version, object, .. } = first;
         let version = version_override.cloned().or(version);
 
         let version = version.ok_or_else(|| {
@@ -634,6 +759,7 @@ pub fn semck_check(
 pub fn manifest_compile<'a, L, F>(
     matches: &'a ArgMatches,
     preamble: ManifestPreamble,
+    output: &Output,
     compile: F,
 ) -> Result<()>
 where
@@ -646,7 +772,7 @@ where
            -> backend::errors::Result<()>,
 {
     let manifest = manifest::<L>(matches, preamble)?;
-    let env = setup_environment(&manifest)?;
+    let env = setup_environment(&manifest, output)?;
     let options = setup_options(&manifest)?;
     let compiler_options = setup_compiler_options(&manifest, matches)?;
 
@@ -673,16 +799,35 @@ where
     Ok(())
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
+/// High-level helper function like `manifest_use`, which also passes on the given output.
+pub fn manifest_use_with_output<'a, 'o, L, F>(
+    matches: &'a ArgMatches,
+    preamble: ManifestPreamble,
+    output: &'o Output,
+    use_f: F,
+) -> Result<()>
+where
+    L: Lang,
+    F: FnOnce(&'a ArgMatches, Manifest<L>, &'o Output) -> Result<()>,
+{
+    let manifest = manifest::<L>(matches, preamble)?;
+    use_f(matches, manifest, output)?;
+    Ok(())
+}
+
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
     let (name, matches) = matches.subcommand();
     let matches = matches.ok_or_else(|| "no subcommand")?;
 
     match name {
-        "build" => return build::entry(matches),
-        "check" => return check::entry(matches),
-        "doc" => return doc::entry(matches),
-        "update" => return update::entry(matches),
-        "publish" => return publish::entry(matches),
+        "build" => return build::entry(matches, output),
+        "check" => return check::entry(matches, output),
+        "doc" => return doc::entry(matches, output),
+        "fmt" => return fmt::entry(matches),
+        "language-server" => return language_server::entry(matches),
+        "update" => return update::entry(matches, output),
+        "vendor" => return vendor::entry(matches, output),
+        "publish" => return publish::entry(matches, output),
         "repo" => return repo::entry(matches),
         _ => {}
     }
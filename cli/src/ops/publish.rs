@@ -1,6 +1,6 @@
 use super::imports::*;
-use super::setup_environment;
-use core::Version;
+use super::{setup_environment, setup_signing_key};
+use core::{RpPackage, Version};
 use manifest::{Lang, Manifest};
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
@@ -29,20 +29,69 @@ pub fn options<'a, 'b>() -> App<'a, 'b> {
             .help("Override published version with argument"),
     );
 
+    let out = out.arg(
+        Arg::with_name("yank")
+            .long("yank")
+            .takes_value(true)
+            .value_name("package@version")
+            .help("Yank a published version, instead of publishing"),
+    );
+
+    let out = out.arg(
+        Arg::with_name("message")
+            .long("message")
+            .takes_value(true)
+            .requires("yank")
+            .help("Deprecation message to warn about when a yanked version is used"),
+    );
+
     let out = out.arg(Arg::with_name("package").multiple(true));
 
     out
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
+/// Parse a `<package>@<version>` argument.
+fn parse_exact(input: &str) -> Result<(RpPackage, Version)> {
+    let mut parts = input.splitn(2, '@');
+
+    let package = parts.next().map(RpPackage::parse);
+    let version = parts.next();
+
+    let (package, version) = match (package, version) {
+        (Some(package), Some(version)) => (package, version),
+        _ => return Err(format!("expected <package>@<version>, but got: {}", input).into()),
+    };
+
+    let version = Version::parse(version).map_err(|e| {
+        format!("not a valid version: {}: {}", version, e)
+    })?;
+
+    Ok((package, version))
+}
+
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
     let preamble = manifest_preamble(matches)?;
-    return do_manifest_use!(matches, preamble, inner);
+    return do_manifest_use!(matches, preamble, output, inner);
 
-    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>, output: &Output) -> Result<()>
     where
         L: Lang,
     {
-        let mut env = setup_environment(&manifest)?;
+        if let Some(yank) = matches.value_of("yank") {
+            let (package, version) = parse_exact(yank)?;
+            let repository = setup_repository(&manifest)?;
+
+            if matches.is_present("pretend") {
+                info!("(pretend) yanking: {}@{}", package, version);
+            } else {
+                info!("yanking: {}@{}", package, version);
+                repository.yank(&package, &version, matches.value_of("message"))?;
+            }
+
+            return Ok(());
+        }
+
+        let mut env = setup_environment(&manifest, output)?;
 
         let mut manifest_resolver = setup_path_resolver(&manifest)?.ok_or_else(|| {
             "could not setup manifest resolver"
@@ -84,6 +133,11 @@ pub fn entry(matches: &ArgMatches) -> Result<()> {
 
         let mut repository = setup_repository(&manifest)?;
 
+        if let Some(signing_key) = setup_signing_key()? {
+            info!("signing with key: {}", signing_key.public_key());
+            repository = repository.with_signing_key(signing_key);
+        }
+
         // errors that would prevent publishing
         let mut semck_errors = Vec::new();
 
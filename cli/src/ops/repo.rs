@@ -1,5 +1,11 @@
+use super::{base_args, setup_resolvers, setup_signing_key, signing_key_path};
 use super::imports::*;
-use repository::init_file_index;
+use core::{ErrorPos, Object, RpPackage, RpPackageFormat, RpVersionedPackage, Version};
+use manifest::{Lang, Manifest};
+use repository::{Repository, generate_key, init_file_index};
+use semck;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
 
 fn init(matches: &ArgMatches) -> Result<()> {
     for path in matches.values_of("path").into_iter().flat_map(|it| it) {
@@ -21,9 +27,403 @@ fn init_options<'a, 'b>() -> App<'a, 'b> {
     out
 }
 
+fn list_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("list").about("List packages in the repository");
+
+    let out = out.arg(Arg::with_name("prefix").help(
+        "Only list packages with the given prefix",
+    ));
+
+    out
+}
+
+fn versions_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("versions").about("List published versions of a package");
+
+    let out = out.arg(Arg::with_name("package").required(true).help(
+        "Package to list versions for",
+    ));
+
+    out
+}
+
+fn show_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("show").about(
+        "Show the specification of a published version",
+    );
+
+    let out = out.arg(Arg::with_name("package").required(true).help(
+        "Package to show",
+    ));
+
+    let out = out.arg(Arg::with_name("version").required(true).help(
+        "Version to show",
+    ));
+
+    out
+}
+
+fn diff_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("diff").about(
+        "Summarize changes between two published versions",
+    );
+
+    let out = out.arg(Arg::with_name("package").required(true).help(
+        "Package to compare",
+    ));
+
+    let out = out.arg(Arg::with_name("from").required(true).help(
+        "Version to compare from",
+    ));
+
+    let out = out.arg(Arg::with_name("to").required(true).help(
+        "Version to compare to",
+    ));
+
+    out
+}
+
+fn fsck_options<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("fsck").about(
+        "Verify the integrity of objects, and that every deployment refers to an existing object",
+    )
+}
+
+fn gc_options<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("gc").about(
+        "Remove objects which are not referred to by any deployment, and prune caches",
+    )
+}
+
+fn key_options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("key").about("Manage keys used to sign deployments");
+
+    let generate = SubCommand::with_name("generate").about("Generate a new signing key");
+
+    let generate = generate.arg(
+        Arg::with_name("out")
+            .long("out")
+            .short("o")
+            .takes_value(true)
+            .help("Where to write the key, defaults to ~/.reproto/signing.key"),
+    );
+
+    let show = SubCommand::with_name("show").about("Show the public key of the signing key");
+
+    let list = SubCommand::with_name("list").about("List keys trusted by the manifest");
+
+    let out = out.subcommand(generate);
+    let out = out.subcommand(show);
+    let out = out.subcommand(base_args(list));
+    out
+}
+
+fn package_arg(matches: &ArgMatches) -> Result<RpPackage> {
+    let package = matches.value_of("package").ok_or_else(
+        || "package: missing argument",
+    )?;
+
+    Ok(RpPackage::parse(package))
+}
+
+fn version_arg(matches: &ArgMatches, name: &str) -> Result<Version> {
+    let version = matches.value_of(name).ok_or_else(
+        || format!("{}: missing argument", name),
+    )?;
+
+    Version::parse(version).map_err(|e| {
+        format!("{}: bad version: {}: {}", name, version, e).into()
+    })
+}
+
+/// Find the object of a published version.
+fn find_object(
+    repository: &mut Repository,
+    package: &RpPackage,
+    version: &Version,
+) -> Result<Box<Object>> {
+    let deployment = repository
+        .get_deployments(package, version)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            format!("{}: version not published", RpPackageFormat(package, Some(version)))
+        })?;
+
+    let object = repository.get_object(&deployment)?.ok_or_else(|| {
+        format!("No object found for deployment: {:?}", deployment)
+    })?;
+
+    Ok(object.with_name(
+        RpPackageFormat(package, Some(version)).to_string(),
+    ))
+}
+
+fn list(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let repository = setup_repository(&manifest)?;
+        let prefix = matches.value_of("prefix").map(RpPackage::parse);
+
+        for package in repository.packages()? {
+            if let Some(ref prefix) = prefix {
+                if !package.parts.starts_with(&prefix.parts) {
+                    continue;
+                }
+            }
+
+            println!("{}", package);
+        }
+
+        Ok(())
+    }
+}
+
+fn versions(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let repository = setup_repository(&manifest)?;
+        let package = package_arg(matches)?;
+
+        let deployments = repository.all(&package)?;
+
+        if deployments.is_empty() {
+            return Err(format!("{}: no versions published", package).into());
+        }
+
+        for deployment in deployments {
+            if deployment.yanked {
+                println!("{} {} (yanked)", deployment.version, deployment.object);
+            } else {
+                println!("{} {}", deployment.version, deployment.object);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn show(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let mut repository = setup_repository(&manifest)?;
+        let package = package_arg(matches)?;
+        let version = version_arg(matches, "version")?;
+
+        let object = find_object(&mut repository, &package, &version)?;
+
+        let mut content = Vec::new();
+        object.read()?.read_to_end(&mut content)?;
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(&content)?;
+        Ok(())
+    }
+}
+
+fn fsck(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(_: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let mut repository = setup_repository(&manifest)?;
+        let fsck = repository.fsck()?;
+
+        for &(ref expected, ref actual) in &fsck.corrupt {
+            println!("corrupt object: {} (actual checksum: {})", expected, actual);
+        }
+
+        for &(ref package, ref deployment) in &fsck.dangling {
+            println!(
+                "dangling deployment: {}: missing object {}",
+                RpPackageFormat(package, Some(&deployment.version)),
+                deployment.object
+            );
+        }
+
+        if !fsck.is_ok() {
+            return Err(
+                format!(
+                    "{} corrupt object(s), {} dangling deployment(s)",
+                    fsck.corrupt.len(),
+                    fsck.dangling.len()
+                ).into(),
+            );
+        }
+
+        info!("no problems found");
+        Ok(())
+    }
+}
+
+fn gc(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(_: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let mut repository = setup_repository(&manifest)?;
+
+        let removed = repository.gc()?;
+
+        for checksum in &removed {
+            debug!("removed: {}", checksum);
+        }
+
+        info!("removed {} object(s)", removed.len());
+        Ok(())
+    }
+}
+
+fn key_generate(matches: &ArgMatches) -> Result<()> {
+    let path = match matches.value_of("out") {
+        Some(out) => PathBuf::from(out),
+        None => signing_key_path()?.ok_or_else(|| "no home directory to store key in")?,
+    };
+
+    let public_key = generate_key(&path)?;
+
+    info!("Wrote signing key to: {}", path.display());
+    println!("{}", public_key);
+    Ok(())
+}
+
+fn key_show() -> Result<()> {
+    let signing_key = setup_signing_key()?.ok_or_else(
+        || "no signing key, generate one with `reproto repo key generate`",
+    )?;
+
+    println!("{}", signing_key.public_key());
+    Ok(())
+}
+
+fn key_list(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(_: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        for key in &manifest.repository.trusted_keys {
+            println!("{}", key);
+        }
+
+        Ok(())
+    }
+}
+
+fn key(matches: &ArgMatches) -> Result<()> {
+    let (name, matches) = matches.subcommand();
+    let matches = matches.ok_or_else(|| "no subcommand")?;
+
+    match name {
+        "generate" => key_generate(matches),
+        "show" => key_show(),
+        "list" => key_list(matches),
+        _ => unreachable!("bad subcommand"),
+    }
+}
+
+/// Find the line number of the given position.
+fn line_of(pos: &ErrorPos) -> Result<usize> {
+    let mut content = Vec::new();
+    pos.object.read()?.read_to_end(&mut content)?;
+    let end = ::std::cmp::min(pos.start, content.len());
+    Ok(content[..end].iter().filter(|b| **b == b'\n').count() + 1)
+}
+
+fn diff(matches: &ArgMatches) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, inner);
+
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    where
+        L: Lang,
+    {
+        let mut repository = setup_repository(&manifest)?;
+        let package = package_arg(matches)?;
+        let from = version_arg(matches, "from")?;
+        let to = version_arg(matches, "to")?;
+
+        let from_object = find_object(&mut repository, &package, &from)?;
+        let to_object = find_object(&mut repository, &package, &to)?;
+
+        // imports of the published specifications are resolved like any other.
+        let mut env = Environment::new(None, setup_resolvers(&manifest, None)?);
+
+        let package_from = RpVersionedPackage::new(package.clone(), Some(from.clone()));
+        let file_from = env.load_object(from_object, &package_from)?;
+
+        let package_to = RpVersionedPackage::new(package.clone(), Some(to.clone()));
+        let file_to = env.load_object(to_object, &package_to)?;
+
+        let changes = semck::changes(&file_from, &file_to)?;
+
+        if changes.is_empty() {
+            info!("{} -> {}: no changes", from, to);
+            return Ok(());
+        }
+
+        let mut lines = Vec::new();
+
+        for change in &changes {
+            let (message, pos) = change.describe();
+            lines.push((pos.object.to_string(), line_of(pos)?, message));
+        }
+
+        lines.sort();
+
+        for (object, line, message) in lines {
+            println!("{}:{}: {}", object, line, message);
+        }
+
+        let violations = semck::check((&from, &file_from), (&to, &file_to))?;
+
+        if !violations.is_empty() {
+            warn!(
+                "{} -> {}: {} change(s) are not permitted by semantic versioning",
+                from,
+                to,
+                violations.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("repo").about("Manage repositories");
     let out = out.subcommand(init_options());
+    let out = out.subcommand(base_args(list_options()));
+    let out = out.subcommand(base_args(versions_options()));
+    let out = out.subcommand(base_args(show_options()));
+    let out = out.subcommand(base_args(diff_options()));
+    let out = out.subcommand(base_args(fsck_options()));
+    let out = out.subcommand(base_args(gc_options()));
+    let out = out.subcommand(key_options());
     out
 }
 
@@ -33,6 +433,13 @@ pub fn entry(matches: &ArgMatches) -> Result<()> {
 
     match name {
         "init" => init(matches),
+        "list" => list(matches),
+        "versions" => versions(matches),
+        "show" => show(matches),
+        "diff" => diff(matches),
+        "fsck" => fsck(matches),
+        "gc" => gc(matches),
+        "key" => key(matches),
         _ => unreachable!("bad subcommand"),
     }
 }
@@ -1,20 +1,28 @@
 //! Update action that synchronizes all repositories.
 
 use manifest::{Lang, Manifest};
+use ops::{lock_path, setup_locked_environment};
 use ops::imports::*;
-use repository::Update;
+use repository::{Lock, Update};
+use std::cell::RefCell;
 use std::collections::HashSet;
+use std::rc::Rc;
 
 pub fn options<'a, 'b>() -> App<'a, 'b> {
     let out = SubCommand::with_name("update").about("Update local repository");
+
+    let out = out.arg(Arg::with_name("lock").long("lock").help(
+        "Resolve all packages again, and refresh the lockfile",
+    ));
+
     out
 }
 
-pub fn entry(matches: &ArgMatches) -> Result<()> {
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
     let preamble = manifest_preamble(matches)?;
-    return do_manifest_use!(matches, preamble, inner);
+    return do_manifest_use!(matches, preamble, output, inner);
 
-    fn inner<L>(_matches: &ArgMatches, manifest: Manifest<L>) -> Result<()>
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>, output: &Output) -> Result<()>
     where
         L: Lang,
     {
@@ -25,6 +33,19 @@ pub fn entry(matches: &ArgMatches) -> Result<()> {
             update.update()?;
         }
 
+        if matches.is_present("lock") {
+            let path = lock_path(&manifest).ok_or_else(
+                || "--lock: requires a manifest",
+            )?;
+
+            // resolve from scratch, ignoring anything which is currently locked.
+            let lock = Rc::new(RefCell::new(Lock::new()));
+            setup_locked_environment(&manifest, Some(lock.clone()), output)?;
+
+            info!("Writing: {}", path.display());
+            lock.borrow().write(&path)?;
+        }
+
         Ok(())
     }
 }
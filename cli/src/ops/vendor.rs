@@ -0,0 +1,261 @@
+//! Vendor action that copies all dependencies from the repository into a local directory.
+
+use backend::Environment;
+use core::{Object, RpPackage, RpRequiredPackage, RpVersionedPackage};
+use manifest::{Lang, Manifest};
+use ops::{lock_path, setup_environment_with};
+use ops::imports::*;
+use repository::{Resolved, ResolvedByPrefix, Resolver, Resolvers, read_lock};
+use repository::errors as repository;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+const DEFAULT_OUT: &str = "vendor";
+const EXT: &str = "reproto";
+
+/// Resolver which records the objects that are resolved through another resolver.
+struct Recording {
+    resolver: Box<Resolver>,
+    resolved: Rc<RefCell<Vec<(RpVersionedPackage, Box<Object>)>>>,
+}
+
+impl Resolver for Recording {
+    fn resolve(&mut self, package: &RpRequiredPackage) -> repository::Result<Vec<Resolved>> {
+        let resolved = self.resolver.resolve(package)?;
+
+        // which of these are used is decided by the environment, across all resolvers.
+        for r in &resolved {
+            let versioned = RpVersionedPackage::new(package.package.clone(), r.version.clone());
+            self.resolved.borrow_mut().push((versioned, r.object.clone_object()));
+        }
+
+        Ok(resolved)
+    }
+
+    fn resolve_by_prefix(
+        &mut self,
+        package: &RpPackage,
+    ) -> repository::Result<Vec<ResolvedByPrefix>> {
+        self.resolver.resolve_by_prefix(package)
+    }
+}
+
+/// Pick the recorded objects of the packages that were imported into the environment.
+fn used_objects(
+    env: &Environment,
+    recorded: Vec<(RpVersionedPackage, Box<Object>)>,
+) -> BTreeMap<RpVersionedPackage, Box<Object>> {
+    let used: BTreeSet<&RpVersionedPackage> = env.for_each_file().map(|(p, _)| p).collect();
+    let mut out = BTreeMap::new();
+
+    for (package, object) in recorded {
+        if used.contains(&package) {
+            out.entry(package).or_insert(object);
+        }
+    }
+
+    out
+}
+
+pub fn options<'a, 'b>() -> App<'a, 'b> {
+    let out = SubCommand::with_name("vendor").about(
+        "Copy all packages resolved from the repository into a local directory",
+    );
+
+    let out = out.arg(
+        Arg::with_name("out")
+            .long("out")
+            .short("o")
+            .takes_value(true)
+            .help("Directory to vendor packages into, defaults to `vendor`"),
+    );
+
+    out
+}
+
+/// Write a resolved object using the layout that the paths resolver understands.
+fn write_object(out: &Path, package: &RpVersionedPackage, object: &Object) -> Result<()> {
+    let mut parts = package.package.parts.iter().peekable();
+    let mut path = out.to_owned();
+
+    while let Some(part) = parts.next() {
+        if parts.peek().is_some() {
+            path = path.join(part);
+            continue;
+        }
+
+        let name = match package.version {
+            Some(ref version) => format!("{}-{}.{}", part, version, EXT),
+            None => format!("{}.{}", part, EXT),
+        };
+
+        if !path.is_dir() {
+            fs::create_dir_all(&path)?;
+        }
+
+        path = path.join(name);
+    }
+
+    let mut content = Vec::new();
+    object.read()?.read_to_end(&mut content)?;
+
+    debug!("writing: {}", path.display());
+    File::create(&path)?.write_all(&content)?;
+    Ok(())
+}
+
+pub fn entry(matches: &ArgMatches, output: &Output) -> Result<()> {
+    let preamble = manifest_preamble(matches)?;
+    return do_manifest_use!(matches, preamble, output, inner);
+
+    fn inner<L>(matches: &ArgMatches, manifest: Manifest<L>, output: &Output) -> Result<()>
+    where
+        L: Lang,
+    {
+        let out = Path::new(matches.value_of("out").unwrap_or(DEFAULT_OUT));
+
+        let mut repository = setup_repository(&manifest)?;
+
+        // vendor exactly what is locked, if anything.
+        if let Some(path) = lock_path(&manifest) {
+            repository = repository.with_lock(Rc::new(RefCell::new(read_lock(&path)?)));
+        }
+
+        let resolved = Rc::new(RefCell::new(Vec::new()));
+
+        let mut resolvers: Vec<Box<Resolver>> = Vec::new();
+
+        resolvers.push(Box::new(Recording {
+            resolver: Box::new(repository),
+            resolved: resolved.clone(),
+        }));
+
+        if let Some(resolver) = setup_path_resolver(&manifest)? {
+            resolvers.push(resolver);
+        }
+
+        let env = setup_environment_with(&manifest, Box::new(Resolvers::new(resolvers)), output)?;
+
+        let recorded = resolved.borrow_mut().drain(..).collect();
+        let resolved = used_objects(&env, recorded);
+
+        for (package, object) in &resolved {
+            info!("Vendoring: {}", package);
+            write_object(out, package, object.as_ref())?;
+        }
+
+        info!(
+            "Vendored {} package(s) into: {}",
+            resolved.len(),
+            out.display()
+        );
+
+        info!(
+            "Build without a repository by adding `{}` to `paths` in the manifest, or by using \
+             `--no-repository --path {}`",
+            out.display(),
+            out.display()
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{BytesObject, Version, VersionReq};
+    use std::sync::Arc;
+
+    /// Resolver which resolves every package to the given versions.
+    struct Fixed(Vec<&'static str>);
+
+    impl Resolver for Fixed {
+        fn resolve(&mut self, package: &RpRequiredPackage) -> repository::Result<Vec<Resolved>> {
+            let mut out = Vec::new();
+
+            for version in &self.0 {
+                let name = format!("{}-{}", package.package, version);
+                let bytes = Arc::new(b"type Foo {}".to_vec());
+
+                out.push(Resolved {
+                    version: Some(Version::parse(version).unwrap()),
+                    object: Box::new(BytesObject::new(name, bytes)),
+                    deprecated: None,
+                });
+            }
+
+            Ok(out)
+        }
+
+        fn resolve_by_prefix(
+            &mut self,
+            _: &RpPackage,
+        ) -> repository::Result<Vec<ResolvedByPrefix>> {
+            Ok(vec![])
+        }
+    }
+
+    fn recording(
+        versions: Vec<&'static str>,
+        resolved: &Rc<RefCell<Vec<(RpVersionedPackage, Box<Object>)>>>,
+    ) -> Box<Resolver> {
+        Box::new(Recording {
+            resolver: Box::new(Fixed(versions)),
+            resolved: resolved.clone(),
+        })
+    }
+
+    fn import(resolvers: Vec<Box<Resolver>>) -> Environment {
+        let mut env = Environment::new(None, Box::new(Resolvers::new(resolvers)));
+        let required = RpRequiredPackage::new(RpPackage::parse("test"), VersionReq::any());
+        env.import(&required).unwrap();
+        env
+    }
+
+    fn vendored(
+        env: &Environment,
+        resolved: &Rc<RefCell<Vec<(RpVersionedPackage, Box<Object>)>>>,
+    ) -> Vec<String> {
+        let recorded = resolved.borrow_mut().drain(..).collect();
+        let used = used_objects(env, recorded);
+        used.values().map(|object| object.to_string()).collect()
+    }
+
+    #[test]
+    fn test_vendor_used() {
+        let resolved = Rc::new(RefCell::new(Vec::new()));
+        let env = import(vec![recording(vec!["1.0.0", "1.1.0"], &resolved)]);
+
+        assert_eq!(vec!["<test-1.1.0>"], vendored(&env, &resolved));
+    }
+
+    #[test]
+    fn test_vendor_ignores_other_resolvers() {
+        let resolved = Rc::new(RefCell::new(Vec::new()));
+
+        // the environment picks the last match across all resolvers, which is not recorded.
+        let env = import(vec![
+            recording(vec!["1.0.0", "1.1.0"], &resolved),
+            Box::new(Fixed(vec!["1.2.0"])),
+        ]);
+
+        assert!(vendored(&env, &resolved).is_empty());
+    }
+
+    #[test]
+    fn test_vendor_picks_recorded_over_other_resolvers() {
+        let resolved = Rc::new(RefCell::new(Vec::new()));
+
+        let env = import(vec![
+            Box::new(Fixed(vec!["1.0.0"])),
+            recording(vec!["1.1.0"], &resolved),
+        ]);
+
+        assert_eq!(vec!["<test-1.1.0>"], vendored(&env, &resolved));
+    }
+}
@@ -1,5 +1,5 @@
 use super::{LockableWrite, Output, find_line};
-use ansi_term::Colour::{Blue, Red};
+use ansi_term::Colour::{Blue, Red, Yellow};
 use core::ErrorPos;
 use errors::*;
 use log;
@@ -28,6 +28,12 @@ where
         Ok(())
     }
 
+    fn print_warning(&self, m: &str) -> Result<()> {
+        let mut o = self.out.lock();
+        writeln!(o, "WARNING: {}", Yellow.paint(m.as_ref()))?;
+        Ok(())
+    }
+
     fn print_error(&self, m: &str, p: &ErrorPos) -> Result<()> {
         use std::iter::repeat;
         use std::cmp::max;
@@ -32,8 +32,35 @@ impl LockableWrite for io::Stdout {
     }
 }
 
+impl LockableWrite for io::Stderr {
+    fn open_new(&self) -> Self {
+        io::stderr()
+    }
+
+    fn lock<'a>(&'a self) -> Box<Write + 'a> {
+        Box::new(self.lock())
+    }
+}
+
 const NL: u8 = '\n' as u8;
 
+/// Display helper for the default value of a field.
+struct DefaultValue<'a>(Option<&'a core::RpValue>);
+
+impl<'a> fmt::Display for DefaultValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use core::RpValue::*;
+
+        match self.0 {
+            None => write!(f, "no default"),
+            Some(&String(ref string)) => write!(f, "`{:?}`", string),
+            Some(&Number(ref number)) => write!(f, "`{}`", number),
+            Some(&Boolean(ref boolean)) => write!(f, "`{}`", boolean),
+            Some(value) => write!(f, "`{}`", value),
+        }
+    }
+}
+
 fn find_line<'a, R: AsMut<Read + 'a>>(
     mut reader: R,
     pos: (usize, usize),
@@ -165,6 +192,11 @@ pub trait Output {
                 self.print_error("previous name here", other)?;
                 true
             }
+            EnumOrdinalConflict(ref pos, ref other) => {
+                self.print_error("conflicting ordinal", pos)?;
+                self.print_error("previous ordinal here", other)?;
+                true
+            }
             EndpointConflict(ref new, ref old) => {
                 self.print_error("conflicting id of endpoint", new)?;
                 self.print_error("previous id here", old)?;
@@ -386,6 +418,21 @@ pub trait Output {
                     field,
                 )?;
             }
+            FieldDefaultChange(ref c, ref from_default, ref from, ref to_default, ref to) => {
+                self.print_error(
+                    format!(
+                        "{}: default value changed to {}",
+                        c.describe(),
+                        DefaultValue(to_default.as_ref())
+                    ).as_str(),
+                    to,
+                )?;
+
+                self.print_error(
+                    format!("from {}", DefaultValue(from_default.as_ref())).as_str(),
+                    from,
+                )?;
+            }
             FieldModifierChange(ref c, ref from, ref to) => {
                 self.print_error(
                     format!(
@@ -450,6 +497,8 @@ pub trait Output {
 
     fn print(&self, m: &str) -> Result<()>;
 
+    fn print_warning(&self, m: &str) -> Result<()>;
+
     fn print_error(&self, m: &str, p: &core::ErrorPos) -> Result<()>;
 
     fn print_root_error(&self, e: &Error) -> Result<()>;
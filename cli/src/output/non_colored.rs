@@ -48,6 +48,12 @@ where
         Ok(())
     }
 
+    fn print_warning(&self, m: &str) -> Result<()> {
+        let mut o = self.out.lock();
+        writeln!(o, "WARNING: {}", m)?;
+        Ok(())
+    }
+
     fn print_error(&self, m: &str, p: &ErrorPos) -> Result<()> {
         use std::iter::repeat;
         use std::cmp::max;
@@ -0,0 +1,110 @@
+package test;
+
+import java.util.List;
+import java.util.Objects;
+import java.util.Optional;
+import java.util.regex.Pattern;
+
+public class Entry {
+  private final int age;
+  private final String name;
+  private final Optional<List<String>> tags;
+
+  public Entry(
+    final int age,
+    final String name,
+    final Optional<List<String>> tags
+  ) {
+    if (age < 0) {
+      throw new IllegalArgumentException("age: must be greater than or equal to 0");
+    }
+    if (age > 150) {
+      throw new IllegalArgumentException("age: must be less than or equal to 150");
+    }
+    this.age = age;
+    Objects.requireNonNull(name, "name");
+    if (name.length() < 1) {
+      throw new IllegalArgumentException("name: length must be at least 1");
+    }
+    if (name.length() > 10) {
+      throw new IllegalArgumentException("name: length must be at most 10");
+    }
+    if (!Pattern.compile("^[a-z]+$").matcher(name).find()) {
+      throw new IllegalArgumentException("name: must match pattern `^[a-z]+$`");
+    }
+    this.name = name;
+    Objects.requireNonNull(tags, "tags");
+    if (tags.isPresent() && tags.get().size() > 3) {
+      throw new IllegalArgumentException("tags: length must be at most 3");
+    }
+    this.tags = tags;
+  }
+
+  public int getAge() {
+    return this.age;
+  }
+
+  public String getName() {
+    return this.name;
+  }
+
+  public Optional<List<String>> getTags() {
+    return this.tags;
+  }
+
+  @Override
+  public int hashCode() {
+    int result = 1;
+    result = result * 31 + this.age;
+    result = result * 31 + this.name.hashCode();
+    result = result * 31 + this.tags.hashCode();
+    return result;
+  }
+
+  @Override
+  public boolean equals(final Object other) {
+    if (other == null) {
+      return false;
+    }
+
+    if (!(other instanceof Entry)) {
+      return false;
+    }
+
+    @SuppressWarnings("unchecked")
+    final Entry o = (Entry) other;
+
+    if (this.age != o.age) {
+      return false;
+    }
+
+    if (!this.name.equals(o.name)) {
+      return false;
+    }
+
+    if (!this.tags.equals(o.tags)) {
+      return false;
+    }
+
+    return true;
+  }
+
+  @Override
+  public String toString() {
+    final StringBuilder b = new StringBuilder();
+
+    b.append("Entry");
+    b.append("(");
+    b.append("age=");
+    b.append(Integer.toString(this.age));
+    b.append(", ");
+    b.append("name=");
+    b.append(this.name.toString());
+    b.append(", ");
+    b.append("tags=");
+    b.append(this.tags.toString());
+    b.append(")");
+
+    return b.toString();
+  }
+}
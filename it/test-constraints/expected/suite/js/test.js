@@ -0,0 +1,74 @@
+export class Entry {
+  constructor(age, name, tags) {
+    this.age = age;
+    this.name = name;
+    this.tags = tags;
+  }
+
+  static decode(data) {
+    const v_age = data["age"];
+
+    if (v_age === null || v_age === undefined) {
+      throw new Error("age" + ": required field");
+    }
+
+    if (v_age < 0) {
+      throw new Error("age: must be greater than or equal to 0");
+    }
+    if (v_age > 150) {
+      throw new Error("age: must be less than or equal to 150");
+    }
+
+    const v_name = data["name"];
+
+    if (v_name === null || v_name === undefined) {
+      throw new Error("name" + ": required field");
+    }
+
+    if (v_name.length < 1) {
+      throw new Error("name: length must be at least 1");
+    }
+    if (v_name.length > 10) {
+      throw new Error("name: length must be at most 10");
+    }
+    if (!new RegExp("^[a-z]+$").test(v_name)) {
+      throw new Error("name: must match pattern `^[a-z]+$`");
+    }
+
+    let v_tags = data["tags"];
+
+    if (v_tags !== null && v_tags !== undefined) {
+      v_tags = v_tags;
+    } else {
+      v_tags = null;
+    }
+
+    if (v_tags !== null && v_tags.length > 3) {
+      throw new Error("tags: length must be at most 3");
+    }
+
+    return new Entry(v_age, v_name, v_tags);
+  }
+
+  encode() {
+    const data = {};
+
+    if (this.age === null || this.age === undefined) {
+      throw new Error("age: is a required field");
+    }
+
+    data["age"] = this.age;
+
+    if (this.name === null || this.name === undefined) {
+      throw new Error("name: is a required field");
+    }
+
+    data["name"] = this.name;
+
+    if (this.tags !== null && this.tags !== undefined) {
+      data["tags"] = this.tags;
+    }
+
+    return data;
+  }
+}
@@ -0,0 +1,59 @@
+import re
+
+class Entry:
+  def __init__(self, age, name, tags):
+    self.age = age
+    self.name = name
+    self.tags = tags
+
+  @staticmethod
+  def decode(data):
+    f_age = data["age"]
+
+    if f_age < 0:
+      raise Exception("age: must be greater than or equal to 0")
+    if f_age > 150:
+      raise Exception("age: must be less than or equal to 150")
+
+    f_name = data["name"]
+
+    if len(f_name) < 1:
+      raise Exception("name: length must be at least 1")
+    if len(f_name) > 10:
+      raise Exception("name: length must be at most 10")
+    if not re.search("^[a-z]+$", f_name):
+      raise Exception("name: must match pattern `^[a-z]+$`")
+
+    if "tags" in data:
+      f_tags = data["tags"]
+
+      if f_tags is not None:
+        f_tags = f_tags
+    else:
+      f_tags = None
+
+    if f_tags is not None and len(f_tags) > 3:
+      raise Exception("tags: length must be at most 3")
+
+    return Entry(f_age, f_name, f_tags)
+
+  def encode(self):
+    data = dict()
+
+    if self.age is None:
+      raise Exception("age: is a required field")
+
+    data["age"] = self.age
+
+    if self.name is None:
+      raise Exception("name: is a required field")
+
+    data["name"] = self.name
+
+    if self.tags is not None:
+      data["tags"] = self.tags
+
+    return data
+
+  def __repr__(self):
+    return "<Entry age: {!r}, name: {!r}, tags: {!r}>".format(self.age, self.name, self.tags)
@@ -0,0 +1,99 @@
+
+export class Entry {
+  constructor(priority, offset) {
+    this.priority = priority;
+    this.offset = offset;
+  }
+
+  static decode(data) {
+    let v_priority = data["priority"];
+
+    if (v_priority !== null && v_priority !== undefined) {
+      v_priority = Priority.decode(v_priority);
+    } else {
+      v_priority = null;
+    }
+
+    let v_offset = data["offset"];
+
+    if (v_offset !== null && v_offset !== undefined) {
+      v_offset = Offset.decode(v_offset);
+    } else {
+      v_offset = null;
+    }
+
+    return new Entry(v_priority, v_offset);
+  }
+
+  encode() {
+    const data = {};
+
+    if (this.priority !== null && this.priority !== undefined) {
+      data["priority"] = this.priority.encode();
+    }
+
+    if (this.offset !== null && this.offset !== undefined) {
+      data["offset"] = this.offset.encode();
+    }
+
+    return data;
+  }
+}
+
+export class Priority {
+  constructor(name, value) {
+    this.name = name;
+    this.value = value;
+  }
+
+  encode() {
+    return this.value;
+  }
+  static decode(data) {
+    for (let i = 0, l = Priority.values.length; i < l; i++) {
+      const member = Priority.values[i]
+
+
+
+      if (member.value === data) {
+        return member;
+      }
+    }
+
+    throw new Error("no matching value: " + data);
+  }
+}
+
+Priority.LOW = new Priority("LOW", 1);
+Priority.HIGH = new Priority("HIGH", 10);
+
+Priority.values = [Priority.LOW, Priority.HIGH];
+
+export class Offset {
+  constructor(name, value) {
+    this.name = name;
+    this.value = value;
+  }
+
+  encode() {
+    return this.value;
+  }
+  static decode(data) {
+    for (let i = 0, l = Offset.values.length; i < l; i++) {
+      const member = Offset.values[i]
+
+
+
+      if (member.value === data) {
+        return member;
+      }
+    }
+
+    throw new Error("no matching value: " + data);
+  }
+}
+
+Offset.BEFORE = new Offset("BEFORE", -1);
+Offset.AFTER = new Offset("AFTER", 1);
+
+Offset.values = [Offset.BEFORE, Offset.AFTER];
@@ -0,0 +1,80 @@
+import enum
+
+class Entry:
+  def __init__(self, priority, offset):
+    self.priority = priority
+    self.offset = offset
+
+  @staticmethod
+  def decode(data):
+    if "priority" in data:
+      f_priority = data["priority"]
+
+      if f_priority is not None:
+        f_priority = Priority.decode(f_priority)
+    else:
+      f_priority = None
+
+    if "offset" in data:
+      f_offset = data["offset"]
+
+      if f_offset is not None:
+        f_offset = Offset.decode(f_offset)
+    else:
+      f_offset = None
+
+    return Entry(f_priority, f_offset)
+
+  def encode(self):
+    data = dict()
+
+    if self.priority is not None:
+      data["priority"] = self.priority.encode()
+
+    if self.offset is not None:
+      data["offset"] = self.offset.encode()
+
+    return data
+
+  def __repr__(self):
+    return "<Entry priority: {!r}, offset: {!r}>".format(self.priority, self.offset)
+
+class Priority:
+  def __init__(self, _value):
+    self._value = _value
+
+  def encode(self):
+    return self._value
+
+  @classmethod
+  def decode(cls, data):
+    for value in cls.__members__.values():
+      if value._value == data:
+        return value
+
+    raise Exception("data does not match enum")
+
+  def __repr__(self):
+    return "<Priority _value: {!r}>".format(self._value)
+
+class Offset:
+  def __init__(self, _value):
+    self._value = _value
+
+  def encode(self):
+    return self._value
+
+  @classmethod
+  def decode(cls, data):
+    for value in cls.__members__.values():
+      if value._value == data:
+        return value
+
+    raise Exception("data does not match enum")
+
+  def __repr__(self):
+    return "<Offset _value: {!r}>".format(self._value)
+
+Priority = enum.Enum("Priority", [("LOW", 1), ("HIGH", 10)], type=Priority)
+
+Offset = enum.Enum("Offset", [("BEFORE", -1), ("AFTER", 1)], type=Offset)
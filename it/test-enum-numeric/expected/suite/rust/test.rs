@@ -0,0 +1,41 @@
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Entry {
+  #[serde(skip_serializing_if="Option::is_none")]
+  priority: Option<Priority>,
+  #[serde(skip_serializing_if="Option::is_none")]
+  offset: Option<Offset>,
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug)]
+#[repr(u32)]
+pub enum Priority {
+  LOW = 1,
+  HIGH = 10,
+}
+
+impl Priority {
+  pub fn value(&self) -> u32 {
+    use self::Priority::*;
+    match *self {
+      LOW => 1,
+      HIGH => 10,
+    }
+  }
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Debug)]
+#[repr(i64)]
+pub enum Offset {
+  BEFORE = -1,
+  AFTER = 1,
+}
+
+impl Offset {
+  pub fn value(&self) -> i64 {
+    use self::Offset::*;
+    match *self {
+      BEFORE => -1,
+      AFTER => 1,
+    }
+  }
+}
@@ -0,0 +1,75 @@
+package test;
+
+import java.util.Objects;
+import java.util.Optional;
+
+public class Entry {
+  private final Optional<Page<Other>> page;
+  private final Optional<Pair<String, Integer>> pair;
+
+  public Entry(
+    final Optional<Page<Other>> page,
+    final Optional<Pair<String, Integer>> pair
+  ) {
+    Objects.requireNonNull(page, "page");
+    this.page = page;
+    Objects.requireNonNull(pair, "pair");
+    this.pair = pair;
+  }
+
+  public Optional<Page<Other>> getPage() {
+    return this.page;
+  }
+
+  public Optional<Pair<String, Integer>> getPair() {
+    return this.pair;
+  }
+
+  @Override
+  public int hashCode() {
+    int result = 1;
+    result = result * 31 + this.page.hashCode();
+    result = result * 31 + this.pair.hashCode();
+    return result;
+  }
+
+  @Override
+  public boolean equals(final Object other) {
+    if (other == null) {
+      return false;
+    }
+
+    if (!(other instanceof Entry)) {
+      return false;
+    }
+
+    @SuppressWarnings("unchecked")
+    final Entry o = (Entry) other;
+
+    if (!this.page.equals(o.page)) {
+      return false;
+    }
+
+    if (!this.pair.equals(o.pair)) {
+      return false;
+    }
+
+    return true;
+  }
+
+  @Override
+  public String toString() {
+    final StringBuilder b = new StringBuilder();
+
+    b.append("Entry");
+    b.append("(");
+    b.append("page=");
+    b.append(this.page.toString());
+    b.append(", ");
+    b.append("pair=");
+    b.append(this.pair.toString());
+    b.append(")");
+
+    return b.toString();
+  }
+}
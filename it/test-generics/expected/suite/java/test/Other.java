@@ -0,0 +1,58 @@
+package test;
+
+import java.util.Objects;
+
+public class Other {
+  private final String a;
+
+  public Other(
+    final String a
+  ) {
+    Objects.requireNonNull(a, "a");
+    this.a = a;
+  }
+
+  public String getA() {
+    return this.a;
+  }
+
+  @Override
+  public int hashCode() {
+    int result = 1;
+    result = result * 31 + this.a.hashCode();
+    return result;
+  }
+
+  @Override
+  public boolean equals(final Object other) {
+    if (other == null) {
+      return false;
+    }
+
+    if (!(other instanceof Other)) {
+      return false;
+    }
+
+    @SuppressWarnings("unchecked")
+    final Other o = (Other) other;
+
+    if (!this.a.equals(o.a)) {
+      return false;
+    }
+
+    return true;
+  }
+
+  @Override
+  public String toString() {
+    final StringBuilder b = new StringBuilder();
+
+    b.append("Other");
+    b.append("(");
+    b.append("a=");
+    b.append(this.a.toString());
+    b.append(")");
+
+    return b.toString();
+  }
+}
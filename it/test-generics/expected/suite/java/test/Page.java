@@ -0,0 +1,76 @@
+package test;
+
+import java.util.List;
+import java.util.Objects;
+import java.util.Optional;
+
+public class Page<T> {
+  private final List<T> items;
+  private final Optional<String> next;
+
+  public Page(
+    final List<T> items,
+    final Optional<String> next
+  ) {
+    Objects.requireNonNull(items, "items");
+    this.items = items;
+    Objects.requireNonNull(next, "next");
+    this.next = next;
+  }
+
+  public List<T> getItems() {
+    return this.items;
+  }
+
+  public Optional<String> getNext() {
+    return this.next;
+  }
+
+  @Override
+  public int hashCode() {
+    int result = 1;
+    result = result * 31 + this.items.hashCode();
+    result = result * 31 + this.next.hashCode();
+    return result;
+  }
+
+  @Override
+  public boolean equals(final Object other) {
+    if (other == null) {
+      return false;
+    }
+
+    if (!(other instanceof Page)) {
+      return false;
+    }
+
+    @SuppressWarnings("unchecked")
+    final Page o = (Page) other;
+
+    if (!this.items.equals(o.items)) {
+      return false;
+    }
+
+    if (!this.next.equals(o.next)) {
+      return false;
+    }
+
+    return true;
+  }
+
+  @Override
+  public String toString() {
+    final StringBuilder b = new StringBuilder();
+
+    b.append("Page");
+    b.append("(");
+    b.append("items=");
+    b.append(this.items.toString());
+    b.append(", ");
+    b.append("next=");
+    b.append(this.next.toString());
+    b.append(")");
+
+    return b.toString();
+  }
+}
@@ -0,0 +1,74 @@
+package test;
+
+import java.util.Objects;
+
+public class Pair<A, B> {
+  private final A a;
+  private final B b;
+
+  public Pair(
+    final A a,
+    final B b
+  ) {
+    Objects.requireNonNull(a, "a");
+    this.a = a;
+    Objects.requireNonNull(b, "b");
+    this.b = b;
+  }
+
+  @Override
+  public int hashCode() {
+    int result = 1;
+    result = result * 31 + this.a.hashCode();
+    result = result * 31 + this.b.hashCode();
+    return result;
+  }
+
+  @Override
+  public boolean equals(final Object other) {
+    if (other == null) {
+      return false;
+    }
+
+    if (!(other instanceof Pair)) {
+      return false;
+    }
+
+    @SuppressWarnings("unchecked")
+    final Pair o = (Pair) other;
+
+    if (!this.a.equals(o.a)) {
+      return false;
+    }
+
+    if (!this.b.equals(o.b)) {
+      return false;
+    }
+
+    return true;
+  }
+
+  @Override
+  public String toString() {
+    final StringBuilder b = new StringBuilder();
+
+    b.append("Pair");
+    b.append("(");
+    b.append("a=");
+    b.append(this.a.toString());
+    b.append(", ");
+    b.append("b=");
+    b.append(this.b.toString());
+    b.append(")");
+
+    return b.toString();
+  }
+
+  public A getA() {
+    return this.a;
+  }
+
+  public B getB() {
+    return this.b;
+  }
+}
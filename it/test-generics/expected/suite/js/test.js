@@ -0,0 +1,153 @@
+export class Entry {
+  constructor(page, pair) {
+    this.page = page;
+    this.pair = pair;
+  }
+
+  static decode(data) {
+    let v_page = data["page"];
+
+    if (v_page !== null && v_page !== undefined) {
+      v_page = Page.decode(v_page, function(v) { return Other.decode(v); });
+    } else {
+      v_page = null;
+    }
+
+    let v_pair = data["pair"];
+
+    if (v_pair !== null && v_pair !== undefined) {
+      v_pair = Pair.decode(v_pair, function(v) { return v; }, function(v) { return v; });
+    } else {
+      v_pair = null;
+    }
+
+    return new Entry(v_page, v_pair);
+  }
+
+  encode() {
+    const data = {};
+
+    if (this.page !== null && this.page !== undefined) {
+      data["page"] = this.page.encode(function(v) { return v.encode(); });
+    }
+
+    if (this.pair !== null && this.pair !== undefined) {
+      data["pair"] = this.pair.encode(function(v) { return v; }, function(v) { return v; });
+    }
+
+    return data;
+  }
+}
+
+/**
+ * @template T
+ */
+export class Page {
+  constructor(items, next) {
+    this.items = items;
+    this.next = next;
+  }
+
+  static decode(data, decodeT) {
+    const v_items = data["items"].map(function(v) { return decodeT(v); });
+
+    if (v_items === null || v_items === undefined) {
+      throw new Error("items" + ": required field");
+    }
+
+    let v_next = data["next"];
+
+    if (v_next !== null && v_next !== undefined) {
+      v_next = v_next;
+    } else {
+      v_next = null;
+    }
+
+    return new Page(v_items, v_next);
+  }
+
+  encode(encodeT) {
+    const data = {};
+
+    if (this.items === null || this.items === undefined) {
+      throw new Error("items: is a required field");
+    }
+
+    data["items"] = this.items.map(function(v) { return encodeT(v); });
+
+    if (this.next !== null && this.next !== undefined) {
+      data["next"] = this.next;
+    }
+
+    return data;
+  }
+}
+
+/**
+ * @template A
+ * @template B
+ */
+export class Pair {
+  constructor(a, b) {
+    this.a = a;
+    this.b = b;
+  }
+
+  static decode(data, decodeA, decodeB) {
+    const v_a = decodeA(data[0]);
+
+    if (v_a === null || v_a === undefined) {
+      throw new Error(0 + ": required field");
+    }
+
+    const v_b = decodeB(data[1]);
+
+    if (v_b === null || v_b === undefined) {
+      throw new Error(1 + ": required field");
+    }
+
+    return new Pair(v_a, v_b);
+  }
+
+  encode(encodeA, encodeB) {
+    if (this.a === null || this.a === undefined) {
+      throw new Error("a: is a required field");
+    }
+
+    if (this.b === null || this.b === undefined) {
+      throw new Error("b: is a required field");
+    }
+
+    return [
+    encodeA(this.a), 
+    encodeB(this.b)];
+  }
+}
+
+export class Other {
+  constructor(a) {
+    this.a = a;
+  }
+
+  static decode(data) {
+    const v_a = data["a"];
+
+    if (v_a === null || v_a === undefined) {
+      throw new Error("a" + ": required field");
+    }
+
+    return new Other(v_a);
+  }
+
+  encode() {
+    const data = {};
+
+    if (this.a === null || this.a === undefined) {
+      throw new Error("a: is a required field");
+    }
+
+    data["a"] = this.a;
+
+    return data;
+  }
+}
@@ -0,0 +1,24 @@
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Entry {
+  #[serde(skip_serializing_if="Option::is_none")]
+  page: Option<Page<Other>>,
+  #[serde(skip_serializing_if="Option::is_none")]
+  pair: Option<Pair<String, i32>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Page<T> {
+  items: Vec<T>,
+  #[serde(skip_serializing_if="Option::is_none")]
+  next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Pair<A, B>(
+A, 
+B);
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Other {
+  a: String,
+}
@@ -0,0 +1,5 @@
+package other
+
+type Thing struct {
+  Name string `json:"name"`
+}
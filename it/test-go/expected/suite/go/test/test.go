@@ -0,0 +1,9 @@
+package test
+
+import (
+  other "github.com/reproto/it/other"
+)
+
+type Entry struct {
+  Thing *other.Thing `json:"thing,omitempty"`
+}
@@ -0,0 +1,116 @@
+export class Entry {
+  foo: Foo | null;
+  kind: Kind;
+  tags: Array<string>;
+
+  constructor(foo: Foo | null, kind: Kind, tags: Array<string>) {
+    this.foo = foo;
+    this.kind = kind;
+    this.tags = tags;
+  }
+
+  static encode(value: Entry): any {
+    return value.encode();
+  }
+
+  static decode(data: any): Entry {
+    let v_foo = data["foo"];
+
+    if (v_foo !== null && v_foo !== undefined) {
+      v_foo = Foo.decode(v_foo);
+    } else {
+      v_foo = null;
+    }
+
+    const v_kind = Kind.decode(data["kind"]);
+
+    if (v_kind === null || v_kind === undefined) {
+      throw new Error("kind" + ": required field");
+    }
+
+    const v_tags = data["tags"];
+
+    if (v_tags === null || v_tags === undefined) {
+      throw new Error("tags" + ": required field");
+    }
+
+    return new Entry(v_foo, v_kind, v_tags);
+  }
+
+  encode(): any {
+    const data: any = {};
+
+    if (this.foo !== null && this.foo !== undefined) {
+      data["foo"] = Foo.encode(this.foo);
+    }
+
+    if (this.kind === null || this.kind === undefined) {
+      throw new Error("kind: is a required field");
+    }
+
+    data["kind"] = Kind.encode(this.kind);
+
+    if (this.tags === null || this.tags === undefined) {
+      throw new Error("tags: is a required field");
+    }
+
+    data["tags"] = this.tags;
+
+    return data;
+  }
+}
+
+export class Foo {
+  field: string;
+
+  constructor(field: string) {
+    this.field = field;
+  }
+
+  static encode(value: Foo): any {
+    return value.encode();
+  }
+
+  static decode(data: any): Foo {
+    const v_field = data["field"];
+
+    if (v_field === null || v_field === undefined) {
+      throw new Error("field" + ": required field");
+    }
+
+    return new Foo(v_field);
+  }
+
+  encode(): any {
+    const data: any = {};
+
+    if (this.field === null || this.field === undefined) {
+      throw new Error("field: is a required field");
+    }
+
+    data["field"] = this.field;
+
+    return data;
+  }
+}
+
+export type Kind = "a" | "b";
+
+export namespace Kind {
+  export const A: Kind = "a";
+  export const B: Kind = "b";
+
+  export const values: Kind[] = [A, B];
+
+  export function decode(data: any): Kind {
+    if (values.indexOf(data) === -1) {
+      throw new Error("no matching value: " + data);
+    }
+
+    return data;
+  }
+
+  export function encode(value: Kind): any {
+    return value;
+  }
+}
@@ -0,0 +1,91 @@
+package test;
+
+import java.util.Objects;
+import java.util.Optional;
+
+public class Entry {
+  private final Optional<Integer> small;
+  private final Optional<Long> large;
+  private final Optional<Long> signed;
+
+  public Entry(
+    final Optional<Integer> small,
+    final Optional<Long> large,
+    final Optional<Long> signed
+  ) {
+    Objects.requireNonNull(small, "small");
+    this.small = small.isPresent() ? small : Optional.of((int) 4294967295L);
+    Objects.requireNonNull(large, "large");
+    this.large = large.isPresent() ? large : Optional.of(0xffffffffffffffffL);
+    Objects.requireNonNull(signed, "signed");
+    this.signed = signed.isPresent() ? signed : Optional.of(9223372036854775807L);
+  }
+
+  public Optional<Integer> getSmall() {
+    return this.small;
+  }
+
+  public Optional<Long> getLarge() {
+    return this.large;
+  }
+
+  public Optional<Long> getSigned() {
+    return this.signed;
+  }
+
+  @Override
+  public int hashCode() {
+    int result = 1;
+    result = result * 31 + this.small.hashCode();
+    result = result * 31 + this.large.hashCode();
+    result = result * 31 + this.signed.hashCode();
+    return result;
+  }
+
+  @Override
+  public boolean equals(final Object other) {
+    if (other == null) {
+      return false;
+    }
+
+    if (!(other instanceof Entry)) {
+      return false;
+    }
+
+    @SuppressWarnings("unchecked")
+    final Entry o = (Entry) other;
+
+    if (!this.small.equals(o.small)) {
+      return false;
+    }
+
+    if (!this.large.equals(o.large)) {
+      return false;
+    }
+
+    if (!this.signed.equals(o.signed)) {
+      return false;
+    }
+
+    return true;
+  }
+
+  @Override
+  public String toString() {
+    final StringBuilder b = new StringBuilder();
+
+    b.append("Entry");
+    b.append("(");
+    b.append("small=");
+    b.append(this.small.toString());
+    b.append(", ");
+    b.append("large=");
+    b.append(this.large.toString());
+    b.append(", ");
+    b.append("signed=");
+    b.append(this.signed.toString());
+    b.append(")");
+
+    return b.toString();
+  }
+}
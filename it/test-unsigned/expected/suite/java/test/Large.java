@@ -0,0 +1,28 @@
+package test;
+
+public enum Large {
+  MAX(0xffffffffffffffffL),
+  SIGNED(9223372036854775807L);
+
+  private final long value;
+
+  private Large(
+    final long value
+  ) {
+    this.value = value;
+  }
+
+  public static Large fromValue(final long value) {
+    for (final Large v_value : values()) {
+      if (v_value.value == value) {
+        return v_value;
+      }
+    }
+
+    throw new IllegalArgumentException("value");
+  }
+
+  public long toValue() {
+    return this.value;
+  }
+}
@@ -0,0 +1,28 @@
+package test;
+
+public enum Small {
+  MAX((int) 4294967295L),
+  ZERO(0);
+
+  private final int value;
+
+  private Small(
+    final int value
+  ) {
+    this.value = value;
+  }
+
+  public static Small fromValue(final int value) {
+    for (final Small v_value : values()) {
+      if (v_value.value == value) {
+        return v_value;
+      }
+    }
+
+    throw new IllegalArgumentException("value");
+  }
+
+  public int toValue() {
+    return this.value;
+  }
+}
@@ -4,7 +4,7 @@ use reproto_core::{Loc, OptionEntry, RpModifier, RpNumber, RpPackage};
 
 /// A type.
 ///
-/// For example: `u32`, `::Relative::Name`, or `bytes`.
+/// For example: `u32`, `::Relative::Name`, `Page<Foo>`, or `bytes`.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Type {
     Double,
@@ -17,7 +17,7 @@ pub enum Type {
     Any,
     /// ISO-8601 for date and time.
     DateTime,
-    Name { name: Name },
+    Name { name: Name, arguments: Vec<Type> },
     Array { inner: Box<Type> },
     Map { key: Box<Type>, value: Box<Type> },
 }
@@ -72,13 +72,23 @@ pub struct EnumVariant<'input> {
     pub name: Loc<&'input str>,
     pub comment: Vec<&'input str>,
     pub argument: Option<Loc<Value<'input>>>,
+    pub options: Vec<Loc<OptionDecl<'input>>>,
 }
 
 /// A field.
 ///
 /// ```ignore
 /// /// <comment>
-/// <name><modifier>: <ty> as <field_as>
+/// <name><modifier>: <ty> as <field_as> = <default>;
+/// ```
+///
+/// Or, with options:
+///
+/// ```ignore
+/// /// <comment>
+/// <name><modifier>: <ty> as <field_as> = <default> {
+///     <options>
+/// }
 /// ```
 #[derive(Debug, PartialEq, Eq)]
 pub struct Field<'input> {
@@ -87,6 +97,8 @@ pub struct Field<'input> {
     pub comment: Vec<&'input str>,
     pub ty: Type,
     pub field_as: Option<String>,
+    pub default: Option<Loc<Value<'input>>>,
+    pub options: Vec<Loc<OptionDecl<'input>>>,
 }
 
 /// A file.
@@ -145,7 +157,7 @@ pub enum Name {
 ///
 /// ```ignore
 /// /// <comment>
-/// interface <name> {
+/// interface <name><<type_parameters>> {
 ///   <members>
 ///   <sub_types>
 /// }
@@ -154,6 +166,7 @@ pub enum Name {
 pub struct InterfaceBody<'input> {
     pub name: &'input str,
     pub comment: Vec<&'input str>,
+    pub type_parameters: Vec<Loc<&'input str>>,
     pub members: Vec<Loc<Member<'input>>>,
     pub sub_types: Vec<Loc<SubType<'input>>>,
 }
@@ -281,7 +294,7 @@ pub struct SubType<'input> {
 ///
 /// ```ignore
 /// /// <comment>
-/// tuple <name> {
+/// tuple <name><<type_parameters>> {
 ///     <members>
 /// }
 /// ```
@@ -289,6 +302,7 @@ pub struct SubType<'input> {
 pub struct TupleBody<'input> {
     pub name: &'input str,
     pub comment: Vec<&'input str>,
+    pub type_parameters: Vec<Loc<&'input str>>,
     pub members: Vec<Loc<Member<'input>>>,
 }
 
@@ -296,7 +310,7 @@ pub struct TupleBody<'input> {
 ///
 /// ```ignore
 /// /// <comment>
-/// type <name> {
+/// type <name><<type_parameters>> {
 ///     <members>
 /// }
 /// ```
@@ -304,6 +318,7 @@ pub struct TupleBody<'input> {
 pub struct TypeBody<'input> {
     pub name: &'input str,
     pub comment: Vec<&'input str>,
+    pub type_parameters: Vec<Loc<&'input str>>,
     pub members: Vec<Loc<Member<'input>>>,
 }
 
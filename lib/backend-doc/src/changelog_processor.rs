@@ -0,0 +1,197 @@
+//! Processor for the changelog of a package.
+//!
+//! Consecutive versions of the package are compared, and the changes between them are listed
+//! with the newest version first.
+
+use backend::Environment;
+use backend::errors::*;
+use core::{ErrorPos, RpFile, RpPackage, RpVersionedPackage};
+use doc_builder::DocBuilder;
+use escape::Escape;
+use macros::FormatAttribute;
+use processor::Processor;
+use semck;
+use std::io::Read;
+
+/// Name of the file that the changelog of a package is written to.
+pub const CHANGELOG_HTML: &str = "changelog.html";
+
+pub struct Data<'a> {
+    pub package: &'a RpPackage,
+    /// All versions of the package, oldest first.
+    pub versions: Vec<(&'a RpVersionedPackage, &'a RpFile)>,
+}
+
+define_processor!(ChangelogProcessor, Data<'env>, self,
+    process => {
+        self.write_doc(|| {
+            html!(self, section {class => "section-content"} => {
+                html!(self, h1 {class => "section-title"} => {
+                    html!(self, span {class => "kind"} ~ "changelog");
+                    html!(self, span {class => "name-package"} ~
+                          Escape(self.body.package.to_string().as_str()));
+                });
+
+                let versions = &self.body.versions;
+
+                for (i, &(package, file)) in versions.iter().enumerate().rev() {
+                    let previous = match i {
+                        0 => None,
+                        i => Some(versions[i - 1]),
+                    };
+
+                    self.version(package, file, previous)?;
+                }
+            });
+
+            Ok(())
+        })
+    };
+);
+
+impl<'env> ChangelogProcessor<'env> {
+    /// Write the changes made in a version, since the previous one.
+    fn version(
+        &self,
+        package: &RpVersionedPackage,
+        file: &RpFile,
+        previous: Option<(&RpVersionedPackage, &RpFile)>,
+    ) -> Result<()> {
+        html!(self, h2 {class => "changelog-version"} => {
+            let package_url = self.package_url(package);
+            html!(self, a {href => package_url} ~ Escape(package.to_string().as_str()));
+
+            if let Some((from, _)) = previous {
+                let from_url = self.package_url(from);
+                html!(self, span {class => "changelog-since"} => {
+                    html!(self, span {} ~ "since");
+                    html!(self, a {href => from_url} ~ Escape(from.to_string().as_str()));
+                });
+            }
+        });
+
+        let from_file = match previous {
+            Some((_, from_file)) => from_file,
+            None => {
+                html!(self, p {class => "missing-doc"} ~ "initial version");
+                return Ok(());
+            }
+        };
+
+        let changes = semck::changes(from_file, file)?;
+
+        if changes.is_empty() {
+            html!(self, p {class => "missing-doc"} ~ "no changes");
+            return Ok(());
+        }
+
+        html!(self, ul {class => "changelog"} => {
+            for change in &changes {
+                let (message, pos) = change.describe();
+
+                html!(self, li {} => {
+                    html!(self, span {class => "change-kind"} ~ Escape(message.as_str()));
+                    html!(self, code {} ~ Escape(excerpt(pos)?.as_str()));
+                });
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// The first line of the source spanned by the given position.
+fn excerpt(pos: &ErrorPos) -> Result<String> {
+    let mut content = String::new();
+    pos.object.read()?.read_to_string(&mut content)?;
+
+    let excerpt = content
+        .get(pos.start..pos.end)
+        .and_then(|span| span.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or("");
+
+    Ok(excerpt.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{BytesObject, Object, Version};
+    use source::Sources;
+    use std::cell::RefCell;
+    use std::sync::Arc;
+    use syntect::highlighting::Theme;
+    use syntect::parsing::SyntaxSet;
+
+    fn object(content: &str) -> Box<Object> {
+        let bytes = Arc::new(content.as_bytes().to_vec());
+        Box::new(BytesObject::new("test".to_string(), bytes))
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let content = "type Foo {\n    a: string;\n}";
+
+        let pos = |start, end| ErrorPos {
+            object: object(content),
+            start: start,
+            end: end,
+        };
+
+        assert_eq!("a: string;", excerpt(&pos(10, content.len())).unwrap());
+        assert_eq!("type Foo {", excerpt(&pos(0, content.len())).unwrap());
+        assert_eq!("", excerpt(&pos(10, 11)).unwrap());
+        // positions outside of the source have no excerpt.
+        assert_eq!("", excerpt(&pos(0, content.len() + 1)).unwrap());
+    }
+
+    #[test]
+    fn test_changelog() {
+        let mut env = Environment::empty();
+        let package = RpPackage::parse("test");
+
+        let specs = vec![
+            ("1.0.0", "type Foo { a: string; }"),
+            ("1.1.0", "type Foo { a: string; b?: string; }"),
+            ("1.2.0", "type Foo { a: string; b?: string; }"),
+        ];
+
+        let mut packages = Vec::new();
+        let mut files = Vec::new();
+
+        for (version, spec) in specs {
+            let version = Version::parse(version).unwrap();
+            let versioned = RpVersionedPackage::new(package.clone(), Some(version));
+            files.push(env.load_str(spec, &versioned).unwrap());
+            packages.push(versioned);
+        }
+
+        let theme = Theme::default();
+        let syntax_set = SyntaxSet::new();
+        let sources = Sources::new();
+        let mut out = String::new();
+
+        ChangelogProcessor {
+            out: RefCell::new(DocBuilder::new(&mut out)),
+            env: &env,
+            syntax: (&theme, &syntax_set),
+            root: ".",
+            sources: &sources,
+            body: &Data {
+                package: &package,
+                versions: packages.iter().zip(files.iter()).collect(),
+            },
+        }.process()
+            .unwrap();
+
+        // versions are listed with the newest first.
+        let no_changes = out.find("no changes").expect("no changes");
+        let added = out.find("field added").expect("field added");
+        let initial = out.find("initial version").expect("initial version");
+
+        assert!(no_changes < added, "{}", out);
+        assert!(added < initial, "{}", out);
+        assert!(out.contains("<code>b?: string"), "{}", out);
+        assert!(out.contains("./test/1.1.0/index.html"), "{}", out);
+    }
+}
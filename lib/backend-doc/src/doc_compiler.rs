@@ -1,9 +1,10 @@
 //! Compiler for generating documentation.
 
-use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME};
+use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME, SEARCH_INDEX_NAME, SEARCH_JS_NAME};
 use backend::Environment;
 use backend::errors::*;
-use core::{ForEachLoc, RpDecl, RpFile, RpVersionedPackage};
+use changelog_processor::{CHANGELOG_HTML, ChangelogProcessor, Data as ChangelogData};
+use core::{ForEachLoc, RpDecl, RpFile, RpPackage, RpVersionedPackage};
 use doc_builder::DocBuilder;
 use enum_processor::EnumProcessor;
 use genco::IoFmt;
@@ -11,8 +12,12 @@ use index_processor::{Data as IndexData, IndexProcessor};
 use interface_processor::InterfaceProcessor;
 use package_processor::{Data as PackageData, PackageProcessor};
 use processor::Processor;
+use search::search_index;
 use service_processor::ServiceProcessor;
+use source::{SOURCE_HTML, Source, Sources};
+use source_processor::{Data as SourceData, SourceProcessor};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
@@ -23,11 +28,16 @@ use tuple_processor::TupleProcessor;
 use type_processor::TypeProcessor;
 
 const NORMALIZE_CSS: &[u8] = include_bytes!("static/normalize.css");
+const SEARCH_JS: &[u8] = include_bytes!("static/search.js");
 
 pub struct DocCompiler<'a> {
     pub env: Environment,
     pub out_path: PathBuf,
     pub skip_static: bool,
+    /// Embed the source of specifications.
+    pub source: bool,
+    /// Build changelogs from all versions of packages.
+    pub changelog: bool,
     pub theme_css: &'a [u8],
     pub syntax_theme: &'a Theme,
     pub syntax_set: &'a SyntaxSet,
@@ -36,27 +46,61 @@ pub struct DocCompiler<'a> {
 impl<'a> DocCompiler<'a> {
     /// Do the compilation.
     pub fn compile(&self) -> Result<()> {
+        let sources = self.read_sources()?;
+
         for (_, file) in self.env.for_each_file() {
             file.for_each_decl().for_each_loc(
-                |decl| self.process_decl(decl),
+                |decl| self.process_decl(&sources, decl),
             )?;
         }
 
-        self.write_index(self.env.for_each_file())?;
+        self.write_index(&sources, self.env.for_each_file())?;
 
         for (package, file) in self.env.for_each_file() {
-            self.write_package(package, file)?;
+            self.write_package(&sources, package, file)?;
+        }
+
+        for (package, source) in &sources {
+            self.write_source(&sources, package, source)?;
+        }
+
+        if self.changelog {
+            self.write_changelogs(&sources)?;
         }
 
+        self.write_search_index()?;
+
         if !self.skip_static {
             self.write_stylesheets()?;
+            self.write_scripts()?;
         }
 
         Ok(())
     }
 
+    /// Read the sources of all files, unless sources should not be embedded.
+    fn read_sources(&self) -> Result<Sources> {
+        let mut sources = Sources::new();
+
+        if !self.source {
+            return Ok(sources);
+        }
+
+        for (package, file) in self.env.for_each_file() {
+            // the object that a file was loaded from is only known through its declarations.
+            let object = match file.decls.iter().next() {
+                Some(decl) => decl.pos().object.clone(),
+                None => continue,
+            };
+
+            sources.insert(package.clone(), Source::read(&**object)?);
+        }
+
+        Ok(sources)
+    }
+
     /// Process a single declaration.
-    fn process_decl(&self, decl: &RpDecl) -> Result<()> {
+    fn process_decl(&self, sources: &Sources, decl: &RpDecl) -> Result<()> {
         use self::RpDecl::*;
 
         let package = decl.name().package.clone().as_package(|v| v.to_string());
@@ -93,6 +137,7 @@ impl<'a> DocCompiler<'a> {
                     env: &self.env,
                     syntax: (self.syntax_theme, self.syntax_set),
                     root: &root,
+                    sources: sources,
                     body: body,
                 }.process()
             }
@@ -102,6 +147,7 @@ impl<'a> DocCompiler<'a> {
                     env: &self.env,
                     syntax: (self.syntax_theme, self.syntax_set),
                     root: &root,
+                    sources: sources,
                     body: body,
                 }.process()
             }
@@ -111,6 +157,7 @@ impl<'a> DocCompiler<'a> {
                     env: &self.env,
                     syntax: (self.syntax_theme, self.syntax_set),
                     root: &root,
+                    sources: sources,
                     body: body,
                 }.process()
             }
@@ -120,6 +167,7 @@ impl<'a> DocCompiler<'a> {
                     env: &self.env,
                     syntax: (self.syntax_theme, self.syntax_set),
                     root: &root,
+                    sources: sources,
                     body: body,
                 }.process()
             }
@@ -129,6 +177,7 @@ impl<'a> DocCompiler<'a> {
                     env: &self.env,
                     syntax: (self.syntax_theme, self.syntax_set),
                     root: &root,
+                    sources: sources,
                     body: body,
                 }.process()
             }
@@ -157,8 +206,42 @@ impl<'a> DocCompiler<'a> {
         Ok(())
     }
 
+    /// Write scripts.
+    fn write_scripts(&self) -> Result<()> {
+        if !self.out_path.is_dir() {
+            debug!("+dir: {}", self.out_path.display());
+            fs::create_dir_all(&self.out_path)?;
+        }
+
+        let search_js = self.out_path.join(SEARCH_JS_NAME);
+
+        debug!("+js: {}", search_js.display());
+        let mut f = fs::File::create(search_js)?;
+        f.write_all(SEARCH_JS)?;
+
+        Ok(())
+    }
+
+    /// Write the search index of all declarations.
+    fn write_search_index(&self) -> Result<()> {
+        let index = search_index(self.env.for_each_file(), decl_url)?;
+
+        let search_index_js = self.out_path.join(SEARCH_INDEX_NAME);
+
+        debug!("+js: {}", search_index_js.display());
+        let mut f = fs::File::create(search_index_js)?;
+        f.write_all(index.as_bytes())?;
+
+        Ok(())
+    }
+
     /// Write the package index file index file.
-    fn write_package(&self, package: &RpVersionedPackage, file: &RpFile) -> Result<()> {
+    fn write_package(
+        &self,
+        sources: &Sources,
+        package: &RpVersionedPackage,
+        file: &RpFile,
+    ) -> Result<()> {
         let mut path = self.out_path.to_owned();
 
         let mut root = Vec::new();
@@ -176,9 +259,11 @@ impl<'a> DocCompiler<'a> {
             env: &self.env,
             syntax: (self.syntax_theme, self.syntax_set),
             root: &root.join("/"),
+            sources: sources,
             body: &PackageData {
                 package: package,
                 file: file,
+                changelog: self.changelog && package.version.is_some(),
             },
         }.process()?;
 
@@ -186,8 +271,95 @@ impl<'a> DocCompiler<'a> {
         Ok(())
     }
 
+    /// Write the source of a package.
+    fn write_source(
+        &self,
+        sources: &Sources,
+        package: &RpVersionedPackage,
+        source: &Source,
+    ) -> Result<()> {
+        let mut path = self.out_path.to_owned();
+
+        let mut root = Vec::new();
+
+        for part in package.as_package(|v| v.to_string()).parts {
+            root.push("..");
+            path = path.join(part);
+        }
+
+        let source_html = path.join(SOURCE_HTML);
+        let mut f = File::create(&source_html)?;
+
+        SourceProcessor {
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            env: &self.env,
+            syntax: (self.syntax_theme, self.syntax_set),
+            root: &root.join("/"),
+            sources: sources,
+            body: &SourceData {
+                package: package,
+                source: source,
+            },
+        }.process()?;
+
+        debug!("+file: {}", source_html.display());
+        Ok(())
+    }
+
+    /// Write the changelogs of all versioned packages.
+    fn write_changelogs(&self, sources: &Sources) -> Result<()> {
+        let mut packages: BTreeMap<&RpPackage, Vec<(&RpVersionedPackage, &RpFile)>> =
+            BTreeMap::new();
+
+        // files are iterated in order of version, so versions end up oldest first.
+        for (package, file) in self.env.for_each_file() {
+            if package.version.is_none() {
+                continue;
+            }
+
+            packages.entry(&package.package).or_insert_with(Vec::new).push(
+                (package, file),
+            );
+        }
+
+        for (package, versions) in packages {
+            let mut path = self.out_path.to_owned();
+
+            let mut root = Vec::new();
+
+            for part in &package.parts {
+                root.push("..");
+                path = path.join(part);
+            }
+
+            if !path.is_dir() {
+                debug!("+dir: {}", path.display());
+                fs::create_dir_all(&path)?;
+            }
+
+            let changelog_html = path.join(CHANGELOG_HTML);
+            let mut f = File::create(&changelog_html)?;
+
+            ChangelogProcessor {
+                out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+                env: &self.env,
+                syntax: (self.syntax_theme, self.syntax_set),
+                root: &root.join("/"),
+                sources: sources,
+                body: &ChangelogData {
+                    package: package,
+                    versions: versions,
+                },
+            }.process()?;
+
+            debug!("+file: {}", changelog_html.display());
+        }
+
+        Ok(())
+    }
+
     /// Write the root index file.
-    fn write_index<'it, I>(&self, entries: I) -> Result<()>
+    fn write_index<'it, I>(&self, sources: &Sources, entries: I) -> Result<()>
     where
         I: IntoIterator<Item = (&'it RpVersionedPackage, &'it RpFile)>,
     {
@@ -201,6 +373,7 @@ impl<'a> DocCompiler<'a> {
             env: &self.env,
             syntax: (self.syntax_theme, self.syntax_set),
             root: &".",
+            sources: sources,
             body: &IndexData { entries: entries },
         }.process()?;
 
@@ -208,3 +381,10 @@ impl<'a> DocCompiler<'a> {
         Ok(())
     }
 }
+
+/// URL of the page of a declaration, relative to the root of the documentation.
+fn decl_url(decl: &RpDecl) -> String {
+    let mut parts = decl.name().package.clone().as_package(|v| v.to_string()).parts;
+    parts.push(format!("{}.{}.html", decl.kind(), decl.name().parts.join(".")));
+    parts.join("/")
+}
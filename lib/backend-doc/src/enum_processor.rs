@@ -2,21 +2,20 @@
 
 use backend::Environment;
 use backend::errors::*;
-use core::{Loc, RpEnumBody, RpVariant};
+use core::{Loc, RpEnumBody};
 use doc_builder::DocBuilder;
-use escape::Escape;
 use macros::FormatAttribute;
 use processor::Processor;
-use std::rc::Rc;
 
-define_processor!(EnumProcessor, RpEnumBody, self,
+define_processor!(EnumProcessor, Loc<RpEnumBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
 
             html!(self, section {id => &id, class => "section-content section-enum"} => {
-                self.section_title("enum", &self.body.name)?;
+                self.section_title("enum", &self.body.name, self.body.pos())?;
                 self.doc(&self.body.comment)?;
+                self.example(&self.body.name)?;
                 self.variants(self.body.variants.iter())?;
                 self.nested_decls(self.body.decls.iter())?;
             });
@@ -28,31 +27,4 @@ define_processor!(EnumProcessor, RpEnumBody, self,
     current_package => &self.body.name.package;
 );
 
-impl<'p> EnumProcessor<'p> {
-    fn variants<'b, I>(&self, variants: I) -> Result<()>
-    where
-        I: IntoIterator<Item = &'b Rc<Loc<RpVariant>>>,
-    {
-        let mut it = variants.into_iter().peekable();
-
-        if it.peek().is_none() {
-            return Ok(());
-        }
-
-        for variant in it {
-            let id = variant.name.join("_");
-
-            html!(self, h3 {id => id} => {
-                html!(self, span {class => "kind"} ~ "variant");
-                self.full_name_without_package(&variant.name)?;
-                html!(self, span {class => "keyword"} ~ "as");
-                html!(self, span {class => "variant-ordinal"} ~
-                      Escape(format!("\"{}\"", variant.ordinal()).as_str()));
-            });
-
-            self.doc(&variant.comment)?;
-        }
-
-        Ok(())
-    }
-}
+impl<'p> EnumProcessor<'p> {}
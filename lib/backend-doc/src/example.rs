@@ -0,0 +1,500 @@
+//! Synthesizing and validating JSON examples of declarations.
+//!
+//! Examples are synthesized from the model, unless the declaration provides one through the
+//! `example` option, in which case it is parsed and validated against the declaration.
+
+use backend::Environment;
+use backend::errors::*;
+use core::{Loc, RpField, RpInterfaceBody, RpOrdinalValue, RpReg, RpSubType, RpType, RpVariant};
+use serde_json::{self, Map, Value};
+
+/// Name of the property holding the name of a sub-type.
+const TYPE_PROPERTY: &'static str = "type";
+
+/// How deep declarations are expanded, to avoid expanding recursive declarations forever.
+///
+/// Past this depth, optional fields are left out and declarations are represented by `null`.
+const MAX_DEPTH: usize = 4;
+
+pub struct Examples<'env> {
+    env: &'env Environment,
+}
+
+impl<'env> Examples<'env> {
+    pub fn new(env: &'env Environment) -> Examples<'env> {
+        Examples { env: env }
+    }
+
+    /// Build an example of the given registered declaration.
+    pub fn example(&self, reg: &RpReg) -> Result<Value> {
+        self.reg(reg, 0)
+    }
+
+    /// Parse and validate the `example` option of the given declaration, if present.
+    fn provided(&self, reg: &RpReg) -> Result<Option<Value>> {
+        let example = match provided_example(reg) {
+            Some(example) => example,
+            None => return Ok(None),
+        };
+
+        let value: Value = serde_json::from_str(example.as_str()).map_err(|e| {
+            ErrorKind::Pos(format!("bad example: {}", e), example.pos().into())
+        })?;
+
+        if let Err(e) = self.check_reg(reg, &value, "$") {
+            return Err(
+                ErrorKind::Pos(format!("bad example: {}", e), example.pos().into()).into(),
+            );
+        }
+
+        Ok(Some(value))
+    }
+
+    fn reg(&self, reg: &RpReg, depth: usize) -> Result<Value> {
+        use self::RpReg::*;
+
+        if let Some(value) = self.provided(reg)? {
+            return Ok(value);
+        }
+
+        let value = match *reg {
+            Type(ref body) => Value::Object(self.fields(body.fields.iter(), depth)?),
+            Tuple(ref body) => {
+                let mut values = Vec::new();
+
+                for field in &body.fields {
+                    values.push(self.ty(&field.ty, depth)?);
+                }
+
+                Value::Array(values)
+            }
+            Interface(ref body) => {
+                match body.sub_types.values().next() {
+                    Some(sub_type) => self.sub_type(body, sub_type, depth)?,
+                    None => Value::Object(self.fields(body.fields.iter(), depth)?),
+                }
+            }
+            SubType(ref body, ref sub_type) => self.sub_type(body, sub_type, depth)?,
+            Enum(ref body) => {
+                match body.variants.iter().next() {
+                    Some(variant) => ordinal(variant),
+                    None => Value::Null,
+                }
+            }
+            EnumVariant(_, ref variant) => ordinal(variant),
+            Service(ref body) => {
+                return Err(format!("no example for service: {}", body.name).into());
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn sub_type(
+        &self,
+        body: &RpInterfaceBody,
+        sub_type: &RpSubType,
+        depth: usize,
+    ) -> Result<Value> {
+        let mut object = Map::new();
+
+        object.insert(
+            TYPE_PROPERTY.to_string(),
+            Value::String(sub_type.name().to_string()),
+        );
+
+        let fields = body.fields.iter().chain(sub_type.fields.iter());
+        object.extend(self.fields(fields, depth)?);
+        Ok(Value::Object(object))
+    }
+
+    fn fields<'a, I>(&self, fields: I, depth: usize) -> Result<Map<String, Value>>
+    where
+        I: IntoIterator<Item = &'a Loc<RpField>>,
+    {
+        let mut object = Map::new();
+
+        for field in fields {
+            if field.is_optional() && depth >= MAX_DEPTH {
+                continue;
+            }
+
+            object.insert(field.name().to_string(), self.ty(&field.ty, depth)?);
+        }
+
+        Ok(object)
+    }
+
+    fn ty(&self, ty: &RpType, depth: usize) -> Result<Value> {
+        let value = match *ty {
+            RpType::Double | RpType::Float => Value::from(3.14),
+            RpType::Signed { .. } => Value::from(-42),
+            RpType::Unsigned { .. } => Value::from(42),
+            RpType::Boolean => Value::Bool(true),
+            RpType::String => Value::String("string".to_string()),
+            RpType::DateTime => Value::String("2017-01-01T00:00:00Z".to_string()),
+            RpType::Bytes => Value::String("Ynl0ZXM=".to_string()),
+            RpType::Any => Value::Object(Map::new()),
+            // type parameters are not substituted.
+            RpType::Generic { .. } => Value::Null,
+            RpType::Array { ref inner } => Value::Array(vec![self.ty(inner, depth)?]),
+            RpType::Map { ref key, ref value } => {
+                let key = match self.ty(key, depth)? {
+                    Value::String(string) => string,
+                    Value::Null => "key".to_string(),
+                    other => other.to_string(),
+                };
+
+                let mut object = Map::new();
+                object.insert(key, self.ty(value, depth)?);
+                Value::Object(object)
+            }
+            RpType::Name { ref name, .. } => {
+                if depth >= MAX_DEPTH {
+                    return Ok(Value::Null);
+                }
+
+                let reg = self.env.lookup(name)?;
+                self.reg(reg, depth + 1)?
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Check that the given value is a valid instance of the registered declaration.
+    fn check_reg(&self, reg: &RpReg, value: &Value, path: &str) -> Result<()> {
+        use self::RpReg::*;
+
+        match *reg {
+            Type(ref body) => self.check_fields(body.fields.iter(), value, path, None),
+            Tuple(ref body) => {
+                let values = value.as_array().ok_or_else(
+                    || format!("{}: expected array", path),
+                )?;
+
+                if values.len() != body.fields.len() {
+                    return Err(
+                        format!("{}: expected array of {} items", path, body.fields.len()).into(),
+                    );
+                }
+
+                for (i, (field, value)) in body.fields.iter().zip(values).enumerate() {
+                    self.check_ty(&field.ty, value, &format!("{}[{}]", path, i))?;
+                }
+
+                Ok(())
+            }
+            Interface(ref body) => {
+                let name = value
+                    .get(TYPE_PROPERTY)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("{}: expected object with `{}`", path, TYPE_PROPERTY))?;
+
+                let sub_type = body.sub_types
+                    .values()
+                    .find(|s| s.names.iter().any(|n| n.as_str() == name))
+                    .ok_or_else(|| format!("{}: no such sub-type: {}", path, name))?;
+
+                self.check_sub_type(body, sub_type, value, path)
+            }
+            SubType(ref body, ref sub_type) => self.check_sub_type(body, sub_type, value, path),
+            Enum(ref body) => {
+                if body.variants.iter().any(|v| ordinal(v) == *value) {
+                    return Ok(());
+                }
+
+                Err(format!("{}: not a variant of {}", path, body.name).into())
+            }
+            EnumVariant(_, ref variant) => {
+                if ordinal(variant) == *value {
+                    return Ok(());
+                }
+
+                Err(format!("{}: expected {}", path, ordinal(variant)).into())
+            }
+            Service(ref body) => {
+                Err(format!("{}: service {} is not a type", path, body.name).into())
+            }
+        }
+    }
+
+    fn check_sub_type(
+        &self,
+        body: &RpInterfaceBody,
+        sub_type: &RpSubType,
+        value: &Value,
+        path: &str,
+    ) -> Result<()> {
+        let name = value.get(TYPE_PROPERTY).and_then(Value::as_str);
+
+        if !sub_type.names.iter().any(|n| Some(n.as_str()) == name) {
+            return Err(
+                format!(
+                    "{}: expected `{}` to be {:?}",
+                    path,
+                    TYPE_PROPERTY,
+                    sub_type.name()
+                ).into(),
+            );
+        }
+
+        let fields = body.fields.iter().chain(sub_type.fields.iter());
+        self.check_fields(fields, value, path, Some(TYPE_PROPERTY))
+    }
+
+    /// Check that the value is an object with the given fields, and no other properties than the
+    /// fields and the `ignore`d one.
+    fn check_fields<'a, I>(
+        &self,
+        fields: I,
+        value: &Value,
+        path: &str,
+        ignore: Option<&str>,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Loc<RpField>>,
+    {
+        let object = value.as_object().ok_or_else(
+            || format!("{}: expected object", path),
+        )?;
+
+        let fields: Vec<_> = fields.into_iter().collect();
+
+        for key in object.keys() {
+            if Some(key.as_str()) == ignore {
+                continue;
+            }
+
+            if !fields.iter().any(|f| f.name() == key.as_str()) {
+                return Err(format!("{}: unknown field `{}`", path, key).into());
+            }
+        }
+
+        for field in fields {
+            let path = format!("{}.{}", path, field.name());
+
+            match object.get(field.name()) {
+                Some(&Value::Null) | None if field.is_optional() => {}
+                Some(value) => self.check_ty(&field.ty, value, &path)?,
+                None => return Err(format!("{}: missing required field", path).into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_ty(&self, ty: &RpType, value: &Value, path: &str) -> Result<()> {
+        let expected = match *ty {
+            RpType::Double | RpType::Float => {
+                if value.is_number() {
+                    return Ok(());
+                }
+
+                "number"
+            }
+            RpType::Signed { .. } => {
+                if value.is_i64() {
+                    return Ok(());
+                }
+
+                "signed integer"
+            }
+            RpType::Unsigned { .. } => {
+                if value.is_u64() {
+                    return Ok(());
+                }
+
+                "unsigned integer"
+            }
+            RpType::Boolean => {
+                if value.is_boolean() {
+                    return Ok(());
+                }
+
+                "boolean"
+            }
+            RpType::String | RpType::DateTime | RpType::Bytes => {
+                if value.is_string() {
+                    return Ok(());
+                }
+
+                "string"
+            }
+            RpType::Any | RpType::Generic { .. } => return Ok(()),
+            RpType::Array { ref inner } => {
+                let values = value.as_array().ok_or_else(
+                    || format!("{}: expected array", path),
+                )?;
+
+                for (i, value) in values.iter().enumerate() {
+                    self.check_ty(inner, value, &format!("{}[{}]", path, i))?;
+                }
+
+                return Ok(());
+            }
+            RpType::Map { value: ref inner, .. } => {
+                let object = value.as_object().ok_or_else(
+                    || format!("{}: expected object", path),
+                )?;
+
+                for (key, value) in object {
+                    self.check_ty(inner, value, &format!("{}.{}", path, key))?;
+                }
+
+                return Ok(());
+            }
+            RpType::Name { ref name, .. } => {
+                let reg = self.env.lookup(name)?;
+                return self.check_reg(reg, value, path);
+            }
+        };
+
+        Err(format!("{}: expected {}", path, expected).into())
+    }
+}
+
+/// The example provided through the `example` option of a declaration.
+fn provided_example(reg: &RpReg) -> Option<&Loc<String>> {
+    use self::RpReg::*;
+
+    match *reg {
+        Type(ref body) => body.example.as_ref(),
+        Tuple(ref body) => body.example.as_ref(),
+        Interface(ref body) => body.example.as_ref(),
+        SubType(_, ref sub_type) => sub_type.example.as_ref(),
+        Enum(ref body) => body.example.as_ref(),
+        EnumVariant(..) | Service(..) => None,
+    }
+}
+
+/// The JSON representation of an enum variant.
+fn ordinal(variant: &RpVariant) -> Value {
+    match variant.ordinal() {
+        RpOrdinalValue::String(string) => Value::String(string.to_string()),
+        RpOrdinalValue::Number(number) => {
+            serde_json::from_str(&number.to_string()).unwrap_or(Value::Null)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{RpName, RpPackage, RpVersionedPackage};
+
+    fn package() -> RpVersionedPackage {
+        RpVersionedPackage::new(RpPackage::parse("test"), None)
+    }
+
+    fn env(content: &str) -> Environment {
+        let mut env = Environment::empty();
+        env.import_str(content, package()).unwrap();
+        env
+    }
+
+    /// Build the example of the declaration with the given name.
+    fn example(content: &str, parts: &[&str]) -> Result<Value> {
+        let env = env(content);
+        let parts = parts.iter().map(|p| p.to_string()).collect();
+        let reg = env.lookup(&RpName::new(None, package(), parts))?;
+        Examples::new(&env).example(reg)
+    }
+
+    fn error(content: &str) -> String {
+        example(content, &["Foo"]).err().expect("expected error").to_string()
+    }
+
+    #[test]
+    fn test_field_as() {
+        let content = "type Foo { foo_bar: string as \"fooBar\"; other?: boolean; }";
+        let expected = json!({"fooBar": "string", "other": true});
+        assert_eq!(expected, example(content, &["Foo"]).unwrap());
+    }
+
+    #[test]
+    fn test_sub_type() {
+        let content = "interface Animal { name: string; Cat as \"cat\" { lives: u32; } Dog; }";
+        let cat = json!({"type": "cat", "name": "string", "lives": 42});
+
+        assert_eq!(cat, example(content, &["Animal", "Cat"]).unwrap());
+        let dog = json!({"type": "Dog", "name": "string"});
+        assert_eq!(dog, example(content, &["Animal", "Dog"]).unwrap());
+        // interfaces use their first sub-type.
+        assert_eq!(cat, example(content, &["Animal"]).unwrap());
+    }
+
+    #[test]
+    fn test_tuple() {
+        let content = "tuple Foo { a: string; b: u64; c: [Bar]; } type Bar { x: double; }";
+        let expected = json!(["string", 42, [{"x": 3.14}]]);
+        assert_eq!(expected, example(content, &["Foo"]).unwrap());
+    }
+
+    #[test]
+    fn test_enum_ordinals() {
+        let content = "enum Size as u32 { Small as 1; Large as 2; }\n\
+                       enum Color as string { Red as \"red\"; Blue as \"blue\"; }";
+
+        assert_eq!(json!(1), example(content, &["Size"]).unwrap());
+        assert_eq!(json!(2), example(content, &["Size", "Large"]).unwrap());
+        assert_eq!(json!("red"), example(content, &["Color"]).unwrap());
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let content = "type Node { value: string; next: Node; extra?: string; }";
+        let mut value = example(content, &["Node"]).unwrap();
+        let mut depth = 0;
+
+        while let Value::Object(mut object) = value {
+            assert_eq!(Some(&json!("string")), object.get("value"));
+            // optional fields are left out at the maximum depth.
+            assert_eq!(depth < MAX_DEPTH, object.contains_key("extra"));
+            value = object.remove("next").expect("next field");
+            depth += 1;
+        }
+
+        assert_eq!(Value::Null, value);
+        assert_eq!(MAX_DEPTH + 1, depth);
+    }
+
+    #[test]
+    fn test_provided() {
+        let content = r#"type Foo {
+            foo_bar: string as "fooBar";
+            baz?: u32;
+            option example = "{\"fooBar\": \"hello\"}";
+        }"#;
+
+        assert_eq!(json!({"fooBar": "hello"}), example(content, &["Foo"]).unwrap());
+    }
+
+    #[test]
+    fn test_provided_unknown_field() {
+        let content = r#"type Foo { a: string; option example = "{\"a\": \"x\", \"b\": 1}"; }"#;
+        assert_eq!("bad example: $: unknown field `b`", error(content));
+    }
+
+    #[test]
+    fn test_provided_wrong_type() {
+        let content = r#"type Foo { a: [u32]; option example = "{\"a\": [1, -2]}"; }"#;
+        assert_eq!("bad example: $.a[1]: expected unsigned integer", error(content));
+    }
+
+    #[test]
+    fn test_provided_missing_required() {
+        let content = r#"type Foo { a: string; b?: string; option example = "{\"b\": \"x\"}"; }"#;
+        assert_eq!("bad example: $.a: missing required field", error(content));
+    }
+
+    #[test]
+    fn test_provided_bad_sub_type() {
+        let content = r#"interface Foo {
+            option example = "{\"type\": \"other\"}";
+            Bar as "bar";
+        }"#;
+
+        assert_eq!("bad example: $: no such sub-type: other", error(content));
+    }
+}
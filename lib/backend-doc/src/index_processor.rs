@@ -18,6 +18,8 @@ define_processor!(IndexProcessor, Data<'env>, self,
             html!(self, section {class => "section-content"} => {
                 html!(self, h1 {class => "section-title"} ~ "Index");
 
+                self.search_box()?;
+
                 html!(self, h2 {class => "kind"} ~ "Packages");
 
                 html!(self, table {} => {
@@ -2,23 +2,27 @@
 
 use backend::Environment;
 use backend::errors::*;
-use core::{RpInterfaceBody, RpSubType};
+use core::{Loc, RpInterfaceBody};
 use doc_builder::DocBuilder;
 use macros::FormatAttribute;
 use processor::Processor;
 
-define_processor!(InterfaceProcessor, RpInterfaceBody, self,
+define_processor!(InterfaceProcessor, Loc<RpInterfaceBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
 
             html!(self, section {id => &id, class => "section-content section-interface"} => {
-                self.section_title("interface", &self.body.name)?;
+                self.section_title("interface", &self.body.name, self.body.pos())?;
 
                 self.doc(&self.body.comment)?;
 
+                if self.body.example.is_some() {
+                    self.example(&self.body.name)?;
+                }
+
                 for sub_type in self.body.sub_types.values() {
-                    self.sub_type(sub_type)?;
+                    self.sub_type(self.body, sub_type)?;
                 }
 
                 self.nested_decls(self.body.decls.iter())?;
@@ -31,20 +35,4 @@ define_processor!(InterfaceProcessor, RpInterfaceBody, self,
     current_package => &self.body.name.package;
 );
 
-impl<'p> InterfaceProcessor<'p> {
-    fn sub_type(&self, sub_type: &RpSubType) -> Result<()> {
-        let id = sub_type.name.join("_");
-
-        html!(self, h2 {id => id, class => "sub-type-title"} => {
-            html!(self, span {class => "kind"} ~ "subtype");
-            self.full_name_without_package(&sub_type.name)?;
-        });
-
-        self.doc(&self.body.comment)?;
-
-        let fields = self.body.fields.iter().chain(sub_type.fields.iter());
-        self.fields(fields)?;
-        self.nested_decls(sub_type.decls.iter())?;
-        Ok(())
-    }
-}
+impl<'p> InterfaceProcessor<'p> {}
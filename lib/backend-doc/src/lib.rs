@@ -5,26 +5,38 @@ extern crate genco;
 extern crate reproto_backend as backend;
 extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
+extern crate reproto_semck as semck;
 extern crate pulldown_cmark;
+#[macro_use]
+extern crate serde_json;
 extern crate syntect;
 
 #[macro_use]
 mod macros;
+mod changelog_processor;
 mod doc_builder;
 mod doc_compiler;
 mod escape;
+mod example;
 mod processor;
 mod service_processor;
 mod tuple_processor;
 mod type_processor;
 mod enum_processor;
 mod interface_processor;
+mod markdown_compiler;
+mod markdown_processor;
 mod index_processor;
 mod package_processor;
 mod rendering;
+mod search;
+mod source;
+mod source_processor;
 
 pub const NORMALIZE_CSS_NAME: &str = "normalize.css";
 pub const DOC_CSS_NAME: &str = "doc.css";
+pub const SEARCH_JS_NAME: &str = "search.js";
+pub const SEARCH_INDEX_NAME: &str = "search-index.js";
 pub const EXT: &str = "html";
 pub const INDEX: &str = "index";
 pub const DEFAULT_THEME: &str = "light";
@@ -33,6 +45,7 @@ pub const DEFAULT_SYNTAX_THEME: &str = "ayu-mirage";
 use self::backend::{App, Arg, ArgMatches, CompilerOptions, Environment, Options};
 use self::backend::errors::*;
 use self::doc_compiler::DocCompiler;
+use self::markdown_compiler::MarkdownCompiler;
 use manifest::{Lang, Manifest};
 use std::collections::HashMap;
 use syntect::dumps::from_binary;
@@ -94,6 +107,18 @@ pub fn shared_options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
             .help("List available syntax themes"),
     );
 
+    let out = out.arg(
+        Arg::with_name("format")
+            .long("format")
+            .takes_value(true)
+            .possible_values(&["html", "markdown"])
+            .help("Format of the documentation, html (default) or markdown"),
+    );
+
+    let out = out.arg(Arg::with_name("changelog").long("changelog").help(
+        "Load all published versions of packages from the index, and build their changelogs",
+    ));
+
     let out = out.arg(Arg::with_name("skip-static").long("skip-static").help(
         "Skip building \
          with static \
@@ -103,6 +128,15 @@ pub fn shared_options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
     out
 }
 
+/// Check that the given options can be used together.
+pub fn check_options(matches: &ArgMatches) -> Result<()> {
+    if matches.value_of("format") == Some("markdown") && matches.is_present("changelog") {
+        return Err("--changelog is not supported with --format markdown".into());
+    }
+
+    Ok(())
+}
+
 pub fn compile_options<'a, 'b>(out: App<'a, 'b>) -> App<'a, 'b> {
     shared_options(out).about("Compile Documentation")
 }
@@ -204,6 +238,8 @@ pub fn compile<L>(
 where
     L: Lang,
 {
+    check_options(matches)?;
+
     let themes = build_themes();
 
     let mut done = false;
@@ -223,10 +259,28 @@ where
         return Ok(());
     }
 
-    let skip_static = matches.is_present("skip-static");
-
     let out = compiler_options.out_path.clone();
 
+    if matches.value_of("format") == Some("markdown") {
+        with_initialized(matches, manifest, &themes, move |syntax_theme, syntax_set, _| {
+            let compiler = MarkdownCompiler {
+                env: env,
+                out_path: compiler_options.out_path,
+                syntax_theme: syntax_theme,
+                syntax_set: syntax_set,
+            };
+
+            compiler.compile()
+        })?;
+
+        println!("Wrote documentation in: {}", out.display());
+        return Ok(());
+    }
+
+    let skip_static = matches.is_present("skip-static");
+    let changelog = matches.is_present("changelog");
+    let source = manifest.doc.source.unwrap_or(true);
+
     with_initialized(matches, manifest, &themes, move |syntax_theme,
           syntax_set,
           theme_css| {
@@ -234,6 +288,8 @@ where
             env: env,
             out_path: compiler_options.out_path,
             skip_static: skip_static,
+            source: source,
+            changelog: changelog,
             theme_css: theme_css,
             syntax_theme: syntax_theme,
             syntax_set: syntax_set,
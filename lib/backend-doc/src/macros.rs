@@ -43,6 +43,7 @@ macro_rules! define_processor {
             pub env: &'env Environment,
             pub syntax: (&'env ::syntect::highlighting::Theme, &'env ::syntect::parsing::SyntaxSet),
             pub root: &'env str,
+            pub sources: &'env ::source::Sources,
             pub body: &'env $body,
         }
 
@@ -59,6 +60,10 @@ macro_rules! define_processor {
                 self.root
             }
 
+            fn sources(&self) -> &'env ::source::Sources {
+                self.sources
+            }
+
             fn syntax(&self) -> (&'env ::syntect::highlighting::Theme, &'env ::syntect::parsing::SyntaxSet) {
                 self.syntax
             }
@@ -85,6 +90,12 @@ macro_rules! define_processor {
         define_processor!(@tail $slf $($tail)*);
     );
 
+    (@tail $slf:ident overrides => {$($item:tt)*}; $($tail:tt)*) => (
+        $($item)*
+
+        define_processor!(@tail $slf $($tail)*);
+    );
+
     (@tail $slf:ident) => ();
 }
 
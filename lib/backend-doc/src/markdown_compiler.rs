@@ -0,0 +1,91 @@
+//! Compiler for generating documentation as Markdown.
+//!
+//! One file is written per package, in which declarations link to each other.
+
+use backend::Environment;
+use backend::errors::*;
+use core::{RpFile, RpVersionedPackage};
+use doc_builder::DocBuilder;
+use genco::IoFmt;
+use index_processor::Data as IndexData;
+use markdown_processor::{Data as MarkdownData, MarkdownIndexProcessor, MarkdownProcessor,
+                         package_file};
+use processor::Processor;
+use source::Sources;
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use syntect::highlighting::Theme;
+use syntect::parsing::SyntaxSet;
+
+pub struct MarkdownCompiler<'a> {
+    pub env: Environment,
+    pub out_path: PathBuf,
+    pub syntax_theme: &'a Theme,
+    pub syntax_set: &'a SyntaxSet,
+}
+
+impl<'a> MarkdownCompiler<'a> {
+    /// Do the compilation.
+    pub fn compile(&self) -> Result<()> {
+        if !self.out_path.is_dir() {
+            debug!("+dir: {}", self.out_path.display());
+            fs::create_dir_all(&self.out_path)?;
+        }
+
+        // sources are not embedded in markdown.
+        let sources = Sources::new();
+
+        self.write_index(&sources)?;
+
+        for (package, file) in self.env.for_each_file() {
+            self.write_package(&sources, package, file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the index of all packages.
+    fn write_index(&self, sources: &Sources) -> Result<()> {
+        let index_md = self.out_path.join("index.md");
+        let mut f = File::create(&index_md)?;
+
+        MarkdownIndexProcessor {
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            env: &self.env,
+            syntax: (self.syntax_theme, self.syntax_set),
+            root: &".",
+            sources: sources,
+            body: &IndexData { entries: self.env.for_each_file().collect() },
+        }.process()?;
+
+        debug!("+file: {}", index_md.display());
+        Ok(())
+    }
+
+    /// Write the documentation of a single package.
+    fn write_package(
+        &self,
+        sources: &Sources,
+        package: &RpVersionedPackage,
+        file: &RpFile,
+    ) -> Result<()> {
+        let package_md = self.out_path.join(package_file(package));
+        let mut f = File::create(&package_md)?;
+
+        MarkdownProcessor {
+            out: RefCell::new(DocBuilder::new(&mut IoFmt(&mut f))),
+            env: &self.env,
+            syntax: (self.syntax_theme, self.syntax_set),
+            root: &".",
+            sources: sources,
+            body: &MarkdownData {
+                package: package,
+                file: file,
+            },
+        }.process()?;
+
+        debug!("+file: {}", package_md.display());
+        Ok(())
+    }
+}
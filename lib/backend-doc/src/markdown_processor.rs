@@ -0,0 +1,450 @@
+//! Processors for generating documentation as Markdown.
+//!
+//! Declarations are walked through `Processor::decl_body`, which renders the same parts as the
+//! HTML processors of each declaration, but each part of them is rendered as Markdown. Doc
+//! comments are already Markdown, and are included as-is.
+
+use backend::Environment;
+use backend::errors::*;
+use core::{Loc, RpChannel, RpDecl, RpEndpoint, RpField, RpFile, RpInterfaceBody, RpName,
+           RpServiceBody, RpSubType, RpType, RpVariant, RpVersionedPackage, WithPos};
+use doc_builder::DocBuilder;
+use index_processor::Data as IndexData;
+use processor::Processor;
+use std::rc::Rc;
+
+/// Name of the file for the package without a name.
+const DEFAULT_PACKAGE: &str = "default";
+
+pub struct Data<'a> {
+    pub package: &'a RpVersionedPackage,
+    pub file: &'a RpFile,
+}
+
+define_processor!(MarkdownIndexProcessor, IndexData<'env>, self,
+    process => {
+        writeln!(self.out(), "# Packages")?;
+        writeln!(self.out(), "")?;
+
+        for (package, file) in self.body.entries.iter().cloned() {
+            write!(self.out(), "* [`{}`]({})", package, package_file(package))?;
+
+            if let Some(line) = first_line(&file.comment) {
+                write!(self.out(), " &mdash; {}", line)?;
+            }
+
+            writeln!(self.out(), "")?;
+        }
+
+        Ok(())
+    };
+);
+
+impl<'env> MarkdownIndexProcessor<'env> {}
+
+define_processor!(MarkdownProcessor, Data<'env>, self,
+    process => {
+        writeln!(self.out(), "# Package `{}`", self.body.package)?;
+        writeln!(self.out(), "")?;
+
+        self.doc(&self.body.file.comment)?;
+
+        for decl in self.body.file.for_each_decl() {
+            let name = decl.name();
+            let url = self.type_url(name)?;
+            writeln!(self.out(), "* {} [`{}`]({})", decl.kind(), name.parts.join("::"), url)?;
+        }
+
+        writeln!(self.out(), "")?;
+
+        for decl in self.body.file.for_each_decl() {
+            let name = decl.name();
+
+            writeln!(self.out(), "{}", anchor(name))?;
+            writeln!(self.out(), "## {} `{}`", decl.kind(), name.parts.join("::"))?;
+            writeln!(self.out(), "")?;
+
+            self.decl_body(decl.value()).with_pos(decl.pos())?;
+        }
+
+        Ok(())
+    };
+
+    current_package => self.body.package;
+
+    overrides => {
+        /// Declarations are linked to by their anchor in the file of their package.
+        fn type_url(&self, name: &RpName) -> Result<String> {
+            Ok(format!("{}#{}", package_file(&name.package), Self::anchor(name)))
+        }
+
+        fn doc<'a, I>(&self, comment: I) -> Result<()>
+        where
+            I: IntoIterator<Item = &'a String>,
+        {
+            let comment = comment.into_iter().map(String::as_str).collect::<Vec<_>>();
+
+            if comment.is_empty() {
+                return Ok(());
+            }
+
+            writeln!(self.out(), "{}", comment.join("\n"))?;
+            writeln!(self.out(), "")?;
+            Ok(())
+        }
+
+        /// Write a code-fenced example of the given declaration.
+        fn example(&self, name: &RpName) -> Result<()> {
+            let example = self.example_json(name)?;
+
+            writeln!(self.out(), "```json")?;
+            writeln!(self.out(), "{}", example)?;
+            writeln!(self.out(), "```")?;
+            writeln!(self.out(), "")?;
+            Ok(())
+        }
+
+        /// Write a table of the given fields.
+        fn fields<'b, I>(&self, fields: I) -> Result<()>
+        where
+            I: Iterator<Item = &'b Loc<RpField>>,
+        {
+            let mut it = fields.peekable();
+
+            if it.peek().is_none() {
+                return Ok(());
+            }
+
+            writeln!(self.out(), "| Field | Type | Required | Description |")?;
+            writeln!(self.out(), "|-------|------|----------|-------------|")?;
+
+            for field in it {
+                let name = if field.ident() != field.name() {
+                    format!("`{}` as `{}`", field.ident(), field.name())
+                } else {
+                    format!("`{}`", field.name())
+                };
+
+                let ty = self.ty(&field.ty).with_pos(field.pos())?;
+                let required = if field.is_optional() { "no" } else { "yes" };
+
+                writeln!(
+                    self.out(),
+                    "| {} | {} | {} | {} |",
+                    name,
+                    ty,
+                    required,
+                    cell(&field.comment)
+                )?;
+            }
+
+            writeln!(self.out(), "")?;
+            Ok(())
+        }
+
+        fn sub_type(&self, body: &RpInterfaceBody, sub_type: &Loc<RpSubType>) -> Result<()> {
+            writeln!(self.out(), "{}", anchor(&sub_type.name))?;
+            writeln!(self.out(), "### subtype `{}`", sub_type.name.parts.join("::"))?;
+            writeln!(self.out(), "")?;
+
+            self.doc(&sub_type.comment)?;
+            self.example(&sub_type.name)?;
+
+            let fields = body.fields.iter().chain(sub_type.fields.iter());
+            self.fields(fields)
+        }
+
+        /// Write a table of the given variants.
+        fn variants<'b, I>(&self, variants: I) -> Result<()>
+        where
+            I: IntoIterator<Item = &'b Rc<Loc<RpVariant>>>,
+        {
+            let mut it = variants.into_iter().peekable();
+
+            if it.peek().is_none() {
+                return Ok(());
+            }
+
+            writeln!(self.out(), "| Variant | Value | Description |")?;
+            writeln!(self.out(), "|---------|-------|-------------|")?;
+
+            for variant in it {
+                writeln!(
+                    self.out(),
+                    "| {}`{}` | `{}` | {} |",
+                    anchor(&variant.name),
+                    variant.local_name.as_str(),
+                    variant.ordinal(),
+                    cell(&variant.comment)
+                )?;
+            }
+
+            writeln!(self.out(), "")?;
+            Ok(())
+        }
+
+        fn endpoint(&self, body: &RpServiceBody, endpoint: &Loc<RpEndpoint>) -> Result<()> {
+            let id = Self::endpoint_anchor(body, endpoint);
+
+            writeln!(self.out(), "<a id=\"{}\"></a>", id)?;
+            writeln!(self.out(), "### endpoint `{}`", endpoint.id.as_str())?;
+            writeln!(self.out(), "")?;
+
+            if let Some(ref request) = endpoint.request {
+                let request = self.channel(request).with_pos(request.pos())?;
+                writeln!(self.out(), "**Request:** {}", request)?;
+                writeln!(self.out(), "")?;
+            }
+
+            if let Some(ref response) = endpoint.response {
+                let response = self.channel(response).with_pos(response.pos())?;
+                writeln!(self.out(), "**Response:** {}", response)?;
+                writeln!(self.out(), "")?;
+            }
+
+            if endpoint.id.as_str() != endpoint.name.as_str() {
+                writeln!(self.out(), "**Sent as:** `{}`", endpoint.name)?;
+                writeln!(self.out(), "")?;
+            }
+
+            self.doc(&endpoint.comment)
+        }
+
+        /// Nested declarations are documented in their own sections of the package.
+        fn nested_decls<'b, I>(&self, _: I) -> Result<()>
+        where
+            I: Iterator<Item = &'b Rc<Loc<RpDecl>>>,
+        {
+            Ok(())
+        }
+    };
+);
+
+impl<'env> MarkdownProcessor<'env> {
+    fn channel(&self, channel: &RpChannel) -> Result<String> {
+        let ty = self.ty(channel.ty())?;
+
+        if channel.is_streaming() {
+            return Ok(format!("`stream` {}", ty));
+        }
+
+        Ok(ty)
+    }
+
+    /// Format a type, linking to the declarations it refers to.
+    fn ty(&self, ty: &RpType) -> Result<String> {
+        let out = match *ty {
+            RpType::Double => "`double`".to_string(),
+            RpType::Float => "`float`".to_string(),
+            RpType::Boolean => "`boolean`".to_string(),
+            RpType::String => "`string`".to_string(),
+            RpType::DateTime => "`datetime`".to_string(),
+            RpType::Bytes => "`bytes`".to_string(),
+            RpType::Any => "`any`".to_string(),
+            RpType::Signed { ref size } => format!("`i{}`", size),
+            RpType::Unsigned { ref size } => format!("`u{}`", size),
+            RpType::Generic { ref name } => format!("`{}`", name),
+            RpType::Array { ref inner } => format!("[{}]", self.ty(inner)?),
+            RpType::Map { ref key, ref value } => {
+                format!("{{{}: {}}}", self.ty(key)?, self.ty(value)?)
+            }
+            RpType::Name {
+                ref name,
+                ref arguments,
+            } => {
+                let url = self.type_url(name)?;
+                let mut out = format!("[`{}`]({})", name.parts.join("::"), url);
+
+                if !arguments.is_empty() {
+                    let mut formatted = Vec::new();
+
+                    for argument in arguments {
+                        formatted.push(self.ty(argument)?);
+                    }
+
+                    out.push_str(&format!("&lt;{}&gt;", formatted.join(", ")));
+                }
+
+                out
+            }
+        };
+
+        Ok(out)
+    }
+}
+
+/// File that the documentation of the given package is written to.
+pub fn package_file(package: &RpVersionedPackage) -> String {
+    let parts = package.as_package(ToString::to_string).parts;
+
+    if parts.is_empty() {
+        return format!("{}.md", DEFAULT_PACKAGE);
+    }
+
+    format!("{}.md", parts.join("."))
+}
+
+/// HTML anchor of a name, since generators disagree on how to build anchors from headings.
+fn anchor(name: &RpName) -> String {
+    format!("<a id=\"{}\"></a>", MarkdownProcessor::anchor(name))
+}
+
+fn first_line(comment: &[String]) -> Option<&str> {
+    comment.iter().map(|line| line.trim()).find(|line| !line.is_empty())
+}
+
+/// Format a comment to fit in a table cell.
+fn cell(comment: &[String]) -> String {
+    comment
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{RpPackage, Version};
+    use source::Sources;
+    use std::cell::RefCell;
+    use syntect::highlighting::Theme;
+    use syntect::parsing::SyntaxSet;
+
+    fn package() -> RpVersionedPackage {
+        RpVersionedPackage::new(RpPackage::parse("io.test"), None)
+    }
+
+    fn env(content: &str) -> Environment {
+        let mut env = Environment::empty();
+        env.import_str(content, package()).unwrap();
+        env
+    }
+
+    /// Render the documentation of the only package in the given specification.
+    fn render(content: &str) -> String {
+        let env = env(content);
+        let theme = Theme::default();
+        let syntax_set = SyntaxSet::new();
+        let sources = Sources::new();
+        let mut out = String::new();
+
+        {
+            let (package, file) = env.for_each_file().next().unwrap();
+
+            MarkdownProcessor {
+                out: RefCell::new(DocBuilder::new(&mut out)),
+                env: &env,
+                syntax: (&theme, &syntax_set),
+                root: ".",
+                sources: &sources,
+                body: &Data {
+                    package: package,
+                    file: file,
+                },
+            }.process()
+                .unwrap();
+        }
+
+        out
+    }
+
+    #[test]
+    fn test_package_file() {
+        let default = RpVersionedPackage::new(RpPackage::empty(), None);
+        assert_eq!("default.md", package_file(&default));
+        assert_eq!("io.test.md", package_file(&package()));
+
+        let version = Version::parse("1.0.0").unwrap();
+        let versioned = RpVersionedPackage::new(RpPackage::parse("io.test"), Some(version));
+        assert_eq!("io.test.1.0.0.md", package_file(&versioned));
+    }
+
+    #[test]
+    fn test_cell() {
+        let comment = vec![" a | b".to_string(), "".to_string(), "c ".to_string()];
+        assert_eq!("a \\| b c", cell(&comment));
+    }
+
+    #[test]
+    fn test_fields_table() {
+        let out = render(
+            r#"
+            type Foo {
+                /// Either a | b.
+                /// Second line.
+                foo_bar: string as "fooBar";
+                other?: [u32];
+            }
+            "#,
+        );
+
+        let expected = "| Field | Type | Required | Description |\n\
+                        |-------|------|----------|-------------|\n\
+                        | `foo_bar` as `fooBar` | `string` | yes | Either a \\| b. Second line. |\n\
+                        | `other` | [`u32`] | no |  |\n";
+
+        assert!(out.contains(expected), "{}", out);
+    }
+
+    #[test]
+    fn test_links() {
+        let out = render(
+            r#"
+            type Foo {
+                bar: Bar;
+                kinds: {string: Bar::Kind};
+            }
+
+            type Bar {
+                enum Kind as string {
+                    A as "a";
+                }
+            }
+
+            service Api {
+                get() -> stream Foo;
+            }
+            "#,
+        );
+
+        // declarations are listed with links to their sections.
+        assert!(out.contains("* type [`Foo`](io.test.md#Foo)\n"), "{}", out);
+        assert!(out.contains("* enum [`Bar::Kind`](io.test.md#Bar_Kind)\n"), "{}", out);
+        assert!(out.contains("<a id=\"Bar_Kind\"></a>\n## enum `Bar::Kind`\n"), "{}", out);
+
+        // types link to the declarations they refer to.
+        assert!(out.contains("| `bar` | [`Bar`](io.test.md#Bar) | yes |"), "{}", out);
+        let map = "{`string`: [`Bar::Kind`](io.test.md#Bar_Kind)}";
+        assert!(out.contains(map), "{}", out);
+
+        assert!(out.contains("<a id=\"Bar_Kind_A\"></a>`A` | `\"a\"` |"), "{}", out);
+        assert!(out.contains("<a id=\"Api_get\"></a>\n### endpoint `get`\n"), "{}", out);
+        let response = "**Response:** `stream` [`Foo`](io.test.md#Foo)\n";
+        assert!(out.contains(response), "{}", out);
+    }
+
+    #[test]
+    fn test_index() {
+        let env = env("//! The test package.\n//!\n//! More.\ntype Foo {}");
+        let theme = Theme::default();
+        let syntax_set = SyntaxSet::new();
+        let sources = Sources::new();
+        let mut out = String::new();
+
+        MarkdownIndexProcessor {
+            out: RefCell::new(DocBuilder::new(&mut out)),
+            env: &env,
+            syntax: (&theme, &syntax_set),
+            root: ".",
+            sources: &sources,
+            body: &IndexData { entries: env.for_each_file().collect() },
+        }.process()
+            .unwrap();
+
+        let expected = "# Packages\n\n* [`io.test`](io.test.md) &mdash; The test package.\n";
+        assert_eq!(expected, out);
+    }
+}
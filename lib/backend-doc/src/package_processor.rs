@@ -13,6 +13,8 @@ use processor::Processor;
 pub struct Data<'a> {
     pub package: &'a RpVersionedPackage,
     pub file: &'a RpFile,
+    /// Link to the changelog of the package.
+    pub changelog: bool,
 }
 
 macro_rules! types_section {
@@ -63,8 +65,20 @@ define_processor!(PackageProcessor, Data<'env>, self,
                     html!(self, span {class => "kind"} ~ "package");
                     html!(self, span {class => "name-package"} ~
                           Escape(self.body.package.to_string().as_str()));
+
+                    if self.sources().contains_key(self.body.package) {
+                        let source_url = self.source_url(self.body.package);
+                        html!(self, a {class => "source-link", href => source_url} ~ "source");
+                    }
+
+                    if self.body.changelog {
+                        let changelog_url = self.changelog_url(&self.body.package.package);
+                        html!(self, a {class => "source-link", href => changelog_url} ~
+                              "changelog");
+                    }
                 });
 
+                self.search_box()?;
                 self.doc(self.body.file.comment.iter())?;
 
                 types_section!(self, types, "Types");
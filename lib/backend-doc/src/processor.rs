@@ -1,13 +1,19 @@
 //! Processor trait.
 
-use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME};
+use super::{DOC_CSS_NAME, NORMALIZE_CSS_NAME, SEARCH_INDEX_NAME, SEARCH_JS_NAME};
 use backend::Environment;
 use backend::errors::*;
-use core::{ForEachLoc, Loc, RpDecl, RpField, RpName, RpType, RpVersionedPackage};
+use changelog_processor::CHANGELOG_HTML;
+use core::{ForEachLoc, Loc, Pos, RpConstraints, RpDecl, RpEndpoint, RpField, RpInterfaceBody,
+           RpName, RpPackage, RpServiceBody, RpSubType, RpType, RpVariant, RpVersionedPackage,
+           WithPos};
 use doc_builder::DocBuilder;
 use escape::Escape;
+use example::Examples;
 use macros::FormatAttribute;
-use rendering::markdown_to_html;
+use rendering::{code_to_html, markdown_to_html};
+use serde_json;
+use source::{SOURCE_HTML, Sources};
 use std::ops::DerefMut;
 use std::rc::Rc;
 use syntect::highlighting::Theme;
@@ -23,6 +29,9 @@ pub trait Processor<'env> {
     /// Path to root.
     fn root(&self) -> &'env str;
 
+    /// Sources to link to, empty if sources are not embedded.
+    fn sources(&self) -> &'env Sources;
+
     /// Process the given request.
     fn process(self) -> Result<()>;
 
@@ -41,7 +50,7 @@ pub trait Processor<'env> {
 
         let (fragment, parts) = match member {
             Some(member) => {
-                let fragment = format!("#{}", member.name().parts.join("_"));
+                let fragment = format!("#{}", Self::anchor(member.name()));
 
                 let parts: Vec<_> = name.parts
                     .iter()
@@ -82,6 +91,81 @@ pub trait Processor<'env> {
         Ok(())
     }
 
+    /// Anchor of a declaration, or of a member of a declaration.
+    fn anchor(name: &RpName) -> String {
+        name.join("_")
+    }
+
+    /// Anchor of an endpoint in a service.
+    fn endpoint_anchor(body: &RpServiceBody, endpoint: &RpEndpoint) -> String {
+        format!(
+            "{}_{}",
+            body.name,
+            endpoint.id_parts(Self::fragment_filter).join("_")
+        )
+    }
+
+    /// Render the body of a declaration, through the hooks for each of its parts.
+    ///
+    /// The HTML processors of each kind of declaration render the same parts, in the same order,
+    /// but wrap them in sections of their own.
+    fn decl_body(&self, decl: &RpDecl) -> Result<()> {
+        use self::RpDecl::*;
+
+        self.doc(decl.comment())?;
+
+        match *decl {
+            Type(ref body) => {
+                self.example(&body.name)?;
+                self.fields(body.fields.iter())?;
+            }
+            Tuple(ref body) => {
+                self.example(&body.name)?;
+                self.fields(body.fields.iter())?;
+            }
+            Interface(ref body) => {
+                if body.example.is_some() {
+                    self.example(&body.name)?;
+                }
+
+                for sub_type in body.sub_types.values() {
+                    self.sub_type(body, sub_type)?;
+                }
+            }
+            Enum(ref body) => {
+                self.example(&body.name)?;
+                self.variants(body.variants.iter())?;
+            }
+            Service(ref body) => {
+                for endpoint in body.endpoints.values() {
+                    self.endpoint(body, endpoint)?;
+                }
+            }
+        }
+
+        self.nested_decls(decl.decls())
+    }
+
+    /// Build the example JSON payload of the given declaration.
+    fn example_json(&self, name: &RpName) -> Result<String> {
+        let reg = self.env().lookup(name)?;
+        let example = Examples::new(self.env()).example(reg)?;
+        Ok(serde_json::to_string_pretty(&example)?)
+    }
+
+    /// Render an example JSON payload of the given declaration.
+    fn example(&self, name: &RpName) -> Result<()> {
+        let example = self.example_json(name)?;
+
+        html!(self, div {class => "example"} => {
+            html!(self, h3 {class => "example-title"} ~ "Example");
+            let (theme, syntax_set) = self.syntax();
+            code_to_html(self.out().deref_mut(), &example, "json", theme, syntax_set)?;
+        });
+
+        Ok(())
+    }
+
     fn doc<'a, I>(&self, comment: I) -> Result<()>
     where
         I: IntoIterator<Item = &'a String>,
@@ -121,10 +205,32 @@ pub trait Processor<'env> {
             Any => self.primitive("any")?,
             Signed { ref size } => self.primitive(format!("i{}", size).as_str())?,
             Unsigned { ref size } => self.primitive(format!("u{}", size).as_str())?,
-            Name { ref name } => {
+            Name {
+                ref name,
+                ref arguments,
+            } => {
                 html!(self, span {class => "type-rp-name"} => {
                     self.full_name_without_package(name)?;
                 });
+
+                if !arguments.is_empty() {
+                    html!(self, span {class => "type-arguments"} => {
+                        html!(self, span {class => "type-arguments-left"} ~ "&lt;");
+
+                        for (i, argument) in arguments.iter().enumerate() {
+                            if i > 0 {
+                                html!(self, span {class => "type-arguments-sep"} ~ ",");
+                            }
+
+                            self.write_type(argument)?;
+                        }
+
+                        html!(self, span {class => "type-arguments-right"} ~ "&gt;");
+                    });
+                }
+            }
+            Generic { ref name } => {
+                html!(self, span {class => "type-parameter"} ~ Escape(name.as_str()));
             }
             Array { ref inner } => {
                 html!(self, span {class => "type-array"} => {
@@ -148,7 +254,7 @@ pub trait Processor<'env> {
         Ok(())
     }
 
-    fn field(&self, field: &RpField) -> Result<()> {
+    fn field(&self, field: &Loc<RpField>) -> Result<()> {
         let mut classes = vec!["field"];
 
         if field.is_optional() {
@@ -176,9 +282,52 @@ pub trait Processor<'env> {
                 html!(self, span {class => "keyword"} ~ "as");
                 html!(self, span {class => "field-name"} ~ Escape(field.name()));
             }
+
+            self.source_link(field.pos())?;
         });
 
         self.doc(&field.comment)?;
+        self.constraints(&field.constraints)?;
+
+        Ok(())
+    }
+
+    /// Render the constraints of a field.
+    fn constraints(&self, constraints: &RpConstraints) -> Result<()> {
+        if constraints.is_empty() {
+            return Ok(());
+        }
+
+        let mut items = Vec::new();
+
+        if let Some(ref min) = constraints.min {
+            items.push(("min", min.to_string()));
+        }
+
+        if let Some(ref max) = constraints.max {
+            items.push(("max", max.to_string()));
+        }
+
+        if let Some(min_length) = constraints.min_length {
+            items.push(("min_length", min_length.to_string()));
+        }
+
+        if let Some(max_length) = constraints.max_length {
+            items.push(("max_length", max_length.to_string()));
+        }
+
+        if let Some(ref pattern) = constraints.pattern {
+            items.push(("pattern", format!("{:?}", pattern)));
+        }
+
+        html!(self, ul {class => "field-constraints"} => {
+            for (name, value) in items {
+                html!(self, li {} => {
+                    html!(self, span {class => "constraint-name"} ~ name);
+                    html!(self, span {class => "constraint-value"} ~ Escape(value.as_str()));
+                });
+            }
+        });
 
         Ok(())
     }
@@ -187,7 +336,100 @@ pub trait Processor<'env> {
     where
         I: Iterator<Item = &'b Loc<RpField>>,
     {
-        fields.for_each_loc(|field| self.field(field))?;
+        for field in fields {
+            self.field(field).with_pos(field.pos())?;
+        }
+
+        Ok(())
+    }
+
+    /// Render a sub-type of an interface, with the fields inherited from the interface.
+    fn sub_type(&self, body: &RpInterfaceBody, sub_type: &Loc<RpSubType>) -> Result<()> {
+        let id = Self::anchor(&sub_type.name);
+
+        html!(self, h2 {id => id, class => "sub-type-title"} => {
+            html!(self, span {class => "kind"} ~ "subtype");
+            self.full_name_without_package(&sub_type.name)?;
+            self.source_link(sub_type.pos())?;
+        });
+
+        self.doc(&sub_type.comment)?;
+        self.example(&sub_type.name)?;
+
+        let fields = body.fields.iter().chain(sub_type.fields.iter());
+        self.fields(fields)?;
+        self.nested_decls(sub_type.decls.iter())?;
+        Ok(())
+    }
+
+    /// Render the variants of an enum.
+    fn variants<'b, I>(&self, variants: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'b Rc<Loc<RpVariant>>>,
+    {
+        for variant in variants {
+            let id = Self::anchor(&variant.name);
+
+            html!(self, h3 {id => id} => {
+                html!(self, span {class => "kind"} ~ "variant");
+                self.full_name_without_package(&variant.name)?;
+                html!(self, span {class => "keyword"} ~ "as");
+                html!(self, span {class => "variant-ordinal"} ~
+                      Escape(variant.ordinal().to_string().as_str()));
+                self.source_link(variant.pos())?;
+            });
+
+            self.doc(&variant.comment)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render an endpoint of a service.
+    fn endpoint(&self, body: &RpServiceBody, endpoint: &Loc<RpEndpoint>) -> Result<()> {
+        let id = Self::endpoint_anchor(body, endpoint);
+
+        html!(self, h2 {class => "endpoint-title", id => id} => {
+            self.name_until(&body.name)?;
+
+            html!(self, span {class => "endpoint-id"} ~ Escape(endpoint.id.as_str()));
+            html!(self, span {} ~ Escape("("));
+
+            if let Some(request) = endpoint.request.as_ref() {
+                html!(self, span {class => "endpoint-request-type"} => {
+                    if request.is_streaming() {
+                        html!(self, span {class => "keyword"} ~ Escape("stream"));
+                    }
+
+                    let (req, pos) = request.as_ref_pair();
+                    self.write_type(req.ty()).with_pos(pos)?;
+                });
+            }
+
+            html!(self, span {} ~ Escape(")"));
+
+            if let Some(response) = endpoint.response.as_ref() {
+                html!(self, span {class => "keyword"} ~ "&rarr;");
+
+                html!(self, span {class => "endpoint-response-type"} => {
+                    if response.is_streaming() {
+                        html!(self, span {class => "endpoint-stream"} ~ Escape("stream"));
+                    }
+
+                    let (res, pos) = response.as_ref_pair();
+                    self.write_type(res.ty()).with_pos(pos)?;
+                });
+            }
+
+            if endpoint.id.as_str() != endpoint.name.as_str() {
+                html!(self, span {class => "keyword"} ~ Escape("as"));
+                html!(self, span {} ~ Escape(endpoint.name.as_str()));
+            }
+
+            self.source_link(endpoint.pos())?;
+        });
+
+        self.doc(&endpoint.comment)?;
         Ok(())
     }
 
@@ -211,16 +453,61 @@ pub trait Processor<'env> {
         Ok(())
     }
 
+    /// Write a search box, searching all declarations in the documentation.
+    fn search_box(&self) -> Result<()> {
+        html!(self, div {class => "search"} => {
+            html!(@open self, input {
+                id => "search-input", type => "search", placeholder => "Search...",
+                autocomplete => "off"
+            });
+            self.out().new_line()?;
+
+            html!(self, ul {id => "search-results"} ~ "");
+        });
+
+        html!(self, script {src => format!("{}/{}", self.root(), SEARCH_INDEX_NAME)} ~ "");
+        html!(self, script {src => format!("{}/{}", self.root(), SEARCH_JS_NAME)} ~ "");
+        Ok(())
+    }
+
     /// Write a section title.
-    fn section_title(&self, kind: &str, name: &RpName) -> Result<()> {
+    fn section_title(&self, kind: &str, name: &RpName, pos: &Pos) -> Result<()> {
         html!(self, h1 {class => "section-title"} => {
             html!(self, span {class => "kind"} ~ kind);
             self.full_name(name, Some(name))?;
+            self.source_link(pos)?;
         });
 
         Ok(())
     }
 
+    /// Link to the lines in the source of the current package which the given position spans.
+    ///
+    /// Nothing is written if the source is not embedded.
+    fn source_link(&self, pos: &Pos) -> Result<()> {
+        let package = match self.current_package() {
+            Some(package) => package,
+            None => return Ok(()),
+        };
+
+        let source = match self.sources().get(package) {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+
+        let (start, end) = source.lines(pos);
+
+        let title = if start == end {
+            format!("line {}", start)
+        } else {
+            format!("lines {}-{}", start, end)
+        };
+
+        let url = format!("{}#L{}", self.source_url(package), start);
+        html!(self, a {class => "source-link", href => url, title => title} ~ "source");
+        Ok(())
+    }
+
     /// Write a complete HTML document.
     fn write_doc<Body>(&self, body: Body) -> Result<()>
     where
@@ -277,6 +564,18 @@ pub trait Processor<'env> {
         format!("{}/{}/index.html", self.root(), url)
     }
 
+    fn source_url(&self, package: &RpVersionedPackage) -> String {
+        let url = package.clone().as_package(ToString::to_string).parts.join(
+            "/",
+        );
+
+        format!("{}/{}/{}", self.root(), url, SOURCE_HTML)
+    }
+
+    fn changelog_url(&self, package: &RpPackage) -> String {
+        format!("{}/{}/{}", self.root(), package.parts.join("/"), CHANGELOG_HTML)
+    }
+
     fn fragment_filter(url: &str) -> String {
         let mut bytes = [0u8; 4];
         let mut buffer = String::with_capacity(url.len());
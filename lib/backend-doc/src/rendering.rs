@@ -10,6 +10,67 @@ use syntect::highlighting::Theme;
 use syntect::html::{IncludeBackground, start_coloured_html_snippet, styles_to_coloured_html};
 use syntect::parsing::SyntaxSet;
 
+/// Render a block of highlighted code, in the language identified by the given token.
+pub fn code_to_html(
+    out: &mut DocBuilder,
+    content: &str,
+    token: &str,
+    theme: &Theme,
+    syntax_set: &SyntaxSet,
+) -> Result<()> {
+    let syntax = syntax_set.find_syntax_by_token(token).unwrap_or_else(
+        || syntax_set.find_syntax_plain_text(),
+    );
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    out.write_str("<div class=\"code\">")?;
+    out.write_str(&start_coloured_html_snippet(theme))?;
+
+    for line in content.lines() {
+        // the highlighter expects lines to include their line ending.
+        let line = format!("{}\n", line);
+        let highlighted = highlighter.highlight(&line);
+        out.write_str(&styles_to_coloured_html(&highlighted, IncludeBackground::Yes))?;
+    }
+
+    out.write_str("</pre></div>")?;
+    Ok(())
+}
+
+/// Render the source of a specification, where each line has an anchor like `L1`.
+pub fn source_to_html(
+    out: &mut DocBuilder,
+    content: &str,
+    token: &str,
+    theme: &Theme,
+    syntax_set: &SyntaxSet,
+) -> Result<()> {
+    let syntax = syntax_set.find_syntax_by_token(token).unwrap_or_else(
+        || syntax_set.find_syntax_plain_text(),
+    );
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    out.write_str("<div class=\"code source\">")?;
+    out.write_str(&start_coloured_html_snippet(theme))?;
+
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+
+        let line = format!("{}\n", line);
+        let highlighted = highlighter.highlight(&line);
+        let html = styles_to_coloured_html(&highlighted, IncludeBackground::Yes);
+
+        write!(out, "<span id=\"L{0}\" class=\"source-line\">", number)?;
+        write!(out, "<a class=\"line-number\" href=\"#L{0}\">{0}</a>", number)?;
+        write!(out, "{}</span>", html)?;
+    }
+
+    out.write_str("</pre></div>")?;
+    Ok(())
+}
+
 pub fn markdown_to_html(
     out: &mut DocBuilder,
     content: &str,
@@ -0,0 +1,183 @@
+//! Search index of the documentation.
+//!
+//! The index is written as a script assigning to a global variable, instead of as plain JSON,
+//! since browsers refuse to fetch files when browsing through `file://`.
+
+use backend::errors::*;
+use core::{RpDecl, RpFile, RpVersionedPackage};
+use serde_json::{self, Value};
+
+/// Build the search index entry of a declaration.
+///
+/// `url` is relative to the root of the documentation.
+fn entry(package: &RpVersionedPackage, decl: &RpDecl, url: String) -> Value {
+    use self::RpDecl::*;
+
+    let mut members: Vec<String> = Vec::new();
+
+    match *decl {
+        Type(ref body) => {
+            members.extend(body.fields.iter().map(|f| f.name().to_string()));
+        }
+        Tuple(ref body) => {
+            members.extend(body.fields.iter().map(|f| f.name().to_string()));
+        }
+        Interface(ref body) => {
+            members.extend(body.fields.iter().map(|f| f.name().to_string()));
+
+            for sub_type in body.sub_types.values() {
+                members.push(sub_type.local_name.clone());
+                members.extend(sub_type.fields.iter().map(|f| f.name().to_string()));
+            }
+        }
+        Enum(ref body) => {
+            members.extend(body.variants.iter().map(|v| v.local_name.to_string()));
+        }
+        Service(ref body) => {
+            members.extend(body.endpoints.values().map(|e| e.id.to_string()));
+        }
+    }
+
+    let doc = decl.comment()
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+
+    json!({
+        "package": package.to_string(),
+        "kind": decl.kind(),
+        "name": decl.name().parts.join("::"),
+        "members": members,
+        "doc": doc,
+        "url": url,
+    })
+}
+
+/// Build the script containing the search index of all the given files.
+///
+/// `url` builds the URL of a declaration, relative to the root of the documentation.
+pub fn search_index<'a, I, U>(files: I, url: U) -> Result<String>
+where
+    I: IntoIterator<Item = (&'a RpVersionedPackage, &'a RpFile)>,
+    U: Fn(&RpDecl) -> String,
+{
+    let mut entries = Vec::new();
+
+    for (package, file) in files {
+        for decl in file.for_each_decl() {
+            let decl = decl.value();
+            entries.push(entry(package, decl, url(decl)));
+        }
+    }
+
+    let index = serde_json::to_string(&entries)?;
+    Ok(format!("var searchIndex = {};\n", index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::Environment;
+    use core::{RpPackage, Version};
+
+    const SPEC: &str = r#"
+    ///
+    /// A foo.
+    ///
+    /// More about foo.
+    type Foo {
+        foo_bar: string as "fooBar";
+
+        enum Kind as string {
+            A as "a";
+        }
+    }
+
+    interface Animal {
+        name: string;
+
+        Cat {
+            lives: u32;
+        }
+    }
+
+    tuple Pair {
+        a: string;
+        b: string;
+    }
+
+    service Api {
+        get() -> Foo;
+    }
+    "#;
+
+    /// Build the search index of the spec, and parse its entries.
+    fn entries() -> Vec<Value> {
+        let mut env = Environment::empty();
+        let version = Version::parse("1.0.0").unwrap();
+        let package = RpVersionedPackage::new(RpPackage::parse("test"), Some(version));
+        let file = env.load_str(SPEC, &package).unwrap();
+
+        let url = |decl: &RpDecl| format!("{}.html", decl.name().parts.join("."));
+        let script = search_index(vec![(&package, &file)], url).unwrap();
+
+        assert!(script.starts_with("var searchIndex = "));
+        assert!(script.ends_with(";\n"));
+
+        let index = &script["var searchIndex = ".len()..script.len() - 2];
+
+        match serde_json::from_str(index).unwrap() {
+            Value::Array(entries) => entries,
+            other => panic!("expected array: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_search_index() {
+        let expected = vec![
+            json!({
+                "package": "test-1.0.0",
+                "kind": "type",
+                "name": "Foo",
+                "members": ["fooBar"],
+                "doc": "A foo.",
+                "url": "Foo.html",
+            }),
+            json!({
+                "package": "test-1.0.0",
+                "kind": "interface",
+                "name": "Animal",
+                "members": ["name", "Cat", "lives"],
+                "doc": "",
+                "url": "Animal.html",
+            }),
+            json!({
+                "package": "test-1.0.0",
+                "kind": "tuple",
+                "name": "Pair",
+                "members": ["a", "b"],
+                "doc": "",
+                "url": "Pair.html",
+            }),
+            json!({
+                "package": "test-1.0.0",
+                "kind": "service",
+                "name": "Api",
+                "members": ["get"],
+                "doc": "",
+                "url": "Api.html",
+            }),
+            json!({
+                "package": "test-1.0.0",
+                "kind": "enum",
+                "name": "Foo::Kind",
+                "members": ["A"],
+                "doc": "",
+                "url": "Foo.Kind.html",
+            }),
+        ];
+
+        assert_eq!(expected, entries());
+    }
+}
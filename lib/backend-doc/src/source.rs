@@ -0,0 +1,98 @@
+//! Sources of specifications, which are embedded in the documentation.
+
+use backend::errors::*;
+use core::{Object, Pos, RpVersionedPackage};
+use std::collections::BTreeMap;
+use std::io::Read;
+
+/// Name of the file that the source of a package is written to.
+pub const SOURCE_HTML: &str = "source.html";
+
+/// Sources, by the package they declare.
+pub type Sources = BTreeMap<RpVersionedPackage, Source>;
+
+pub struct Source {
+    pub content: String,
+    /// Offsets at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl Source {
+    /// Read the source from the given object.
+    pub fn read(object: &Object) -> Result<Source> {
+        let mut content = String::new();
+        object.read()?.read_to_string(&mut content)?;
+
+        let mut line_starts = vec![0];
+        line_starts.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+
+        Ok(Source {
+            content: content,
+            line_starts: line_starts,
+        })
+    }
+
+    /// The first and the last line spanned by the given position, starting at 1.
+    pub fn lines(&self, pos: &Pos) -> (usize, usize) {
+        let start = self.line_of(pos.start);
+        let end = self.line_of(::std::cmp::max(pos.start, pos.end.saturating_sub(1)));
+        (start, end)
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::BytesObject;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    const CONTENT: &str = "ab\ncd\n\nef";
+
+    fn object() -> Rc<Box<Object>> {
+        let bytes = Arc::new(CONTENT.as_bytes().to_vec());
+        Rc::new(Box::new(BytesObject::new("test".to_string(), bytes)))
+    }
+
+    fn source() -> Source {
+        Source::read(&**object()).unwrap()
+    }
+
+    fn lines(start: usize, end: usize) -> (usize, usize) {
+        source().lines(&Pos::from((object(), start, end)))
+    }
+
+    #[test]
+    fn test_read() {
+        let source = source();
+        assert_eq!(CONTENT, source.content);
+        assert_eq!(vec![0, 3, 6, 7], source.line_starts);
+    }
+
+    #[test]
+    fn test_line_of() {
+        let source = source();
+        let lines: Vec<_> = (0..CONTENT.len() + 1).map(|o| source.line_of(o)).collect();
+        assert_eq!(vec![1, 1, 1, 2, 2, 2, 3, 4, 4, 4], lines);
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!((1, 1), lines(0, 2));
+        // a trailing newline doesn't span the next line.
+        assert_eq!((1, 1), lines(0, 3));
+        assert_eq!((1, 2), lines(0, 4));
+        assert_eq!((2, 2), lines(3, 6));
+        assert_eq!((2, 4), lines(3, CONTENT.len()));
+        // empty positions are on the line they start on.
+        assert_eq!((2, 2), lines(3, 3));
+        assert_eq!((3, 3), lines(6, 6));
+    }
+}
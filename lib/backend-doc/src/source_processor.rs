@@ -0,0 +1,48 @@
+//! Processor for the source of a package.
+
+use backend::Environment;
+use backend::errors::*;
+use core::RpVersionedPackage;
+use doc_builder::DocBuilder;
+use escape::Escape;
+use macros::FormatAttribute;
+use processor::Processor;
+use rendering::source_to_html;
+use source::Source;
+use std::ops::DerefMut;
+
+pub struct Data<'a> {
+    pub package: &'a RpVersionedPackage,
+    pub source: &'a Source,
+}
+
+define_processor!(SourceProcessor, Data<'env>, self,
+    process => {
+        self.write_doc(|| {
+            html!(self, section {class => "section-content section-source"} => {
+                html!(self, h1 {class => "section-title"} => {
+                    html!(self, span {class => "kind"} ~ "source");
+                    let package_url = self.package_url(self.body.package);
+                    html!(self, a {class => "name-package", href => package_url} ~
+                          Escape(self.body.package.to_string().as_str()));
+                });
+
+                let (theme, syntax_set) = self.syntax();
+
+                source_to_html(
+                    self.out().deref_mut(),
+                    self.body.source.content.as_str(),
+                    "reproto",
+                    theme,
+                    syntax_set,
+                )?;
+            });
+
+            Ok(())
+        })
+    };
+
+    current_package => &self.body.package;
+);
+
+impl<'env> SourceProcessor<'env> {}
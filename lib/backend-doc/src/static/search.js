@@ -0,0 +1,127 @@
+/* Client-side search of the documentation, using the index in search-index.js. */
+
+/* Score an entry against all terms, where zero means that it doesn't match. */
+function searchScore(entry, terms) {
+    var total = 0;
+
+    for (var i = 0; i < terms.length; i++) {
+        var term = terms[i];
+        var name = entry.name.toLowerCase();
+        var separator = name.lastIndexOf("::");
+        var local = separator === -1 ? name : name.substring(separator + 2);
+
+        if (local === term) {
+            total += 100;
+        } else if (local.indexOf(term) === 0) {
+            total += 50;
+        } else if (name.indexOf(term) !== -1) {
+            total += 20;
+        } else if (entry.members.some(function(m) {
+            return m.toLowerCase().indexOf(term) !== -1;
+        })) {
+            total += 10;
+        } else if (entry.package.toLowerCase().indexOf(term) !== -1) {
+            total += 5;
+        } else if (entry.doc.toLowerCase().indexOf(term) !== -1) {
+            total += 1;
+        } else {
+            return 0;
+        }
+    }
+
+    return total;
+}
+
+/* Exported when loaded as a module, so that the scoring can be tested. */
+if (typeof module !== "undefined") {
+    module.exports = {searchScore: searchScore};
+}
+
+(function() {
+    var MAX_RESULTS = 50;
+
+    if (typeof document === "undefined") {
+        return;
+    }
+
+    var script = document.currentScript;
+    var root = script.src.substring(0, script.src.lastIndexOf("/") + 1);
+
+    var input = document.getElementById("search-input");
+    var results = document.getElementById("search-results");
+
+    if (!input || !results || typeof searchIndex === "undefined") {
+        return;
+    }
+
+    function search(query) {
+        var terms = query.toLowerCase().split(/\s+/).filter(function(t) {
+            return t.length > 0;
+        });
+
+        while (results.firstChild) {
+            results.removeChild(results.firstChild);
+        }
+
+        if (terms.length === 0) {
+            return;
+        }
+
+        var matches = [];
+
+        for (var i = 0; i < searchIndex.length; i++) {
+            var s = searchScore(searchIndex[i], terms);
+
+            if (s > 0) {
+                matches.push({score: s, entry: searchIndex[i]});
+            }
+        }
+
+        matches.sort(function(a, b) {
+            return b.score - a.score || a.entry.name.localeCompare(b.entry.name);
+        });
+
+        matches.slice(0, MAX_RESULTS).forEach(function(match) {
+            var entry = match.entry;
+
+            var item = document.createElement("li");
+
+            var kind = document.createElement("span");
+            kind.className = "kind";
+            kind.textContent = entry.kind;
+            item.appendChild(kind);
+
+            var link = document.createElement("a");
+            link.href = root + entry.url;
+            link.textContent = entry.name;
+            item.appendChild(link);
+
+            var pkg = document.createElement("span");
+            pkg.className = "search-package";
+            pkg.textContent = entry.package;
+            item.appendChild(pkg);
+
+            if (entry.doc) {
+                var doc = document.createElement("div");
+                doc.className = "search-doc";
+                doc.textContent = entry.doc;
+                item.appendChild(doc);
+            }
+
+            results.appendChild(item);
+        });
+
+        if (matches.length === 0) {
+            var empty = document.createElement("li");
+            empty.className = "missing-doc";
+            empty.textContent = "no results :(";
+            results.appendChild(empty);
+        }
+    }
+
+    input.addEventListener("input", function() {
+        search(input.value);
+    });
+
+    search(input.value);
+})();
@@ -0,0 +1,35 @@
+/* Tests for the scoring in search.js, run with `node search_test.js`. */
+var assert = require("assert");
+var searchScore = require("./search.js").searchScore;
+
+function entry(name, fields) {
+    fields = fields || {};
+
+    return {
+        name: name,
+        kind: "type",
+        url: name + ".html",
+        package: fields.package || "test",
+        members: fields.members || [],
+        doc: fields.doc || ""
+    };
+}
+
+/* Names without a separator are matched as a whole. */
+assert.equal(searchScore(entry("Foo"), ["foo"]), 100);
+assert.equal(searchScore(entry("Foo"), ["fo"]), 50);
+
+/* Inner names are matched by their last component. */
+assert.equal(searchScore(entry("Foo::Bar"), ["bar"]), 100);
+assert.equal(searchScore(entry("Foo::Bar"), ["ba"]), 50);
+assert.equal(searchScore(entry("Foo::Bar"), ["foo"]), 20);
+
+assert.equal(searchScore(entry("Foo", {members: ["field"]}), ["fie"]), 10);
+assert.equal(searchScore(entry("Foo", {package: "io.reproto"}), ["reproto"]), 5);
+assert.equal(searchScore(entry("Foo", {doc: "Some docs."}), ["docs"]), 1);
+
+/* All terms have to match. */
+assert.equal(searchScore(entry("Foo::Bar"), ["bar", "foo"]), 120);
+assert.equal(searchScore(entry("Foo::Bar"), ["bar", "baz"]), 0);
+
+console.log("search.js: ok");
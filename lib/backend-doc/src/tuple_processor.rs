@@ -2,21 +2,22 @@
 
 use backend::Environment;
 use backend::errors::*;
-use core::RpTupleBody;
+use core::{Loc, RpTupleBody};
 use doc_builder::DocBuilder;
 use macros::FormatAttribute;
 use processor::Processor;
 
-define_processor!(TupleProcessor, RpTupleBody, self,
+define_processor!(TupleProcessor, Loc<RpTupleBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
 
             html!(self, section {id => &id, class => "section-content section-tuple"} => {
-                self.section_title("tuple", &self.body.name)?;
+                self.section_title("tuple", &self.body.name, self.body.pos())?;
 
                 html!(self, div {class => "section-body"} => {
                     self.doc(&self.body.comment)?;
+                    self.example(&self.body.name)?;
                     self.fields(self.body.fields.iter())?;
                     self.nested_decls(self.body.decls.iter())?;
                 });
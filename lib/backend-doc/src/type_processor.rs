@@ -2,21 +2,22 @@
 
 use backend::Environment;
 use backend::errors::*;
-use core::RpTypeBody;
+use core::{Loc, RpTypeBody};
 use doc_builder::DocBuilder;
 use macros::FormatAttribute;
 use processor::Processor;
 
-define_processor!(TypeProcessor, RpTypeBody, self,
+define_processor!(TypeProcessor, Loc<RpTypeBody>, self,
     process => {
         self.write_doc(|| {
             let id = self.body.name.join("_");
 
             html!(self, section {id => &id, class => "section-content section-type"} => {
-                self.section_title("type", &self.body.name)?;
+                self.section_title("type", &self.body.name, self.body.pos())?;
 
                 html!(self, div {class => "section-body"} => {
                     self.doc(&self.body.comment)?;
+                    self.example(&self.body.name)?;
                     self.fields(self.body.fields.iter())?;
                     self.nested_decls(self.body.decls.iter())?;
                 });
@@ -0,0 +1,518 @@
+//! Backend for Go
+
+use super::{GO_CONTEXT, TYPE, TYPE_SEP};
+use backend::{Code, CompilerOptions, Converter, Environment, FromNaming, Naming, PackageUtils,
+              SnakeCase};
+use backend::errors::*;
+use core::{ForEachLoc, Loc, RpEnumBody, RpEnumType, RpField, RpInterfaceBody, RpName,
+           RpTupleBody, RpType, RpTypeBody};
+use genco::{Quoted, Tokens};
+use go_compiler::GoCompiler;
+use go_file_spec::GoFileSpec;
+use go_options::GoOptions;
+use listeners::Listeners;
+use std::rc::Rc;
+
+const JSON_PATH: &str = "encoding/json";
+const JSON_ALIAS: &str = "json";
+const ERRORS_PATH: &str = "errors";
+const ERRORS_ALIAS: &str = "errors";
+
+pub struct GoBackend {
+    pub env: Environment,
+    options: GoOptions,
+    listeners: Box<Listeners>,
+    to_upper_camel: Box<Naming>,
+}
+
+impl GoBackend {
+    pub fn new(env: Environment, options: GoOptions, listeners: Box<Listeners>) -> GoBackend {
+        GoBackend {
+            env: env,
+            options: options,
+            listeners: listeners,
+            to_upper_camel: SnakeCase::new().to_upper_camel(),
+        }
+    }
+
+    pub fn compiler(&self, options: CompilerOptions) -> Result<GoCompiler> {
+        Ok(GoCompiler {
+            out_path: options.out_path,
+            backend: self,
+        })
+    }
+
+    /// Path used when importing the given package.
+    ///
+    /// Packages are relative to the configured import root, if any.
+    fn import_path(&self, name: &RpName) -> String {
+        let path = self.package(&name.package).parts.join("/");
+
+        match self.options.import_root {
+            Some(ref root) => format!("{}/{}", root, path),
+            None => path,
+        }
+    }
+
+    /// Exported identifier of a field.
+    fn field_ident(&self, field: &RpField) -> String {
+        self.to_upper_camel.convert(field.ident())
+    }
+
+    /// Convert the given type into its Go counterpart, registering any necessary imports.
+    fn into_go_type<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        ty: &'el RpType,
+    ) -> Result<Tokens<'el, ()>> {
+        use self::RpType::*;
+
+        let ty = match *ty {
+            String | DateTime => toks!["string"],
+            Bytes => toks!["[]byte"],
+            Signed { ref size } => toks![format!("int{}", size)],
+            Unsigned { ref size } => toks![format!("uint{}", size)],
+            Float => toks!["float32"],
+            Double => toks!["float64"],
+            Boolean => toks!["bool"],
+            Any => {
+                out.import(JSON_PATH, JSON_ALIAS);
+                toks![JSON_ALIAS, ".RawMessage"]
+            }
+            Array { ref inner } => toks!["[]", self.into_go_type(out, inner)?],
+            Map { ref key, ref value } => {
+                let key = self.into_go_type(out, key)?;
+                let value = self.into_go_type(out, value)?;
+                toks!["map[", key, "]", value]
+            }
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                if !arguments.is_empty() {
+                    return Err(format!("{}: generic types are not supported", ty).into());
+                }
+
+                if let Some(ref prefix) = name.prefix {
+                    out.import(&self.import_path(name), prefix);
+                }
+
+                self.convert_type(name)?
+            }
+            Generic { ref name } => {
+                return Err(format!("{}: type parameters are not supported", name).into());
+            }
+        };
+
+        Ok(ty)
+    }
+
+    /// Type of a field, where optional fields are represented as pointers.
+    fn field_type<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        field: &'el RpField,
+    ) -> Result<Tokens<'el, ()>> {
+        let ty = self.into_go_type(out, &field.ty)?;
+
+        if field.is_optional() {
+            return Ok(toks!["*", ty]);
+        }
+
+        Ok(ty)
+    }
+
+    /// Build the declaration of a single struct field, including its json tag.
+    fn field_element<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        field: &'el RpField,
+    ) -> Result<Tokens<'el, ()>> {
+        let ty = self.field_type(out, field)?;
+
+        let tag = if field.is_optional() {
+            format!("`json:\"{},omitempty\"`", field.name())
+        } else {
+            format!("`json:\"{}\"`", field.name())
+        };
+
+        Ok(toks![self.field_ident(field), " ", ty, " ", tag])
+    }
+
+    /// Build a struct declaration out of the given fields.
+    fn build_struct<'el, I>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        name: Rc<String>,
+        fields: I,
+    ) -> Result<Tokens<'el, ()>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        let mut body = Tokens::new();
+
+        for field in fields {
+            body.push(self.field_element(out, field)?);
+        }
+
+        let mut t = Tokens::new();
+        t.push(toks!["type ", name, " struct {"]);
+        t.nested(body);
+        t.push("}");
+        Ok(t)
+    }
+
+    /// Build a method on the given receiver.
+    fn build_method<'el>(
+        &self,
+        receiver: Tokens<'el, ()>,
+        signature: Tokens<'el, ()>,
+        body: Tokens<'el, ()>,
+    ) -> Tokens<'el, ()> {
+        let mut t = Tokens::new();
+        t.push(toks!["func (this ", receiver, ") ", signature, " {"]);
+        t.nested(body);
+        t.push("}");
+        t
+    }
+
+    /// Return early if the given statement fails.
+    fn check_err<'el>(&self, stmt: Tokens<'el, ()>, ret: &'el str) -> Tokens<'el, ()> {
+        let mut t = Tokens::new();
+        t.push(toks!["if err := ", stmt, "; err != nil {"]);
+        t.nested(toks!["return ", ret]);
+        t.push("}");
+        t
+    }
+
+    pub fn process_tuple<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        body: &'el RpTupleBody,
+    ) -> Result<()> {
+        out.import(JSON_PATH, JSON_ALIAS);
+        out.import(ERRORS_PATH, ERRORS_ALIAS);
+
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut elements = Tokens::new();
+
+        elements.push(self.build_struct(out, name.clone(), &body.fields)?);
+
+        // decode the tuple from a JSON array.
+        elements.push({
+            let length = body.fields.len();
+
+            let mut decode = Tokens::new();
+
+            decode.push("var array []json.RawMessage");
+
+            decode.push(self.check_err(toks!["json.Unmarshal(b, &array)"], "err"));
+
+            decode.push({
+                let mut t = Tokens::new();
+                t.push(toks!["if len(array) != ", length.to_string(), " {"]);
+                t.nested(toks![
+                    "return errors.New(",
+                    format!("expected array of length {}", length).quoted(),
+                    ")",
+                ]);
+                t.push("}");
+                t
+            });
+
+            for (i, field) in body.fields.iter().enumerate() {
+                let stmt = toks![
+                    "json.Unmarshal(array[",
+                    i.to_string(),
+                    "], &this.",
+                    self.field_ident(field),
+                    ")",
+                ];
+
+                decode.push(self.check_err(stmt, "err"));
+            }
+
+            decode.push("return nil");
+
+            self.build_method(
+                toks!["*", name.clone()],
+                toks!["UnmarshalJSON(b []byte) error"],
+                decode.join_line_spacing(),
+            )
+        });
+
+        // encode the tuple as a JSON array.
+        elements.push({
+            let mut values = Tokens::new();
+
+            for field in &body.fields {
+                values.append(toks!["this.", self.field_ident(field)]);
+            }
+
+            let encode = toks!["return json.Marshal([]interface{}{", values.join(", "), "})"];
+
+            self.build_method(
+                toks![name.clone()],
+                toks!["MarshalJSON() ([]byte, error)"],
+                encode,
+            )
+        });
+
+        elements.push_unless_empty(Code(&body.codes, GO_CONTEXT));
+
+        out.body.push(elements.join_line_spacing());
+        Ok(())
+    }
+
+    pub fn process_enum<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        body: &'el RpEnumBody,
+    ) -> Result<()> {
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut elements = Tokens::new();
+
+        let variant_type = match body.variant_type {
+            RpEnumType::Signed { size } => format!("int{}", size),
+            RpEnumType::Unsigned { size } => format!("uint{}", size),
+            RpEnumType::String | RpEnumType::Generated => "string".to_string(),
+        };
+
+        elements.push(toks!["type ", name.clone(), " ", variant_type]);
+
+        let mut constants = Tokens::new();
+
+        body.variants.iter().for_each_loc(|variant| {
+            let ordinal = self.ordinal(variant)?;
+
+            let constant = format!("{}{}{}", name, TYPE_SEP, variant.local_name.as_str());
+            constants.push(toks![constant, " ", name.clone(), " = ", ordinal]);
+
+            Ok(()) as Result<()>
+        })?;
+
+        if !constants.is_empty() {
+            let mut t = Tokens::new();
+            t.push("const (");
+            t.nested(constants);
+            t.push(")");
+            elements.push(t);
+        }
+
+        elements.push_unless_empty(Code(&body.codes, GO_CONTEXT));
+
+        out.body.push(elements.join_line_spacing());
+        Ok(())
+    }
+
+    pub fn process_type<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        body: &'el RpTypeBody,
+    ) -> Result<()> {
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut elements = Tokens::new();
+        elements.push(self.build_struct(out, name, &body.fields)?);
+        elements.push_unless_empty(Code(&body.codes, GO_CONTEXT));
+
+        out.body.push(elements.join_line_spacing());
+        Ok(())
+    }
+
+    /// Interfaces are represented as a struct with one pointer per sub-type, of which exactly one
+    /// is expected to be set.
+    ///
+    /// The `type` field is used to determine which sub-type to decode.
+    pub fn process_interface<'el>(
+        &self,
+        out: &mut GoFileSpec<'el>,
+        body: &'el RpInterfaceBody,
+    ) -> Result<()> {
+        out.import(JSON_PATH, JSON_ALIAS);
+        out.import(ERRORS_PATH, ERRORS_ALIAS);
+
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut members = Tokens::new();
+        let mut sub_types = Tokens::new();
+        let mut decode_cases = Tokens::new();
+        let mut encode_cases = Tokens::new();
+
+        let type_var = TYPE.quoted();
+
+        body.sub_types.values().map(|l| l.loc_ref()).for_each_loc(|sub_type| {
+            let sub_type_name = Rc::new(sub_type.name.join(TYPE_SEP));
+            let member = Rc::new(sub_type.local_name.as_str().to_owned());
+
+            members.push(toks![member.clone(), " *", sub_type_name.clone()]);
+
+            let fields = body.fields.iter().chain(sub_type.fields.iter());
+
+            let mut sub_type_elements = Tokens::new();
+            sub_type_elements.push(self.build_struct(out, sub_type_name.clone(), fields)?);
+            sub_type_elements.push_unless_empty(Code(&sub_type.codes, GO_CONTEXT));
+            sub_types.push(sub_type_elements.join_line_spacing());
+
+            decode_cases.push({
+                let mut ids = Tokens::new();
+
+                for id in &sub_type.names {
+                    ids.append(id.as_str().quoted());
+                }
+
+                let mut case_body = Tokens::new();
+                case_body.push(toks!["sub := ", sub_type_name.clone(), "{}"]);
+                case_body.push(self.check_err(toks!["json.Unmarshal(b, &sub)"], "err"));
+                case_body.push({
+                    let mut t = Tokens::new();
+                    t.push(toks!["this.", member.clone(), " = &sub"]);
+                    t.push("return nil");
+                    t
+                });
+
+                let mut t = Tokens::new();
+                t.push(toks!["case ", ids.join(", "), ":"]);
+                t.nested(case_body.join_line_spacing());
+                t
+            });
+
+            encode_cases.push({
+                let id = Rc::new(sub_type.name().to_owned());
+
+                let mut t = Tokens::new();
+                t.push(toks!["case this.", member.clone(), " != nil:"]);
+                t.nested(toks!["t, sub = ", id.quoted(), ", this.", member]);
+                t
+            });
+
+            Ok(()) as Result<()>
+        })?;
+
+        let mut elements = Tokens::new();
+
+        elements.push({
+            let mut t = Tokens::new();
+            t.push(toks!["type ", name.clone(), " struct {"]);
+            t.nested(members);
+            t.push("}");
+            t
+        });
+
+        elements.push_unless_empty(sub_types.join_line_spacing());
+
+        // decode by inspecting the type field.
+        elements.push({
+            let mut decode = Tokens::new();
+
+            decode.push({
+                let mut t = Tokens::new();
+                t.push("var envelope struct {");
+                t.nested(toks!["Type string `json:", type_var.clone(), "`"]);
+                t.push("}");
+                t
+            });
+
+            decode.push(self.check_err(toks!["json.Unmarshal(b, &envelope)"], "err"));
+
+            decode.push({
+                let mut t = Tokens::new();
+                t.push("switch envelope.Type {");
+                t.push(decode_cases);
+                t.push("default:");
+                t.nested(toks!["return errors.New(", "bad type: ".quoted(), " + envelope.Type)"]);
+                t.push("}");
+                t
+            });
+
+            self.build_method(
+                toks!["*", name.clone()],
+                toks!["UnmarshalJSON(b []byte) error"],
+                decode.join_line_spacing(),
+            )
+        });
+
+        // encode whichever sub-type is set, and add the type field.
+        elements.push({
+            let mut encode = Tokens::new();
+
+            encode.push({
+                let mut t = Tokens::new();
+                t.push("var t string");
+                t.push("var sub interface{}");
+                t
+            });
+
+            encode.push({
+                let mut t = Tokens::new();
+                t.push("switch {");
+                t.push(encode_cases);
+                t.push("default:");
+                t.nested(toks![
+                    "return nil, errors.New(",
+                    format!("{}: no sub-type set", name).quoted(),
+                    ")",
+                ]);
+                t.push("}");
+                t
+            });
+
+            encode.push({
+                let mut t = Tokens::new();
+                t.push("b, err := json.Marshal(sub)");
+                t.push({
+                    let mut check = Tokens::new();
+                    check.push("if err != nil {");
+                    check.nested("return nil, err");
+                    check.push("}");
+                    check
+                });
+                t.join_line_spacing()
+            });
+
+            encode.push("var o map[string]json.RawMessage");
+            encode.push(self.check_err(toks!["json.Unmarshal(b, &o)"], "nil, err"));
+
+            encode.push({
+                let mut t = Tokens::new();
+                t.push(toks!["if o[", type_var.clone(), "], err = json.Marshal(t); err != nil {"]);
+                t.nested("return nil, err");
+                t.push("}");
+                t
+            });
+
+            encode.push("return json.Marshal(o)");
+
+            self.build_method(
+                toks![name.clone()],
+                toks!["MarshalJSON() ([]byte, error)"],
+                encode.join_line_spacing(),
+            )
+        });
+
+        elements.push_unless_empty(Code(&body.codes, GO_CONTEXT));
+
+        out.body.push(elements.join_line_spacing());
+        Ok(())
+    }
+}
+
+impl PackageUtils for GoBackend {}
+
+impl<'el> Converter<'el> for GoBackend {
+    type Custom = ();
+
+    fn convert_type(&self, name: &'el RpName) -> Result<Tokens<'el, ()>> {
+        let registered = self.env.lookup(name)?;
+
+        let local_name = registered.local_name(name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP));
+
+        if let Some(ref prefix) = name.prefix {
+            return Ok(toks![prefix.as_str(), ".", local_name]);
+        }
+
+        Ok(toks![local_name])
+    }
+}
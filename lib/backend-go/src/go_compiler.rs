@@ -0,0 +1,89 @@
+//! Compiler for Go Backend
+
+use super::EXT;
+use backend::{Environment, PackageProcessor, PackageUtils};
+use backend::errors::*;
+use core::{Loc, RpEnumBody, RpInterfaceBody, RpPackage, RpTupleBody, RpTypeBody,
+           RpVersionedPackage};
+use go_backend::GoBackend;
+use go_file_spec::GoFileSpec;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub struct GoCompiler<'el> {
+    pub out_path: PathBuf,
+    pub backend: &'el GoBackend,
+}
+
+impl<'el> GoCompiler<'el> {
+    pub fn compile(&self) -> Result<()> {
+        let mut files = self.populate_files()?;
+
+        // every file needs to know which package it belongs to.
+        for (package, out) in &mut files {
+            let package = self.processed_package(package);
+            let name = package.parts.last().cloned().ok_or_else(
+                || "cannot generate Go code for the root package",
+            )?;
+
+            out.package = Some(Rc::new(name));
+        }
+
+        self.write_files(files)
+    }
+}
+
+impl<'el> PackageProcessor<'el> for GoCompiler<'el> {
+    type Out = GoFileSpec<'el>;
+
+    fn ext(&self) -> &str {
+        EXT
+    }
+
+    fn env(&self) -> &'el Environment {
+        &self.backend.env
+    }
+
+    fn out_path(&self) -> &Path {
+        &self.out_path
+    }
+
+    fn processed_package(&self, package: &RpVersionedPackage) -> RpPackage {
+        self.backend.package(package)
+    }
+
+    fn process_tuple(&self, out: &mut Self::Out, body: &'el Loc<RpTupleBody>) -> Result<()> {
+        self.backend.process_tuple(out, body)
+    }
+
+    fn process_enum(&self, out: &mut Self::Out, body: &'el Loc<RpEnumBody>) -> Result<()> {
+        self.backend.process_enum(out, body)
+    }
+
+    fn process_type(&self, out: &mut Self::Out, body: &'el Loc<RpTypeBody>) -> Result<()> {
+        self.backend.process_type(out, body)
+    }
+
+    fn process_interface(
+        &self,
+        out: &mut Self::Out,
+        body: &'el Loc<RpInterfaceBody>,
+    ) -> Result<()> {
+        self.backend.process_interface(out, body)
+    }
+
+    /// Go packages are directories, so each package is placed in its own directory containing a
+    /// single file named after the package.
+    fn resolve_full_path(&self, package: &RpPackage) -> Result<PathBuf> {
+        let full_path = self.out_path().to_owned();
+        let full_path = package.parts.iter().fold(full_path, |a, b| a.join(b));
+
+        let mut full_path = match package.parts.last() {
+            Some(last) => full_path.join(last),
+            None => return Err("cannot generate Go code for the root package".into()),
+        };
+
+        full_path.set_extension(self.ext());
+        Ok(full_path)
+    }
+}
@@ -0,0 +1,61 @@
+//! File spec collecting results from backends
+
+use backend::IntoBytes;
+use backend::errors::*;
+use genco::{Quoted, Tokens};
+use go_compiler::GoCompiler;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+pub struct GoFileSpec<'el> {
+    /// Name of the package, used in the package clause.
+    pub package: Option<Rc<String>>,
+    /// Imports, mapping import path to its alias.
+    pub imports: BTreeMap<String, String>,
+    pub body: Tokens<'el, ()>,
+}
+
+impl<'el> GoFileSpec<'el> {
+    /// Register an import with the given alias.
+    pub fn import(&mut self, path: &str, alias: &str) {
+        self.imports.insert(path.to_owned(), alias.to_owned());
+    }
+}
+
+impl<'el> Default for GoFileSpec<'el> {
+    fn default() -> Self {
+        GoFileSpec {
+            package: None,
+            imports: BTreeMap::new(),
+            body: Tokens::new(),
+        }
+    }
+}
+
+impl<'el> IntoBytes<GoCompiler<'el>> for GoFileSpec<'el> {
+    fn into_bytes(self, _: &GoCompiler<'el>) -> Result<Vec<u8>> {
+        let package = self.package.ok_or_else(|| "missing package name")?;
+
+        let mut file: Tokens<()> = Tokens::new();
+
+        file.push(toks!["package ", package]);
+
+        if !self.imports.is_empty() {
+            let mut imports = Tokens::new();
+
+            imports.push("import (");
+
+            for (path, alias) in self.imports {
+                imports.nested(toks![alias, " ", path.quoted()]);
+            }
+
+            imports.push(")");
+            file.push(imports);
+        }
+
+        file.push_unless_empty(self.body.join_line_spacing());
+
+        let out = file.join_line_spacing().to_file()?;
+        Ok(out.into_bytes())
+    }
+}
@@ -0,0 +1,10 @@
+pub struct GoOptions {
+    /// Import path that generated packages are relative to.
+    pub import_root: Option<String>,
+}
+
+impl GoOptions {
+    pub fn new() -> GoOptions {
+        GoOptions { import_root: None }
+    }
+}
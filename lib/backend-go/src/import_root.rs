@@ -0,0 +1,28 @@
+//! Module that sets the path that imports of generated packages are relative to.
+
+use backend::errors::*;
+use go_options::GoOptions;
+use listeners::Listeners;
+
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    /// Import path of the directory that code is generated into, like `github.com/foo/bar`.
+    pub path: String,
+}
+
+pub struct Module {
+    config: Config,
+}
+
+impl Module {
+    pub fn new(config: Config) -> Module {
+        Module { config: config }
+    }
+}
+
+impl Listeners for Module {
+    fn configure(&self, options: &mut GoOptions) -> Result<()> {
+        options.import_root = Some(self.config.path.trim_right_matches('/').to_string());
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate genco;
+#[allow(unused)]
+#[macro_use]
+extern crate serde_derive;
+extern crate reproto_backend as backend;
+extern crate reproto_core as core;
+extern crate reproto_manifest as manifest;
+extern crate serde;
+extern crate toml;
+
+mod listeners;
+mod go_backend;
+mod import_root;
+mod go_compiler;
+mod go_file_spec;
+mod go_options;
+
+use self::backend::{ArgMatches, CompilerOptions, Environment, Options};
+use self::backend::errors::*;
+use self::go_backend::GoBackend;
+use self::go_options::GoOptions;
+use self::listeners::Listeners;
+use manifest::{Lang, Manifest, NoModule, TryFromToml, self as m};
+use std::path::Path;
+
+const TYPE: &str = "type";
+const TYPE_SEP: &str = "_";
+const EXT: &str = "go";
+const GO_CONTEXT: &str = "go";
+
+#[derive(Default)]
+pub struct GoLang;
+
+impl Lang for GoLang {
+    type Module = GoModule;
+}
+
+#[derive(Debug)]
+pub enum GoModule {
+    ImportRoot(import_root::Config),
+}
+
+impl TryFromToml for GoModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> m::errors::Result<Self> {
+        use self::GoModule::*;
+
+        let result = match id {
+            "import_root" => ImportRoot(import_root::Config { path: value }),
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> m::errors::Result<Self> {
+        use self::GoModule::*;
+
+        let result = match id {
+            "import_root" => ImportRoot(value.try_into()?),
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
+    }
+}
+
+fn setup_listeners(modules: Vec<GoModule>) -> Result<(GoOptions, Box<Listeners>)> {
+    use self::GoModule::*;
+
+    let mut listeners: Vec<Box<Listeners>> = Vec::new();
+
+    for module in modules {
+        let listener = match module {
+            ImportRoot(config) => Box::new(import_root::Module::new(config)) as Box<Listeners>,
+        };
+
+        listeners.push(listener);
+    }
+
+    let mut options = GoOptions::new();
+
+    for listener in &listeners {
+        listener.configure(&mut options)?;
+    }
+
+    Ok((options, Box::new(listeners)))
+}
+
+pub fn compile(
+    env: Environment,
+    _opts: Options,
+    compiler_options: CompilerOptions,
+    _matches: &ArgMatches,
+    manifest: Manifest<GoLang>,
+) -> Result<()> {
+    let (options, listeners) = setup_listeners(manifest.modules)?;
+    let backend = GoBackend::new(env, options, listeners);
+    let compiler = backend.compiler(compiler_options)?;
+    compiler.compile()
+}
@@ -4,7 +4,8 @@ use super::JAVA_CONTEXT;
 use backend::{CamelCase, Code, Converter, Environment, FromNaming, Naming, SnakeCase};
 use backend::errors::*;
 use core::{ForEachLoc, Loc, RpDecl, RpEnumBody, RpEnumType, RpField, RpInterfaceBody, RpName,
-           RpPackage, RpServiceBody, RpTupleBody, RpType, RpTypeBody, RpVersionedPackage};
+           RpNumber, RpOrdinalValue, RpPackage, RpServiceBody, RpTupleBody, RpType, RpTypeBody,
+           RpValue, RpVersionedPackage};
 use genco::{Cons, Element, IoFmt, Java, Quoted, Tokens, WriteTokens};
 use genco::java::{Argument, BOOLEAN, Class, Constructor, DOUBLE, Enum, Extra, FLOAT, Field,
                   INTEGER, Interface, LONG, Method, Modifier, imported, local, optional};
@@ -38,6 +39,7 @@ pub struct JavaBackend {
     illegal_argument: Java<'static>,
     async_container: Java<'static>,
     byte_buffer: Java<'static>,
+    pattern: Java<'static>,
 }
 
 impl JavaBackend {
@@ -71,6 +73,7 @@ impl JavaBackend {
             illegal_argument: imported("java.lang", "IllegalArgumentException"),
             async_container: async_container,
             byte_buffer: imported("java.nio", "ByteBuffer"),
+            pattern: imported("java.util.regex", "Pattern"),
         }
     }
 
@@ -169,7 +172,25 @@ impl JavaBackend {
                 let argument = self.into_java_type(inner)?;
                 self.list.with_arguments(vec![argument]).into()
             }
-            Name { ref name } => self.convert_type_id(name)?,
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                let name = self.convert_type_id(name)?;
+
+                if arguments.is_empty() {
+                    name
+                } else {
+                    let mut out = Vec::new();
+
+                    for argument in arguments {
+                        out.push(self.into_java_type(argument)?);
+                    }
+
+                    name.with_arguments(out)
+                }
+            }
+            Generic { ref name } => local(Rc::new(name.clone())),
             Map { ref key, ref value } => {
                 let key = self.into_java_type(key)?;
                 let value = self.into_java_type(value)?;
@@ -186,8 +207,8 @@ impl JavaBackend {
     fn build_constructor<'el>(&self, fields: &[JavaField<'el>]) -> Constructor<'el> {
         let mut c = Constructor::new();
 
-        for field in fields {
-            let field = &field.spec;
+        for java_field in fields {
+            let field = &java_field.spec;
 
             let argument = Argument::new(field.ty(), field.var());
 
@@ -197,20 +218,182 @@ impl JavaBackend {
                 }
             }
 
+            c.body.push_unless_empty(self.constraint_checks(java_field, &argument));
             c.arguments.push(argument.clone());
 
-            c.body.push(toks![
-                "this.",
-                field.var(),
-                " = ",
-                argument.var(),
-                ";",
-            ]);
+            let value = match java_field.default {
+                Some(ref default) => self.default_value(java_field, &argument, default),
+                None => toks![argument.var()],
+            };
+
+            c.body.push(toks!["this.", field.var(), " = ", value, ";"]);
         }
 
         c
     }
 
+    /// Build a numeric literal, using a `long` literal if it doesn't fit in an `int`.
+    fn number_literal<'el>(&self, number: &RpNumber) -> Tokens<'el, Java<'el>> {
+        if number.decimal == 0 && !number.fits_signed(32) {
+            return toks![format!("{}L", number)];
+        }
+
+        toks![number.to_string()]
+    }
+
+    /// Build a literal of the Java type that the given integer type is mapped to.
+    ///
+    /// Unsigned values which don't fit in the signed Java type are stored with the same bits,
+    /// which requires a cast for `int`, and a hexadecimal literal for `long`.
+    fn integer_literal<'el>(&self, ty: &RpType, number: &RpNumber) -> Tokens<'el, Java<'el>> {
+        match *ty {
+            RpType::Signed { size: 64 } |
+            RpType::Unsigned { size: 64 } => {
+                if number.fits_signed(64) {
+                    return toks![format!("{}L", number)];
+                }
+
+                match number.to_u64() {
+                    Some(value) => toks![format!("0x{:x}L", value)],
+                    None => toks![format!("{}L", number)],
+                }
+            }
+            _ => {
+                if number.fits_signed(32) {
+                    return toks![number.to_string()];
+                }
+
+                toks![format!("(int) {}L", number)]
+            }
+        }
+    }
+
+    /// Build an expression which falls back to the default value if the argument is absent.
+    fn default_value<'el>(
+        &self,
+        field: &JavaField<'el>,
+        argument: &Argument<'el>,
+        default: &RpValue,
+    ) -> Tokens<'el, Java<'el>> {
+        let literal = match (&field.ty, default) {
+            (_, &RpValue::Boolean(true)) => toks!["true"],
+            (_, &RpValue::Boolean(false)) => toks!["false"],
+            (_, &RpValue::String(ref string)) => toks![string.as_str().quoted()],
+            (&RpType::Float, &RpValue::Number(ref number)) => toks![format!("{}f", number)],
+            (&RpType::Double, &RpValue::Number(ref number)) => toks![format!("{}d", number)],
+            (&RpType::Signed { .. }, &RpValue::Number(ref number)) |
+            (&RpType::Unsigned { .. }, &RpValue::Number(ref number)) => {
+                self.integer_literal(&field.ty, number)
+            }
+            (_, &RpValue::Number(ref number)) => self.number_literal(number),
+            (_, value) => toks![value.to_string()],
+        };
+
+        match field.spec.ty() {
+            Java::Optional { .. } => toks![
+                argument.var(),
+                ".isPresent() ? ",
+                argument.var(),
+                " : ",
+                self.optional.clone(),
+                ".of(",
+                literal,
+                ")",
+            ],
+            _ => toks![argument.var(), " != null ? ", argument.var(), " : ", literal],
+        }
+    }
+
+    /// Build checks that the given argument satisfies the constraints of the field.
+    fn constraint_checks<'el>(
+        &self,
+        field: &JavaField<'el>,
+        argument: &Argument<'el>,
+    ) -> Tokens<'el, Java<'el>> {
+        let constraints = &field.constraints;
+
+        let (guard, value) = match field.spec.ty() {
+            Java::Optional { .. } => (
+                Some(toks![argument.var(), ".isPresent()"]),
+                toks![argument.var(), ".get()"],
+            ),
+            Java::Primitive { .. } => (None, toks![argument.var()]),
+            _ if self.options.nullable => (
+                Some(toks![argument.var(), " != null"]),
+                toks![argument.var()],
+            ),
+            _ => (None, toks![argument.var()]),
+        };
+
+        let length = match field.ty {
+            RpType::String => toks![value.clone(), ".length()"],
+            _ => toks![value.clone(), ".size()"],
+        };
+
+        let mut checks = Vec::new();
+
+        if let Some(ref min) = constraints.min {
+            checks.push((
+                toks![value.clone(), " < ", self.number_literal(min)],
+                format!("must be greater than or equal to {}", min),
+            ));
+        }
+
+        if let Some(ref max) = constraints.max {
+            checks.push((
+                toks![value.clone(), " > ", self.number_literal(max)],
+                format!("must be less than or equal to {}", max),
+            ));
+        }
+
+        if let Some(min_length) = constraints.min_length {
+            checks.push((
+                toks![length.clone(), " < ", min_length.to_string()],
+                format!("length must be at least {}", min_length),
+            ));
+        }
+
+        if let Some(max_length) = constraints.max_length {
+            checks.push((
+                toks![length.clone(), " > ", max_length.to_string()],
+                format!("length must be at most {}", max_length),
+            ));
+        }
+
+        if let Some(ref pattern) = constraints.pattern {
+            checks.push((
+                toks![
+                    "!",
+                    self.pattern.clone(),
+                    ".compile(",
+                    Rc::new(pattern.to_string()).quoted(),
+                    ").matcher(",
+                    value.clone(),
+                    ").find()",
+                ],
+                format!("must match pattern `{}`", pattern),
+            ));
+        }
+
+        let name: &str = field.name.as_ref();
+        let mut t = Tokens::new();
+
+        for (condition, message) in checks {
+            let condition = match guard {
+                Some(ref guard) => toks![guard.clone(), " && ", condition],
+                None => condition,
+            };
+
+            let message = Rc::new(format!("{}: {}", name, message)).quoted();
+
+            t.push(toks!["if (", condition, ") {"]);
+            t.nested(toks!["throw new ", self.illegal_argument.clone(), "(", message, ");"]);
+            t.push("}");
+        }
+
+        t
+    }
+
     /// Build a require-non-null check.
     fn require_non_null<'el>(
         &self,
@@ -557,6 +740,7 @@ impl JavaBackend {
 
         match *ty {
             String => Ok(self.string.clone().into()),
+            Signed { .. } | Unsigned { .. } => self.into_java_type(&ty.as_type()),
             Generated => Ok(self.string.clone().into()),
         }
     }
@@ -575,7 +759,13 @@ impl JavaBackend {
 
             let mut enum_toks = toks![name];
 
-            let value = self.ordinal(variant)?;
+            let value = match variant.ordinal() {
+                RpOrdinalValue::Number(number) => {
+                    self.integer_literal(&body.variant_type.as_type(), number)
+                }
+                _ => self.ordinal(variant)?,
+            };
+
             enum_toks.append(toks!["(", value, ")"]);
 
             enum_value.push(enum_toks);
@@ -606,8 +796,28 @@ impl JavaBackend {
         Ok(spec)
     }
 
+    /// Add the type parameters of a declaration to the given parameters.
+    fn type_parameters<'el>(
+        &self,
+        parameters: &mut Tokens<'el, Java<'el>>,
+        type_parameters: &'el [String],
+    ) {
+        if type_parameters.is_empty() {
+            return;
+        }
+
+        let mut out = Tokens::new();
+
+        for parameter in type_parameters {
+            out.append(local(parameter.as_str()));
+        }
+
+        parameters.append(out.join(", "));
+    }
+
     fn process_tuple<'el>(&self, body: &'el RpTupleBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.local_name.clone());
+        self.type_parameters(&mut spec.parameters, &body.type_parameters);
 
         let fields = self.convert_fields(&body.fields)?;
 
@@ -643,6 +853,7 @@ impl JavaBackend {
 
     fn process_type<'el>(&self, body: &'el RpTypeBody) -> Result<Class<'el>> {
         let mut spec = Class::new(body.local_name.clone());
+        self.type_parameters(&mut spec.parameters, &body.type_parameters);
         let fields = self.convert_fields(&body.fields)?;
         let names: Vec<_> = fields.iter().map(|f| f.name.clone()).collect();
 
@@ -680,11 +891,25 @@ impl JavaBackend {
     fn process_interface<'el>(&self, body: &'el RpInterfaceBody) -> Result<Interface<'el>> {
         use self::Modifier::*;
         let mut spec = Interface::new(body.local_name.clone());
+        self.type_parameters(&mut spec.parameters, &body.type_parameters);
+
         let interface_fields = self.convert_fields(&body.fields)?;
 
+        let interface_type = if body.type_parameters.is_empty() {
+            local(spec.name())
+        } else {
+            let arguments = body.type_parameters
+                .iter()
+                .map(|p| local(p.as_str()))
+                .collect();
+
+            local(spec.name()).with_arguments(arguments)
+        };
+
         body.sub_types.values().for_each_loc(|sub_type| {
             let mut class = Class::new(sub_type.local_name.clone());
             class.modifiers = vec![Public, Static];
+            self.type_parameters(&mut class.parameters, &body.type_parameters);
 
             let sub_type_fields = self.convert_fields(&sub_type.fields)?;
 
@@ -692,7 +917,7 @@ impl JavaBackend {
                 Code(&sub_type.codes, JAVA_CONTEXT),
             );
 
-            class.implements = vec![local(spec.name())];
+            class.implements = vec![interface_type.clone()];
 
             // override methods for interface fields.
             for field in &interface_fields {
@@ -824,6 +1049,9 @@ impl JavaBackend {
             name: Rc::new(field.name().to_string()).into(),
             camel_name: camel_name,
             spec: spec,
+            ty: field.ty.clone(),
+            constraints: field.constraints.clone(),
+            default: field.default.as_ref().map(|d| d.value().clone()),
         })
     }
 
@@ -1,4 +1,5 @@
 pub use super::*;
+use core::{RpConstraints, RpType, RpValue};
 use genco::Cons;
 use genco::java::{Argument, Field, Method, Modifier};
 use std::rc::Rc;
@@ -9,6 +10,12 @@ pub struct JavaField<'a> {
     pub name: Cons<'a>,
     pub camel_name: Rc<String>,
     pub spec: Field<'a>,
+    /// Type of the field, before conversion.
+    pub ty: RpType,
+    /// Constraints on the value of the field.
+    pub constraints: RpConstraints,
+    /// Default value of the field, if any.
+    pub default: Option<RpValue>,
 }
 
 impl<'el> JavaField<'el> {
@@ -3,7 +3,7 @@ use backend::{Code, CompilerOptions, Converter, DynamicConverter, DynamicDecode,
               Environment, FromNaming, Naming, PackageUtils, SnakeCase};
 use backend::errors::*;
 use core::{ForEachLoc, Loc, RpEnumBody, RpField, RpInterfaceBody, RpModifier, RpName, RpTupleBody,
-           RpType, RpTypeBody};
+           RpType, RpTypeBody, RpValue, WithPos};
 use genco::{Element, JavaScript, Quoted, Tokens};
 use genco::js::imported_alias;
 use js_compiler::JsCompiler;
@@ -62,6 +62,7 @@ impl JsBackend {
     fn encode_method<'el, B>(
         &self,
         fields: &[Loc<JsField<'el>>],
+        type_parameters: &[String],
         builder: B,
         extra: Option<Tokens<'el, JavaScript<'el>>>,
     ) -> Result<Tokens<'el, JavaScript<'el>>>
@@ -105,9 +106,11 @@ impl JsBackend {
 
         body.push(js![return "data"]);
 
+        let arguments = self.type_parameter_functions("encode", type_parameters);
+
         Ok({
             let mut t = Tokens::new();
-            t.push("encode() {");
+            t.push(toks!["encode(", arguments, ") {"]);
             t.nested(body.join_line_spacing());
             t.push("}");
             t
@@ -117,6 +120,7 @@ impl JsBackend {
     fn encode_tuple_method<'el>(
         &self,
         fields: &[Loc<JsField<'el>>],
+        type_parameters: &[String],
     ) -> Result<Tokens<'el, JavaScript<'el>>> {
         let mut values = Tokens::new();
 
@@ -131,8 +135,10 @@ impl JsBackend {
 
         body.push(js![@return [ values ]]);
 
+        let arguments = self.type_parameter_functions("encode", type_parameters);
+
         let mut encode = Tokens::new();
-        encode.push("encode() {");
+        encode.push(toks!["encode(", arguments, ") {"]);
         encode.nested(body.join_line_spacing());
         encode.push("}");
         Ok(encode)
@@ -167,9 +173,87 @@ impl JsBackend {
         Ok(decode)
     }
 
+    /// Build the literal for the default value of a field.
+    fn default_value<'el>(&self, value: &RpValue) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let out = match *value {
+            RpValue::Boolean(true) => toks!["true"],
+            RpValue::Boolean(false) => toks!["false"],
+            RpValue::String(ref string) => toks![string.as_str().quoted()],
+            RpValue::Number(ref number) => toks![number.to_string()],
+            ref value => return Err(format!("unsupported default value: {}", value).into()),
+        };
+
+        Ok(out)
+    }
+
+    /// Build checks that the decoded value `var` satisfies the constraints of the field.
+    fn constraint_checks<'el>(
+        &self,
+        field: &JsField<'el>,
+        var: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let constraints = field.constraints;
+        let mut checks = Vec::new();
+
+        let length = match *field.ty {
+            RpType::Map { .. } => toks!["Object.keys(", var.clone(), ").length"],
+            _ => toks![var.clone(), ".length"],
+        };
+
+        if let Some(ref min) = constraints.min {
+            checks.push((
+                toks![var.clone(), " < ", min.to_string()],
+                format!("must be greater than or equal to {}", min),
+            ));
+        }
+
+        if let Some(ref max) = constraints.max {
+            checks.push((
+                toks![var.clone(), " > ", max.to_string()],
+                format!("must be less than or equal to {}", max),
+            ));
+        }
+
+        if let Some(min_length) = constraints.min_length {
+            checks.push((
+                toks![length.clone(), " < ", min_length.to_string()],
+                format!("length must be at least {}", min_length),
+            ));
+        }
+
+        if let Some(max_length) = constraints.max_length {
+            checks.push((
+                toks![length.clone(), " > ", max_length.to_string()],
+                format!("length must be at most {}", max_length),
+            ));
+        }
+
+        if let Some(ref pattern) = constraints.pattern {
+            checks.push((
+                toks!["!new RegExp(", pattern.as_str().quoted(), ").test(", var.clone(), ")"],
+                format!("must match pattern `{}`", pattern),
+            ));
+        }
+
+        let mut t = Tokens::new();
+
+        for (condition, message) in checks {
+            let condition = match *field.modifier {
+                RpModifier::Optional => toks![var.clone(), " !== null && ", condition],
+                _ => condition,
+            };
+
+            let message = format!("{}: {}", field.name, message).quoted();
+            t.push(js![if condition, js![throw message]]);
+        }
+
+        t
+    }
+
     fn decode_method<'el, F>(
         &self,
         fields: &[Loc<JsField<'el>>],
+        type_parameters: &[String],
         type_name: Rc<String>,
         variable_fn: F,
     ) -> Result<Tokens<'el, JavaScript<'el>>>
@@ -188,12 +272,17 @@ impl JsBackend {
                     let var_name = toks![var_name.clone()];
                     let var_toks = self.dynamic_decode(field.ty, var_name.clone())?;
 
+                    let default = match field.default {
+                        Some(default) => self.default_value(default).with_pos(default.pos())?,
+                        None => toks!["null"],
+                    };
+
                     let mut check = Tokens::new();
 
                     check.push(toks!["let ", var_name.clone(), " = data[", var, "];"]);
                     check.push(js![if is_defined(var_name.clone()),
                                       toks![var_name.clone(), " = ", var_toks, ";"],
-                                      toks![var_name, " = null", ";"]]);
+                                      toks![var_name, " = ", default, ";"]]);
 
                     Ok(check.join_line_spacing().into()) as Result<Tokens<'el, JavaScript<'el>>>
                 }
@@ -214,6 +303,7 @@ impl JsBackend {
             })?;
 
             assign.push(toks);
+            assign.push_unless_empty(self.constraint_checks(field, toks![var_name.clone()]));
             arguments.append(var_name);
         }
 
@@ -225,13 +315,53 @@ impl JsBackend {
 
         body.push(js![@return new type_name, arguments]);
 
+        let mut decode_arguments = Tokens::new();
+        decode_arguments.append("data");
+
+        for parameter in type_parameters {
+            decode_arguments.append(format!("decode{}", parameter));
+        }
+
         let mut decode = Tokens::new();
-        decode.push("static decode(data) {");
+        decode.push(toks!["static decode(", decode_arguments.join(", "), ") {"]);
         decode.nested(body.join_line_spacing());
         decode.push("}");
         Ok(decode)
     }
 
+    /// Build the list of functions that encode or decode each type parameter.
+    fn type_parameter_functions<'el>(
+        &self,
+        prefix: &str,
+        type_parameters: &[String],
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut out = Tokens::new();
+
+        for parameter in type_parameters {
+            out.append(format!("{}{}", prefix, parameter));
+        }
+
+        out.join(", ")
+    }
+
+    /// Build a JSDoc comment documenting the type parameters of a class.
+    fn type_parameters_doc<'el>(&self, type_parameters: &[String]) -> Tokens<'el, JavaScript<'el>> {
+        let mut doc = Tokens::new();
+
+        if type_parameters.is_empty() {
+            return doc;
+        }
+
+        doc.push("/**");
+
+        for parameter in type_parameters {
+            doc.push(format!(" * @template {}", parameter));
+        }
+
+        doc.push(" */");
+        doc
+    }
+
     fn field_by_name<'el>(_i: usize, field: &JsField<'el>) -> Element<'el, JavaScript<'el>> {
         field.name.quoted()
     }
@@ -362,6 +492,8 @@ impl JsBackend {
             ty: &field.ty,
             name: field.name(),
             ident: Rc::new(ident),
+            constraints: &field.constraints,
+            default: field.default.as_ref(),
         })
     }
 
@@ -393,15 +525,17 @@ impl JsBackend {
 
         class_body.push(self.decode_method(
             &fields,
+            &body.type_parameters,
             tuple_name.clone(),
             Self::field_by_index,
         )?);
 
-        class_body.push(self.encode_tuple_method(&fields)?);
+        class_body.push(self.encode_tuple_method(&fields, &body.type_parameters)?);
         class_body.push_unless_empty(Code(&body.codes, JS_CONTEXT));
 
         let mut class = Tokens::new();
 
+        class.push_unless_empty(self.type_parameters_doc(&body.type_parameters));
         class.push(toks!["export class ", tuple_name, " {"]);
         class.nested(class_body.join_line_spacing());
         class.push("}");
@@ -499,15 +633,17 @@ impl JsBackend {
 
         class_body.push(self.decode_method(
             &fields,
+            &body.type_parameters,
             type_name.clone(),
             Self::field_by_name,
         )?);
 
-        class_body.push(self.encode_method(&fields, "{}", None)?);
+        class_body.push(self.encode_method(&fields, &body.type_parameters, "{}", None)?);
         class_body.push_unless_empty(Code(&body.codes, JS_CONTEXT));
 
         let mut class = Tokens::new();
 
+        class.push_unless_empty(self.type_parameters_doc(&body.type_parameters));
         class.push(toks!["export class ", type_name, " {"]);
         class.nested(class_body.join_line_spacing());
         class.push("}");
@@ -521,6 +657,12 @@ impl JsBackend {
         out: &mut JsFileSpec<'el>,
         body: &'el RpInterfaceBody,
     ) -> Result<()> {
+        if !body.type_parameters.is_empty() {
+            return Err(
+                format!("{}: generic interfaces are not supported", body.name).into(),
+            );
+        }
+
         let mut classes = Tokens::new();
         let interface_type_name = Rc::new(body.name.join(TYPE_SEP));
 
@@ -570,6 +712,7 @@ impl JsBackend {
 
             class_body.push(self.decode_method(
                 &fields,
+                &[],
                 type_name.clone(),
                 Self::field_by_name,
             )?);
@@ -583,7 +726,7 @@ impl JsBackend {
                 ".TYPE;",
             ];
 
-            class_body.push(self.encode_method(&fields, "{}", Some(type_toks))?);
+            class_body.push(self.encode_method(&fields, &[], "{}", Some(type_toks))?);
             class_body.push_unless_empty(Code(&sub_type.codes, JS_CONTEXT));
 
             classes.push({
@@ -676,6 +819,34 @@ impl<'el> DynamicDecode<'el> for JsBackend {
         toks![name, ".decode(", input, ")"]
     }
 
+    /// Decoding a type with type arguments.
+    ///
+    /// A function decoding each argument is passed along to the decode method of the type.
+    fn name_arguments_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut out = Tokens::new();
+        out.append(input);
+
+        for argument in arguments {
+            let decode = self.dynamic_decode(argument, toks!["v"])?;
+            out.append(toks!["function(v) { return ", decode, "; }"]);
+        }
+
+        Ok(toks![self.convert_type(name)?, ".decode(", out.join(", "), ")"])
+    }
+
+    fn generic_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        Ok(toks![format!("decode{}", name), "(", input, ")"])
+    }
+
     /// Decoding an Array in JavaScript.
     ///
     /// Maps over each decoded value using `Array.map(...)`, decoding each variable.
@@ -758,6 +929,33 @@ impl<'el> DynamicEncode<'el> for JsBackend {
         toks![input, ".encode()"]
     }
 
+    /// Encoding a type with type arguments.
+    ///
+    /// A function encoding each argument is passed along to the encode method of the value.
+    fn name_arguments_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        _: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut out = Tokens::new();
+
+        for argument in arguments {
+            let encode = self.dynamic_encode(argument, toks!["v"])?;
+            out.append(toks!["function(v) { return ", encode, "; }"]);
+        }
+
+        Ok(toks![input, ".encode(", out.join(", "), ")"])
+    }
+
+    fn generic_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        Ok(toks![format!("encode{}", name), "(", input, ")"])
+    }
+
     fn array_encode(
         &self,
         input: Tokens<'el, JavaScript<'el>>,
@@ -1,4 +1,4 @@
-use core::{RpModifier, RpType};
+use core::{Loc, RpConstraints, RpModifier, RpType, RpValue};
 use std::rc::Rc;
 
 #[derive(Clone)]
@@ -7,6 +7,8 @@ pub struct JsField<'a> {
     pub ty: &'a RpType,
     pub name: &'a str,
     pub ident: Rc<String>,
+    pub constraints: &'a RpConstraints,
+    pub default: Option<&'a Loc<RpValue>>,
 }
 
 impl<'a> JsField<'a> {
@@ -16,6 +18,8 @@ impl<'a> JsField<'a> {
             ty: self.ty,
             name: self.name,
             ident: Rc::new(ident),
+            constraints: self.constraints,
+            default: self.default,
         }
     }
 }
@@ -9,13 +9,15 @@ use listeners::Listeners;
 
 pub struct JsonBackend {
     pub env: Environment,
+    pub options: JsonOptions,
     listeners: Box<Listeners>,
 }
 
 impl JsonBackend {
-    pub fn new(env: Environment, _options: JsonOptions, listeners: Box<Listeners>) -> JsonBackend {
+    pub fn new(env: Environment, options: JsonOptions, listeners: Box<Listeners>) -> JsonBackend {
         JsonBackend {
             env: env,
+            options: options,
             listeners: listeners,
         }
     }
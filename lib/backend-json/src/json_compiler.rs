@@ -7,8 +7,11 @@ use collector::Collector;
 use core::{Loc, RpEnumBody, RpInterfaceBody, RpName, RpPackage, RpServiceBody, RpTupleBody,
            RpTypeBody, RpVersionedPackage};
 use json_backend::JsonBackend;
+use schema::Schema;
 use serde_json;
 use std::fmt::Write;
+use std::fs::{self, File};
+use std::io::Write as IoWrite;
 use std::path::{Path, PathBuf};
 
 pub struct JsonCompiler<'el> {
@@ -18,10 +21,41 @@ pub struct JsonCompiler<'el> {
 
 impl<'el> JsonCompiler<'el> {
     pub fn compile(&self) -> Result<()> {
+        if self.processor.options.schema {
+            return self.compile_schemas();
+        }
+
         let files = self.populate_files()?;
         self.write_files(files)?;
         Ok(())
     }
+
+    /// Write one schema document per declaration.
+    fn compile_schemas(&self) -> Result<()> {
+        let schema = Schema::new(self.processor);
+
+        for decl in self.processor.env.decl_iter() {
+            let document = match schema.decl(decl)? {
+                Some(document) => document,
+                None => continue,
+            };
+
+            let full_path = self.out_path.join(schema.path(decl.name()));
+
+            if let Some(parent) = full_path.parent() {
+                if !parent.is_dir() {
+                    fs::create_dir_all(parent)?;
+                }
+            }
+
+            let mut f = File::create(full_path)?;
+            f.write_all(serde_json::to_string_pretty(&document)?.as_bytes())?;
+            f.write_all(b"\n")?;
+            f.flush()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<'el> PackageProcessor<'el> for JsonCompiler<'el> {
@@ -1,9 +1,12 @@
 //! Options for JSON
 
-pub struct JsonOptions {}
+pub struct JsonOptions {
+    /// Emit JSON Schema documents instead of the internal model.
+    pub schema: bool,
+}
 
 impl JsonOptions {
     pub fn new() -> JsonOptions {
-        JsonOptions {}
+        JsonOptions { schema: false }
     }
 }
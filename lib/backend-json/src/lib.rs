@@ -6,6 +6,7 @@ extern crate serde_derive;
 extern crate reproto_backend as backend;
 extern crate reproto_core as core;
 extern crate reproto_manifest as manifest;
+#[macro_use]
 extern crate serde_json;
 extern crate serde;
 extern crate toml;
@@ -15,6 +16,7 @@ mod json_backend;
 mod json_compiler;
 mod json_options;
 mod listeners;
+mod schema;
 
 use self::backend::{ArgMatches, CompilerOptions, Environment, Options};
 use self::backend::errors::*;
@@ -35,24 +37,44 @@ impl Lang for JsonLang {
 
 #[derive(Debug)]
 pub enum JsonModule {
+    Schema,
 }
 
 impl TryFromToml for JsonModule {
     fn try_from_string(path: &Path, id: &str, value: String) -> m::errors::Result<Self> {
-        NoModule::illegal(path, id, value)
+        use self::JsonModule::*;
+
+        let result = match id {
+            "schema" => Schema,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
     }
 
     fn try_from_value(path: &Path, id: &str, value: toml::Value) -> m::errors::Result<Self> {
-        NoModule::illegal(path, id, value)
+        use self::JsonModule::*;
+
+        let result = match id {
+            "schema" => Schema,
+            _ => return NoModule::illegal(path, id, value),
+        };
+
+        Ok(result)
     }
 }
 
 fn setup_listeners(modules: &[JsonModule]) -> Result<(JsonOptions, Box<Listeners>)> {
-    let listeners: Vec<Box<Listeners>> = Vec::new();
+    use self::JsonModule::*;
+
+    let mut listeners: Vec<Box<Listeners>> = Vec::new();
 
     for module in modules {
-        match *module {
-        }
+        let listener = match *module {
+            Schema => Box::new(schema::Module::new()) as Box<Listeners>,
+        };
+
+        listeners.push(listener);
     }
 
     let mut options = JsonOptions::new();
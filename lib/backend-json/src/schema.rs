@@ -0,0 +1,418 @@
+//! Module emitting JSON Schema (draft-07) documents.
+//!
+//! Every declaration is written to a separate document, and references between declarations are
+//! relative paths to those documents.
+
+use super::EXT;
+use backend::PackageUtils;
+use backend::errors::*;
+use core::{Loc, RpDecl, RpEnumBody, RpField, RpInterfaceBody, RpName, RpOrdinalValue,
+           RpTupleBody, RpType, RpTypeBody};
+use json_backend::JsonBackend;
+use json_options::JsonOptions;
+use listeners::Listeners;
+use serde_json::{Map as JsonMap, Value};
+
+const DRAFT_07: &str = "http://json-schema.org/draft-07/schema#";
+const TYPE: &str = "type";
+
+pub struct Module;
+
+impl Module {
+    pub fn new() -> Module {
+        Module
+    }
+}
+
+impl Listeners for Module {
+    fn configure(&self, options: &mut JsonOptions) -> Result<()> {
+        options.schema = true;
+        Ok(())
+    }
+}
+
+/// Builds schema documents for declarations.
+pub struct Schema<'a> {
+    backend: &'a JsonBackend,
+}
+
+impl<'a> Schema<'a> {
+    pub fn new(backend: &'a JsonBackend) -> Schema<'a> {
+        Schema { backend: backend }
+    }
+
+    /// Path to the document of the given name, relative to the output directory.
+    pub fn path(&self, name: &RpName) -> String {
+        let package = self.backend.package(&name.package);
+
+        format!(
+            "{}/{}.{}",
+            self.backend.package_file(&package),
+            name.parts.join("."),
+            EXT
+        )
+    }
+
+    /// Build the schema document for the given declaration.
+    ///
+    /// Returns `None` for declarations which do not describe a payload, like services.
+    pub fn decl(&self, decl: &RpDecl) -> Result<Option<Value>> {
+        use self::RpDecl::*;
+
+        let mut schema = match *decl {
+            Type(ref body) => self.type_body(body),
+            Tuple(ref body) => self.tuple_body(body),
+            Enum(ref body) => self.enum_body(body),
+            Interface(ref body) => self.interface_body(body)?,
+            Service(_) => return Ok(None),
+        };
+
+        if let Value::Object(ref mut o) = schema {
+            o.insert("$schema".to_string(), json!(DRAFT_07));
+            o.insert("title".to_string(), json!(decl.name().parts.join(".")));
+
+            let comment = decl.comment();
+
+            if !comment.is_empty() {
+                o.insert("description".to_string(), json!(comment.join("\n")));
+            }
+        }
+
+        Ok(Some(schema))
+    }
+
+    /// Schema for a single type.
+    fn ty(&self, ty: &RpType) -> Value {
+        use self::RpType::*;
+
+        match *ty {
+            Double | Float => json!({"type": "number"}),
+            Signed { .. } => json!({"type": "integer"}),
+            Unsigned { .. } => json!({"type": "integer", "minimum": 0}),
+            Boolean => json!({"type": "boolean"}),
+            String => json!({"type": "string"}),
+            DateTime => json!({"type": "string", "format": "date-time"}),
+            Bytes => json!({"type": "string", "contentEncoding": "base64"}),
+            // JSON Schema can't express type parameters, so generic types refer to the schema of
+            // their declaration where type parameters accept any value.
+            Any | Generic { .. } => json!({}),
+            Name { ref name, .. } => json!({"$ref": format!("../{}", self.path(name))}),
+            Array { ref inner } => json!({"type": "array", "items": self.ty(inner)}),
+            Map { ref value, .. } => {
+                json!({"type": "object", "additionalProperties": self.ty(value)})
+            }
+        }
+    }
+
+    /// Schema for a single field, including its documentation and constraints.
+    fn field(&self, field: &RpField) -> Value {
+        let mut schema = self.ty(&field.ty);
+
+        if let Value::Object(ref mut o) = schema {
+            if !field.comment.is_empty() {
+                o.insert("description".to_string(), json!(field.comment.join("\n")));
+            }
+
+            let constraints = &field.constraints;
+
+            let (min_length, max_length) = match field.ty {
+                RpType::Array { .. } => ("minItems", "maxItems"),
+                RpType::Map { .. } => ("minProperties", "maxProperties"),
+                _ => ("minLength", "maxLength"),
+            };
+
+            if let Some(ref min) = constraints.min {
+                o.insert("minimum".to_string(), json!(min));
+            }
+
+            if let Some(ref max) = constraints.max {
+                o.insert("maximum".to_string(), json!(max));
+            }
+
+            if let Some(min) = constraints.min_length {
+                o.insert(min_length.to_string(), json!(min));
+            }
+
+            if let Some(max) = constraints.max_length {
+                o.insert(max_length.to_string(), json!(max));
+            }
+
+            if let Some(ref pattern) = constraints.pattern {
+                o.insert("pattern".to_string(), json!(pattern));
+            }
+        }
+
+        schema
+    }
+
+    /// Build the properties and the required properties of an object.
+    fn properties<'b, I>(&self, fields: I) -> (JsonMap<String, Value>, Vec<Value>)
+    where
+        I: IntoIterator<Item = &'b Loc<RpField>>,
+    {
+        let mut properties = JsonMap::new();
+        let mut required = Vec::new();
+
+        for field in fields {
+            properties.insert(field.name().to_string(), self.field(field));
+
+            if !field.is_optional() {
+                required.push(json!(field.name()));
+            }
+        }
+
+        (properties, required)
+    }
+
+    fn type_body(&self, body: &RpTypeBody) -> Value {
+        let (properties, required) = self.properties(&body.fields);
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        })
+    }
+
+    /// Tuples are arrays where each position has a fixed type.
+    fn tuple_body(&self, body: &RpTupleBody) -> Value {
+        let items: Vec<Value> = body.fields.iter().map(|f| self.field(f)).collect();
+        let length = items.len();
+
+        json!({
+            "type": "array",
+            "items": items,
+            "additionalItems": false,
+            "minItems": length,
+            "maxItems": length,
+        })
+    }
+
+    /// Enums are a list of their ordinals.
+    fn enum_body(&self, body: &RpEnumBody) -> Value {
+        let values: Vec<Value> = body.variants
+            .iter()
+            .map(|v| match v.ordinal() {
+                RpOrdinalValue::String(string) => json!(string),
+                RpOrdinalValue::Number(number) => {
+                    number
+                        .to_i64()
+                        .map(|n| json!(n))
+                        .or_else(|| number.to_u64().map(|n| json!(n)))
+                        .unwrap_or_else(|| json!(number))
+                }
+            })
+            .collect();
+
+        let mut schema = self.ty(&body.variant_type.as_type());
+
+        if let Value::Object(ref mut o) = schema {
+            o.insert("enum".to_string(), Value::Array(values));
+        }
+
+        schema
+    }
+
+    /// Interfaces are one of their sub-types, discriminated by the `type` field.
+    fn interface_body(&self, body: &RpInterfaceBody) -> Result<Value> {
+        let mut sub_types = Vec::new();
+
+        for sub_type in body.sub_types.values() {
+            let mut names: Vec<Value> = sub_type
+                .names
+                .iter()
+                .map(|n| json!(n.value().as_str()))
+                .collect();
+
+            if names.is_empty() {
+                names.push(json!(sub_type.name()));
+            }
+
+            let fields = body.fields.iter().chain(sub_type.fields.iter());
+            let (mut properties, mut required) = self.properties(fields);
+
+            if properties.contains_key(TYPE) {
+                return Err(
+                    ErrorKind::Pos(
+                        format!("field `{}` conflicts with the sub-type field", TYPE),
+                        sub_type.pos().into(),
+                    ).into(),
+                );
+            }
+
+            properties.insert(TYPE.to_string(), json!({"enum": names}));
+            required.insert(0, json!(TYPE));
+
+            let mut schema = json!({
+                "type": "object",
+                "title": sub_type.name.parts.join("."),
+                "properties": properties,
+                "required": required,
+            });
+
+            if let Value::Object(ref mut o) = schema {
+                if !sub_type.comment.is_empty() {
+                    o.insert("description".to_string(), json!(sub_type.comment.join("\n")));
+                }
+            }
+
+            sub_types.push(schema);
+        }
+
+        Ok(json!({"oneOf": sub_types}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backend::Environment;
+    use core::{RpPackage, RpVersionedPackage};
+
+    fn backend(content: &str) -> JsonBackend {
+        let mut env = Environment::empty();
+        let package = RpVersionedPackage::new(RpPackage::parse("io.test"), None);
+        env.import_str(content, package).unwrap();
+
+        let listeners: Vec<Box<Listeners>> = vec![];
+        JsonBackend::new(env, JsonOptions::new(), Box::new(listeners))
+    }
+
+    /// Build the schema of the declaration with the given title.
+    fn schema(content: &str, title: &str) -> Result<Option<Value>> {
+        let backend = backend(content);
+        let schema = Schema::new(&backend);
+
+        for (_, file) in backend.env.for_each_file() {
+            for decl in file.for_each_decl() {
+                if decl.name().parts.join(".") == title {
+                    return schema.decl(decl);
+                }
+            }
+        }
+
+        panic!("no such declaration: {}", title);
+    }
+
+    #[test]
+    fn test_type() {
+        let content = r#"
+        /// A foo.
+        type Foo {
+            a: string;
+            b?: [Bar];
+            c: u32 as "cee";
+        }
+
+        type Bar {
+        }
+        "#;
+
+        let expected = json!({
+            "$schema": DRAFT_07,
+            "title": "Foo",
+            "description": " A foo.",
+            "type": "object",
+            "properties": {
+                "a": {"type": "string"},
+                "b": {"type": "array", "items": {"$ref": "../io_test/Bar.json"}},
+                "cee": {"type": "integer", "minimum": 0},
+            },
+            "required": ["a", "cee"],
+        });
+
+        assert_eq!(Some(expected), schema(content, "Foo").unwrap());
+    }
+
+    #[test]
+    fn test_path() {
+        let backend = backend("type Foo { type Bar {} }");
+        let schema = Schema::new(&backend);
+
+        let package = RpVersionedPackage::new(RpPackage::parse("io.test"), None);
+        let name = RpName::new(None, package, vec!["Foo".to_string(), "Bar".to_string()]);
+        assert_eq!("io_test/Foo.Bar.json", schema.path(&name));
+    }
+
+    #[test]
+    fn test_tuple() {
+        let expected = json!({
+            "$schema": DRAFT_07,
+            "title": "Pair",
+            "type": "array",
+            "items": [{"type": "string"}, {"type": "number"}],
+            "additionalItems": false,
+            "minItems": 2,
+            "maxItems": 2,
+        });
+
+        let content = "tuple Pair { a: string; b: double; }";
+        assert_eq!(Some(expected), schema(content, "Pair").unwrap());
+    }
+
+    #[test]
+    fn test_enum() {
+        let content = "enum Named as string { A as \"a\"; B as \"b\"; }\n\
+                       enum Numbered as u32 { One as 1; Two as 2; }";
+
+        let named = schema(content, "Named").unwrap().unwrap();
+        assert_eq!(json!("string"), named["type"]);
+        assert_eq!(json!(["a", "b"]), named["enum"]);
+
+        let numbered = schema(content, "Numbered").unwrap().unwrap();
+        assert_eq!(json!("integer"), numbered["type"]);
+        assert_eq!(json!([1, 2]), numbered["enum"]);
+    }
+
+    #[test]
+    fn test_interface() {
+        let content = r#"
+        interface Animal {
+            name: string;
+
+            Cat as "cat" {
+                lives?: u32;
+            }
+
+            Dog;
+        }
+        "#;
+
+        let expected = json!([
+            {
+                "type": "object",
+                "title": "Animal.Cat",
+                "properties": {
+                    "type": {"enum": ["cat"]},
+                    "name": {"type": "string"},
+                    "lives": {"type": "integer", "minimum": 0},
+                },
+                "required": ["type", "name"],
+            },
+            {
+                "type": "object",
+                "title": "Animal.Dog",
+                "properties": {
+                    "type": {"enum": ["Dog"]},
+                    "name": {"type": "string"},
+                },
+                "required": ["type", "name"],
+            },
+        ]);
+
+        let animal = schema(content, "Animal").unwrap().unwrap();
+        assert_eq!(expected, animal["oneOf"]);
+    }
+
+    #[test]
+    fn test_interface_type_conflict() {
+        let content = "interface Animal { type: string; Cat; }";
+        assert!(schema(content, "Animal").is_err());
+    }
+
+    #[test]
+    fn test_service() {
+        let content = "type Foo {} service Api { get() -> Foo; }";
+        assert_eq!(None, schema(content, "Api").unwrap());
+    }
+}
@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate genco;
+#[allow(unused)]
+#[macro_use]
+extern crate serde_derive;
+extern crate reproto_backend as backend;
+extern crate reproto_core as core;
+extern crate reproto_manifest as manifest;
+extern crate serde;
+extern crate toml;
+
+mod listeners;
+mod proto_backend;
+mod proto_compiler;
+mod proto_file_spec;
+mod proto_options;
+
+use self::backend::{ArgMatches, CompilerOptions, Environment, Options};
+use self::backend::errors::*;
+use self::listeners::Listeners;
+use self::proto_backend::ProtoBackend;
+use self::proto_options::ProtoOptions;
+use manifest::{Lang, Manifest, NoModule, TryFromToml, self as m};
+use std::path::Path;
+
+const TYPE_SEP: &str = "_";
+const EXT: &str = "proto";
+
+#[derive(Default)]
+pub struct ProtoLang;
+
+impl Lang for ProtoLang {
+    type Module = ProtoModule;
+}
+
+#[derive(Debug)]
+pub enum ProtoModule {
+}
+
+impl TryFromToml for ProtoModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> m::errors::Result<Self> {
+        NoModule::illegal(path, id, value)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> m::errors::Result<Self> {
+        NoModule::illegal(path, id, value)
+    }
+}
+
+fn setup_listeners(modules: &[ProtoModule]) -> Result<(ProtoOptions, Box<Listeners>)> {
+    let listeners: Vec<Box<Listeners>> = Vec::new();
+
+    for module in modules {
+        match *module {
+        }
+    }
+
+    let mut options = ProtoOptions::new();
+
+    for listener in &listeners {
+        listener.configure(&mut options)?;
+    }
+
+    Ok((options, Box::new(listeners)))
+}
+
+pub fn compile(
+    env: Environment,
+    _opts: Options,
+    compiler_options: CompilerOptions,
+    _matches: &ArgMatches,
+    manifest: Manifest<ProtoLang>,
+) -> Result<()> {
+    let (options, listeners) = setup_listeners(&manifest.modules)?;
+    let backend = ProtoBackend::new(env, options, listeners);
+    let compiler = backend.compiler(compiler_options)?;
+    compiler.compile()
+}
@@ -0,0 +1,672 @@
+//! Backend for Protocol Buffers
+
+use super::{EXT, TYPE_SEP};
+use backend::{CamelCase, CompilerOptions, Environment, FromNaming, Naming, PackageUtils,
+              SnakeCase};
+use backend::errors::*;
+use core::{Loc, Options, Pos, RpChannel, RpEnumBody, RpEnumOrdinal, RpField, RpInterfaceBody,
+           RpName, RpReg, RpServiceBody, RpTupleBody, RpType, RpTypeBody, Version};
+use genco::{Quoted, Tokens};
+use listeners::Listeners;
+use proto_compiler::ProtoCompiler;
+use proto_file_spec::ProtoFileSpec;
+use proto_options::ProtoOptions;
+use std::rc::Rc;
+
+const EMPTY: &str = "google.protobuf.Empty";
+const EMPTY_IMPORT: &str = "google/protobuf/empty.proto";
+/// Largest field number permitted by proto3.
+const MAX_FIELD_NUMBER: u32 = 536_870_911;
+/// Range of field numbers reserved for the Protocol Buffers implementation.
+const RESERVED_FIELD_NUMBERS: (u32, u32) = (19_000, 19_999);
+
+pub struct ProtoBackend {
+    pub env: Environment,
+    listeners: Box<Listeners>,
+    to_upper_camel: Box<Naming>,
+    to_upper_snake: Box<Naming>,
+    to_lower_snake: Box<Naming>,
+}
+
+impl ProtoBackend {
+    pub fn new(env: Environment, _: ProtoOptions, listeners: Box<Listeners>) -> ProtoBackend {
+        ProtoBackend {
+            env: env,
+            listeners: listeners,
+            to_upper_camel: SnakeCase::new().to_upper_camel(),
+            to_upper_snake: CamelCase::new().to_upper_snake(),
+            to_lower_snake: CamelCase::new().to_lower_snake(),
+        }
+    }
+
+    pub fn compiler(&self, options: CompilerOptions) -> Result<ProtoCompiler> {
+        Ok(ProtoCompiler {
+            out_path: options.out_path,
+            backend: self,
+        })
+    }
+
+    /// Build an error for a construct which proto3 can't express.
+    fn unsupported(&self, what: &str, pos: &Pos) -> Error {
+        ErrorKind::Pos(format!("{} cannot be represented in proto3", what), pos.into()).into()
+    }
+
+    /// Convert a name into a message or enum reference, importing its file if needed.
+    fn convert_name<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        name: &'el RpName,
+    ) -> Result<Tokens<'el, ()>> {
+        let registered = self.env.lookup(name)?;
+
+        let local_name = registered.local_name(name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP));
+
+        if let Some(_) = name.prefix {
+            let package = self.package(&name.package);
+            out.imports.insert(format!("{}.{}", package.parts.join("/"), EXT));
+            return Ok(toks![".", package.parts.join("."), ".", local_name]);
+        }
+
+        Ok(toks![local_name])
+    }
+
+    /// Convert a type which is permitted as the element of a field.
+    fn into_proto_type<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        ty: &'el RpType,
+        pos: &Pos,
+    ) -> Result<Tokens<'el, ()>> {
+        use self::RpType::*;
+
+        let ty = match *ty {
+            String | DateTime => toks!["string"],
+            Bytes => toks!["bytes"],
+            Signed { size: 32 } => toks!["int32"],
+            Signed { size: 64 } => toks!["int64"],
+            Unsigned { size: 32 } => toks!["uint32"],
+            Unsigned { size: 64 } => toks!["uint64"],
+            Float => toks!["float"],
+            Double => toks!["double"],
+            Boolean => toks!["bool"],
+            Name {
+                ref name,
+                ref arguments,
+            } if arguments.is_empty() => self.convert_name(out, name)?,
+            Any => return Err(self.unsupported("`any`", pos)),
+            Array { .. } | Map { .. } => {
+                return Err(self.unsupported("nested arrays or maps", pos));
+            }
+            ref ty => return Err(self.unsupported(&format!("`{}`", ty), pos)),
+        };
+
+        Ok(ty)
+    }
+
+    /// Convert the type of a field.
+    fn field_type<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        field: &'el Loc<RpField>,
+    ) -> Result<Tokens<'el, ()>> {
+        use self::RpType::*;
+
+        let pos = field.pos();
+
+        let ty = match field.ty {
+            Array { ref inner } => toks!["repeated ", self.into_proto_type(out, inner, pos)?],
+            Map { ref key, ref value } => {
+                match **key {
+                    String | Signed { .. } | Unsigned { .. } => {}
+                    ref key => {
+                        let what = format!("map with key `{}`", key);
+                        return Err(self.unsupported(&what, pos));
+                    }
+                }
+
+                let key = self.into_proto_type(out, key, pos)?;
+                let value = self.into_proto_type(out, value, pos)?;
+                toks!["map<", key, ", ", value, ">"]
+            }
+            ref ty => self.into_proto_type(out, ty, pos)?,
+        };
+
+        Ok(ty)
+    }
+
+    /// Build message fields, numbered by their `proto_field` option.
+    fn build_fields<'el, I>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        fields: I,
+    ) -> Result<Tokens<'el, ()>>
+    where
+        I: IntoIterator<Item = &'el Loc<RpField>>,
+    {
+        let fields: Vec<_> = fields.into_iter().collect();
+        let numbers = field_numbers(fields.iter().cloned())?;
+        self.build_numbered_fields(out, fields.into_iter().zip(numbers))
+    }
+
+    /// Build message fields, with the numbers that have been assigned to them.
+    fn build_numbered_fields<'el, I>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        fields: I,
+    ) -> Result<Tokens<'el, ()>>
+    where
+        I: IntoIterator<Item = (&'el Loc<RpField>, u32)>,
+    {
+        let mut t = Tokens::new();
+
+        for (field, number) in fields {
+            let ty = self.field_type(out, field)?;
+
+            let mut decl = toks![ty, " ", field.ident(), " = ", number.to_string()];
+
+            if field.name() != field.ident() {
+                decl.append(toks![" [json_name = ", field.name().quoted(), "]"]);
+            }
+
+            decl.append(";");
+            t.push(decl);
+        }
+
+        Ok(t)
+    }
+
+    fn build_message<'el>(&self, name: Rc<String>, body: Tokens<'el, ()>) -> Tokens<'el, ()> {
+        let mut t = Tokens::new();
+        t.push(toks!["message ", name, " {"]);
+        t.nested(body);
+        t.push("}");
+        t
+    }
+
+    /// Tuples are encoded by position, so their fields are numbered by position as well.
+    pub fn process_tuple<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        body: &'el RpTupleBody,
+    ) -> Result<()> {
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        for field in &body.fields {
+            if let Some(number) = proto_field(&field.options)? {
+                let message = "fields of a tuple are numbered by their position".to_string();
+                return Err(ErrorKind::Pos(message, number.pos().into()).into());
+            }
+        }
+
+        let fields = self.build_numbered_fields(out, body.fields.iter().zip(1..))?;
+        out.body.push(self.build_message(name, fields));
+        Ok(())
+    }
+
+    pub fn process_type<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        body: &'el RpTypeBody,
+    ) -> Result<()> {
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut message_body = Tokens::new();
+        message_body.push_unless_empty(self.build_fields(out, &body.fields)?);
+
+        if !body.reserved.is_empty() {
+            let mut reserved: Vec<_> = body.reserved.iter().map(|r| r.value().as_str()).collect();
+            reserved.sort();
+
+            let mut names = Tokens::new();
+
+            for r in reserved {
+                names.append(r.quoted());
+            }
+
+            message_body.push(toks!["reserved ", names.join(", "), ";"]);
+        }
+
+        out.body.push(self.build_message(name, message_body.join_line_spacing()));
+        Ok(())
+    }
+
+    /// Interfaces are represented as a message with a `oneof` field for every sub-type.
+    ///
+    /// Sub-types are numbered by their `proto_field` option.
+    pub fn process_interface<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        body: &'el RpInterfaceBody,
+    ) -> Result<()> {
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut sub_types: Vec<_> = body.sub_types.values().collect();
+        sub_types.sort_by_key(|s| s.pos().start);
+
+        let mut one_of = Tokens::new();
+        let mut messages = Tokens::new();
+
+        let mut numbers = Numbers::new();
+
+        for sub_type in sub_types {
+            let sub_type_name = Rc::new(sub_type.name.join(TYPE_SEP));
+            let field = self.to_lower_snake.convert(sub_type.local_name.as_str());
+
+            let what = format!("sub-type `{}`", sub_type.local_name);
+            let number = proto_field(&sub_type.options)?;
+            let number = numbers.insert(&what, number, sub_type.pos())?;
+
+            one_of.push(toks![
+                sub_type_name.clone(),
+                " ",
+                field,
+                " = ",
+                number.to_string(),
+                ";",
+            ]);
+
+            let fields = body.fields.iter().chain(sub_type.fields.iter());
+            let fields = self.build_fields(out, fields)?;
+            messages.push(self.build_message(sub_type_name, fields));
+        }
+
+        let mut message_body = Tokens::new();
+
+        if !one_of.is_empty() {
+            message_body.push("oneof value {");
+            message_body.nested(one_of);
+            message_body.push("}");
+        }
+
+        let mut elements = Tokens::new();
+        elements.push(self.build_message(name, message_body));
+        elements.push_unless_empty(messages.join_line_spacing());
+
+        out.body.push(elements.join_line_spacing());
+        Ok(())
+    }
+
+    /// Enums with a numeric type are numbered by their ordinals, the variants of other enums have
+    /// to be numbered with the `proto_field` option.
+    pub fn process_enum<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        body: &'el Loc<RpEnumBody>,
+    ) -> Result<()> {
+        if body.variants.is_empty() {
+            return Err(self.unsupported("enum without variants", body.pos()));
+        }
+
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        // enum values are scoped to the package, so they have to be prefixed.
+        let prefix = body.name
+            .parts
+            .iter()
+            .map(|p| self.to_upper_snake.convert(p))
+            .collect::<Vec<_>>()
+            .join("_");
+
+        let numbers = enum_numbers(body)?;
+
+        let mut values = Tokens::new();
+
+        for (variant, number) in body.variants.iter().zip(numbers) {
+            let value = self.to_upper_snake.convert(variant.local_name.as_str());
+
+            values.push(toks![
+                format!("{}_{}", prefix, value),
+                " = ",
+                number.to_string(),
+                ";",
+            ]);
+        }
+
+        let mut t = Tokens::new();
+        t.push(toks!["enum ", name, " {"]);
+        t.nested(values);
+        t.push("}");
+
+        out.body.push(t);
+        Ok(())
+    }
+
+    /// Convert the request or response of an endpoint.
+    fn channel<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        channel: &'el Option<Loc<RpChannel>>,
+    ) -> Result<Tokens<'el, ()>> {
+        let channel = match *channel {
+            Some(ref channel) => channel,
+            None => {
+                out.imports.insert(EMPTY_IMPORT.to_owned());
+                return Ok(toks![EMPTY]);
+            }
+        };
+
+        let name = match *channel.ty() {
+            RpType::Name {
+                ref name,
+                ref arguments,
+            } if arguments.is_empty() => name,
+            ref ty => {
+                let what = format!("endpoint with type `{}`", ty);
+                return Err(self.unsupported(&what, channel.pos()));
+            }
+        };
+
+        if let RpReg::Enum(_) = *self.env.lookup(name)? {
+            return Err(self.unsupported("endpoint with an enum type", channel.pos()));
+        }
+
+        let ty = self.convert_name(out, name)?;
+
+        if channel.is_streaming() {
+            return Ok(toks!["stream ", ty]);
+        }
+
+        Ok(ty)
+    }
+
+    pub fn process_service<'el>(
+        &self,
+        out: &mut ProtoFileSpec<'el>,
+        body: &'el RpServiceBody,
+    ) -> Result<()> {
+        let name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut rpcs = Tokens::new();
+
+        for endpoint in body.endpoints.values() {
+            let request = self.channel(out, &endpoint.request)?;
+            let response = self.channel(out, &endpoint.response)?;
+            let id = self.to_upper_camel.convert(endpoint.id.as_str());
+
+            rpcs.push(toks![
+                "rpc ",
+                id,
+                "(",
+                request,
+                ") returns (",
+                response,
+                ");",
+            ]);
+        }
+
+        let mut t = Tokens::new();
+        t.push(toks!["service ", name, " {"]);
+        t.nested(rpcs);
+        t.push("}");
+
+        out.body.push(t);
+        Ok(())
+    }
+}
+
+impl PackageUtils for ProtoBackend {
+    /// Proto packages can't have components starting with a digit or underscore.
+    fn version_package(input: &Version) -> String {
+        format!("v{}", input).replace(Self::package_version_unsafe, "_")
+    }
+}
+
+/// Parse the `proto_field` option, which has to be a positive number.
+fn proto_field<O>(options: &O) -> Result<Option<Loc<u32>>>
+where
+    O: Options,
+{
+    let number = match options.find_one_u32("proto_field")? {
+        Some(number) => number,
+        None => return Ok(None),
+    };
+
+    let (number, pos) = number.take_pair();
+
+    let value = if number.fits_unsigned(32) {
+        number.to_u32()
+    } else {
+        None
+    };
+
+    match value {
+        Some(value) if value > 0 => Ok(Some(Loc::new(value, pos))),
+        _ => {
+            let message = format!("`proto_field` must be a positive number, not {}", number);
+            Err(ErrorKind::Pos(message, (&pos).into()).into())
+        }
+    }
+}
+
+/// Field numbers in use by a single message.
+struct Numbers {
+    used: Vec<(u32, Pos)>,
+}
+
+impl Numbers {
+    fn new() -> Numbers {
+        Numbers { used: Vec::new() }
+    }
+
+    /// Validate and register the number of a field, described by `what`.
+    fn insert(&mut self, what: &str, number: Option<Loc<u32>>, pos: &Pos) -> Result<u32> {
+        let number = match number {
+            Some(number) => number,
+            None => {
+                let message = format!(
+                    "{} needs a field number, like `option proto_field = 1;`",
+                    what
+                );
+
+                return Err(ErrorKind::Pos(message, pos.into()).into());
+            }
+        };
+
+        let (value, pos) = number.take_pair();
+
+        if value > MAX_FIELD_NUMBER {
+            let message = format!("field number {} is larger than {}", value, MAX_FIELD_NUMBER);
+            return Err(ErrorKind::Pos(message, (&pos).into()).into());
+        }
+
+        if value >= RESERVED_FIELD_NUMBERS.0 && value <= RESERVED_FIELD_NUMBERS.1 {
+            let message = format!("field number {} is reserved by Protocol Buffers", value);
+            return Err(ErrorKind::Pos(message, (&pos).into()).into());
+        }
+
+        if let Some(&(_, ref other)) = self.used.iter().find(|u| u.0 == value) {
+            let message = format!("field number {} is already in use", value);
+            return Err(ErrorKind::FieldConflict(message, (&pos).into(), other.into()).into());
+        }
+
+        self.used.push((value, pos));
+        Ok(value)
+    }
+}
+
+/// Numbers of the given fields, which have to be explicit and unique.
+///
+/// Fields are never numbered by their position, so that removing or reordering fields doesn't
+/// change the numbers of the remaining ones.
+fn field_numbers<'a, I>(fields: I) -> Result<Vec<u32>>
+where
+    I: IntoIterator<Item = &'a Loc<RpField>>,
+{
+    let mut numbers = Numbers::new();
+    let mut out = Vec::new();
+
+    for field in fields {
+        let what = format!("field `{}`", field.ident());
+        let number = proto_field(&field.options)?;
+        out.push(numbers.insert(&what, number, field.pos())?);
+    }
+
+    Ok(out)
+}
+
+/// Numbers of the values of an enum.
+///
+/// Numeric enums are serialized as their ordinals, so those are used as the numbers. Unlike field
+/// numbers, enum values may be zero, and proto3 requires the first value to be zero since it is
+/// the default.
+fn enum_numbers(body: &Loc<RpEnumBody>) -> Result<Vec<i32>> {
+    let mut used: Vec<(i32, Pos)> = Vec::new();
+
+    for variant in &body.variants {
+        let (number, pos) = match variant.ordinal {
+            RpEnumOrdinal::Number(ref number) if body.variant_type.is_numeric() => {
+                (number.clone(), variant.pos().clone())
+            }
+            _ => match variant.options.find_one_u32("proto_field")? {
+                Some(number) => number.take_pair(),
+                None => {
+                    let message = format!(
+                        "variant `{}` needs a value, like `option proto_field = 0;`",
+                        variant.local_name.value()
+                    );
+
+                    return Err(ErrorKind::Pos(message, variant.pos().into()).into());
+                }
+            },
+        };
+
+        let value = if number.fits_signed(32) {
+            number.to_i64().map(|n| n as i32)
+        } else {
+            None
+        };
+
+        let value = match value {
+            Some(value) => value,
+            None => {
+                let message = format!("enum value {} does not fit in `int32`", number);
+                return Err(ErrorKind::Pos(message, (&pos).into()).into());
+            }
+        };
+
+        if let Some(&(_, ref other)) = used.iter().find(|u| u.0 == value) {
+            let message = format!("enum value {} is already in use", value);
+            return Err(ErrorKind::FieldConflict(message, (&pos).into(), other.into()).into());
+        }
+
+        used.push((value, pos));
+    }
+
+    if let Some(&(first, ref pos)) = used.first() {
+        if first != 0 {
+            let message = format!(
+                "the first value of an enum has to be 0 in proto3, not {}",
+                first
+            );
+
+            return Err(ErrorKind::Pos(message, pos.into()).into());
+        }
+    }
+
+    Ok(used.into_iter().map(|u| u.0).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{BytesObject, Object, RpConstraints, RpDecl, RpModifier, RpNumber, RpOptionDecl,
+               RpPackage, RpValue, RpVersionedPackage};
+    use std::sync::Arc;
+
+    fn pos() -> Pos {
+        let object: Box<Object> = Box::new(BytesObject::new("test".to_string(), Arc::new(vec![])));
+        (Rc::new(object), 0, 0).into()
+    }
+
+    fn field(name: &str, number: Option<u32>) -> Loc<RpField> {
+        let options = number.into_iter().map(|n| {
+            let option = RpOptionDecl {
+                name: "proto_field".to_string(),
+                value: Loc::new(RpValue::Number(RpNumber::from(n)), pos()),
+            };
+
+            Loc::new(option, pos())
+        });
+
+        let field = RpField {
+            modifier: RpModifier::Required,
+            name: name.to_string(),
+            comment: vec![],
+            ty: RpType::String,
+            field_as: None,
+            constraints: RpConstraints::default(),
+            default: None,
+            options: options.collect(),
+        };
+
+        Loc::new(field, pos())
+    }
+
+    /// Numbers of the values of the first enum in the given spec.
+    fn enum_values(spec: &str) -> Result<Vec<i32>> {
+        let mut env = Environment::empty();
+        let package = RpVersionedPackage::new(RpPackage::parse("test"), None);
+        let file = env.load_str(spec, &package).unwrap();
+
+        match **file.decls[0] {
+            RpDecl::Enum(ref body) => enum_numbers(body),
+            _ => panic!("expected enum"),
+        }
+    }
+
+    #[test]
+    fn test_numbers_survive_removal() {
+        let fields = [field("a", Some(1)), field("b", Some(2)), field("c", Some(3))];
+        assert_eq!(vec![1, 2, 3], field_numbers(&fields).unwrap());
+
+        let fields = [field("a", Some(1)), field("c", Some(3))];
+        assert_eq!(vec![1, 3], field_numbers(&fields).unwrap());
+    }
+
+    #[test]
+    fn test_numbers_survive_reordering() {
+        let fields = [field("c", Some(3)), field("a", Some(1))];
+        assert_eq!(vec![3, 1], field_numbers(&fields).unwrap());
+    }
+
+    #[test]
+    fn test_missing_number() {
+        let fields = [field("a", Some(1)), field("b", None)];
+        assert!(field_numbers(&fields).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_number() {
+        let fields = [field("a", Some(1)), field("b", Some(1))];
+        assert!(field_numbers(&fields).is_err());
+    }
+
+    #[test]
+    fn test_reserved_number() {
+        assert!(field_numbers(&[field("a", Some(19_000))]).is_err());
+        assert!(field_numbers(&[field("a", Some(MAX_FIELD_NUMBER + 1))]).is_err());
+        assert!(field_numbers(&[field("a", Some(MAX_FIELD_NUMBER))]).is_ok());
+    }
+
+    #[test]
+    fn test_numeric_enum() {
+        assert_eq!(vec![0, 2], enum_values("enum Foo as u32 { A as 0; B as 2; }").unwrap());
+        assert!(enum_values("enum Foo as u32 { A as 0; B as 0; }").is_err());
+    }
+
+    #[test]
+    fn test_enum_first_value() {
+        assert!(enum_values("enum Foo as u32 { A as 1; B as 0; }").is_err());
+    }
+
+    #[test]
+    fn test_string_enum() {
+        let spec = "enum Foo as string {
+            A { option proto_field = 0; }
+            B as \"b\" { option proto_field = 1; }
+        }";
+
+        assert_eq!(vec![0, 1], enum_values(spec).unwrap());
+        assert!(enum_values("enum Foo as string { A { option proto_field = 0; } B; }").is_err());
+    }
+}
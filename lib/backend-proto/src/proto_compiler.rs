@@ -0,0 +1,76 @@
+//! Compiler for Protocol Buffers Backend
+
+use super::EXT;
+use backend::{Environment, PackageProcessor, PackageUtils};
+use backend::errors::*;
+use core::{Loc, RpEnumBody, RpInterfaceBody, RpPackage, RpServiceBody, RpTupleBody, RpTypeBody,
+           RpVersionedPackage};
+use proto_backend::ProtoBackend;
+use proto_file_spec::ProtoFileSpec;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub struct ProtoCompiler<'el> {
+    pub out_path: PathBuf,
+    pub backend: &'el ProtoBackend,
+}
+
+impl<'el> ProtoCompiler<'el> {
+    pub fn compile(&self) -> Result<()> {
+        let mut files = self.populate_files()?;
+
+        for (package, out) in &mut files {
+            let package = self.processed_package(package);
+
+            if !package.parts.is_empty() {
+                out.package = Some(Rc::new(package.parts.join(".")));
+            }
+        }
+
+        self.write_files(files)
+    }
+}
+
+impl<'el> PackageProcessor<'el> for ProtoCompiler<'el> {
+    type Out = ProtoFileSpec<'el>;
+
+    fn ext(&self) -> &str {
+        EXT
+    }
+
+    fn env(&self) -> &'el Environment {
+        &self.backend.env
+    }
+
+    fn out_path(&self) -> &Path {
+        &self.out_path
+    }
+
+    fn processed_package(&self, package: &RpVersionedPackage) -> RpPackage {
+        self.backend.package(package)
+    }
+
+    fn process_tuple(&self, out: &mut Self::Out, body: &'el Loc<RpTupleBody>) -> Result<()> {
+        self.backend.process_tuple(out, body)
+    }
+
+    fn process_enum(&self, out: &mut Self::Out, body: &'el Loc<RpEnumBody>) -> Result<()> {
+        self.backend.process_enum(out, body)
+    }
+
+    fn process_type(&self, out: &mut Self::Out, body: &'el Loc<RpTypeBody>) -> Result<()> {
+        self.backend.process_type(out, body)
+    }
+
+    fn process_interface(
+        &self,
+        out: &mut Self::Out,
+        body: &'el Loc<RpInterfaceBody>,
+    ) -> Result<()> {
+        self.backend.process_interface(out, body)
+    }
+
+    fn process_service(&self, out: &mut Self::Out, body: &'el Loc<RpServiceBody>) -> Result<()> {
+        self.backend.process_service(out, body)
+    }
+}
@@ -0,0 +1,53 @@
+//! File spec collecting results from backends
+
+use backend::IntoBytes;
+use backend::errors::*;
+use genco::{Quoted, Tokens};
+use proto_compiler::ProtoCompiler;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+pub struct ProtoFileSpec<'el> {
+    /// Name of the package, used in the package statement.
+    pub package: Option<Rc<String>>,
+    /// Imported files.
+    pub imports: BTreeSet<String>,
+    pub body: Tokens<'el, ()>,
+}
+
+impl<'el> Default for ProtoFileSpec<'el> {
+    fn default() -> Self {
+        ProtoFileSpec {
+            package: None,
+            imports: BTreeSet::new(),
+            body: Tokens::new(),
+        }
+    }
+}
+
+impl<'el> IntoBytes<ProtoCompiler<'el>> for ProtoFileSpec<'el> {
+    fn into_bytes(self, _: &ProtoCompiler<'el>) -> Result<Vec<u8>> {
+        let mut file: Tokens<()> = Tokens::new();
+
+        file.push(toks!["syntax = ", "proto3".quoted(), ";"]);
+
+        if let Some(package) = self.package {
+            file.push(toks!["package ", package, ";"]);
+        }
+
+        if !self.imports.is_empty() {
+            let mut imports = Tokens::new();
+
+            for import in self.imports {
+                imports.push(toks!["import ", import.quoted(), ";"]);
+            }
+
+            file.push(imports);
+        }
+
+        file.push_unless_empty(self.body.join_line_spacing());
+
+        let out = file.join_line_spacing().to_file()?;
+        Ok(out.into_bytes())
+    }
+}
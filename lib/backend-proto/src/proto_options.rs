@@ -0,0 +1,8 @@
+pub struct ProtoOptions {
+}
+
+impl ProtoOptions {
+    pub fn new() -> ProtoOptions {
+        ProtoOptions {}
+    }
+}
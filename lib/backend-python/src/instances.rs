@@ -0,0 +1,431 @@
+//! Python has no way of expressing generic declarations, so every concrete use of a generic type
+//! or tuple is generated as a separate (monomorphized) class.
+
+use super::TYPE_SEP;
+use backend::Environment;
+use backend::errors::*;
+use core::{Loc, Pos, RpDecl, RpField, RpName, RpReg, RpType, RpVersionedPackage};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A concrete instance of a generic declaration.
+#[derive(Debug)]
+pub struct Instance {
+    /// Name of the generic declaration.
+    pub name: RpName,
+    /// Name of the class generated for the instance.
+    pub local_name: String,
+    /// Fields of the declaration, with all type parameters substituted.
+    pub fields: Vec<Loc<RpField>>,
+}
+
+/// Build the class name of a generic declaration instantiated with the given arguments.
+pub fn instance_name(name: &RpName, arguments: &[RpType]) -> String {
+    let mut parts = vec![name.join(TYPE_SEP)];
+    parts.extend(arguments.iter().map(mangle));
+    parts.join(TYPE_SEP)
+}
+
+/// Mangle a type argument into something that can be part of a class name.
+///
+/// Names include their package, since declarations with the same name from different packages
+/// would otherwise be mangled into the same class name.
+fn mangle(ty: &RpType) -> String {
+    use self::RpType::*;
+
+    match *ty {
+        Name {
+            ref name,
+            ref arguments,
+        } => {
+            let package = mangle_package(&name.package);
+            let name = instance_name(name, arguments);
+
+            if package.is_empty() {
+                return name;
+            }
+
+            format!("{}{}{}", package, TYPE_SEP, name)
+        }
+        Array { ref inner } => format!("array{}{}", TYPE_SEP, mangle(inner)),
+        Map { ref key, ref value } => {
+            format!("map{}{}{}{}", TYPE_SEP, mangle(key), TYPE_SEP, mangle(value))
+        }
+        ref ty => ty.to_string(),
+    }
+}
+
+/// Mangle a package, including its version, into something that can be part of a class name.
+fn mangle_package(package: &RpVersionedPackage) -> String {
+    let parts = package.as_package(ToString::to_string).parts;
+
+    parts
+        .iter()
+        .map(|part| {
+            part.chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(TYPE_SEP)
+}
+
+/// Rewrite all names in the given type so that they can be referenced from `package`.
+///
+/// Names from other packages are given an alias to import them through.
+fn relative_to(ty: &RpType, package: &RpVersionedPackage) -> RpType {
+    use self::RpType::*;
+
+    match *ty {
+        Name {
+            ref name,
+            ref arguments,
+        } => {
+            let mut name = name.clone();
+
+            name.prefix = if name.package == *package {
+                None
+            } else {
+                Some(name.package.package.parts.join(TYPE_SEP))
+            };
+
+            Name {
+                name: name,
+                arguments: arguments.iter().map(|a| relative_to(a, package)).collect(),
+            }
+        }
+        Array { ref inner } => Array { inner: Box::new(relative_to(inner, package)) },
+        Map { ref key, ref value } => Map {
+            key: Box::new(relative_to(key, package)),
+            value: Box::new(relative_to(value, package)),
+        },
+        ref ty => ty.clone(),
+    }
+}
+
+/// Find all concrete uses of generic declarations in the given type.
+fn find_uses(ty: &RpType, queue: &mut VecDeque<(RpName, Vec<RpType>)>) {
+    use self::RpType::*;
+
+    match *ty {
+        Name {
+            ref name,
+            ref arguments,
+        } => {
+            if !arguments.is_empty() {
+                queue.push_back((name.clone(), arguments.clone()));
+            }
+
+            for argument in arguments {
+                find_uses(argument, queue);
+            }
+        }
+        Array { ref inner } => find_uses(inner, queue),
+        Map { ref key, ref value } => {
+            find_uses(key, queue);
+            find_uses(value, queue);
+        }
+        _ => {}
+    }
+}
+
+fn find_field_uses<'a, I>(fields: I, queue: &mut VecDeque<(RpName, Vec<RpType>)>)
+where
+    I: IntoIterator<Item = &'a Loc<RpField>>,
+{
+    for field in fields {
+        find_uses(&field.ty, queue);
+    }
+}
+
+/// A type parameter of a generic declaration.
+type Parameter = (RpName, usize);
+
+/// A type parameter being used in the argument of another generic declaration.
+struct Edge {
+    from: Parameter,
+    to: Parameter,
+    /// If the argument is bigger than the type parameter itself, like `[T]`.
+    expansive: bool,
+    pos: Pos,
+}
+
+/// Check if the given type parameter is used anywhere in the type.
+fn uses_parameter(ty: &RpType, parameter: &str) -> bool {
+    use self::RpType::*;
+
+    match *ty {
+        Generic { ref name } => name == parameter,
+        Name { ref arguments, .. } => arguments.iter().any(|a| uses_parameter(a, parameter)),
+        Array { ref inner } => uses_parameter(inner, parameter),
+        Map { ref key, ref value } => {
+            uses_parameter(key, parameter) || uses_parameter(value, parameter)
+        }
+        _ => false,
+    }
+}
+
+/// Find all edges from the type parameters of `name` through the given type.
+fn find_edges(
+    name: &RpName,
+    type_parameters: &[String],
+    ty: &RpType,
+    pos: &Pos,
+    edges: &mut Vec<Edge>,
+) {
+    use self::RpType::*;
+
+    match *ty {
+        Name {
+            name: ref target,
+            ref arguments,
+        } => {
+            for (j, argument) in arguments.iter().enumerate() {
+                for (i, parameter) in type_parameters.iter().enumerate() {
+                    if !uses_parameter(argument, parameter) {
+                        continue;
+                    }
+
+                    let expansive = match *argument {
+                        Generic { ref name } => name != parameter,
+                        _ => true,
+                    };
+
+                    edges.push(Edge {
+                        from: (name.clone(), i),
+                        to: (target.clone().without_prefix(), j),
+                        expansive: expansive,
+                        pos: pos.clone(),
+                    });
+                }
+
+                find_edges(name, type_parameters, argument, pos, edges);
+            }
+        }
+        Array { ref inner } => find_edges(name, type_parameters, inner, pos, edges),
+        Map { ref key, ref value } => {
+            find_edges(name, type_parameters, key, pos, edges);
+            find_edges(name, type_parameters, value, pos, edges);
+        }
+        _ => {}
+    }
+}
+
+/// Check if `to` can be reached from `from` by following the given edges.
+fn reachable(edges: &[Edge], from: &Parameter, to: &Parameter) -> bool {
+    let mut visited = HashSet::new();
+    let mut queue = vec![from];
+
+    while let Some(current) = queue.pop() {
+        if current == to {
+            return true;
+        }
+
+        if !visited.insert(current) {
+            continue;
+        }
+
+        queue.extend(edges.iter().filter(|e| e.from == *current).map(|e| &e.to));
+    }
+
+    false
+}
+
+/// Check that no generic declaration instantiates itself with an ever growing type argument.
+///
+/// Like `type T<X> { c: [T<[X]>]; }`, where `T<string>` would require `T<[string]>`, which in
+/// turn requires `T<[[string]]>`, and so forth. These can't be represented through a finite number
+/// of instances.
+fn check_expansive(env: &Environment) -> Result<()> {
+    let mut edges = Vec::new();
+
+    for decl in env.decl_iter() {
+        let (name, type_parameters, fields) = match ***decl {
+            RpDecl::Type(ref body) => (&body.name, &body.type_parameters, &body.fields),
+            RpDecl::Tuple(ref body) => (&body.name, &body.type_parameters, &body.fields),
+            _ => continue,
+        };
+
+        for field in fields {
+            find_edges(name, type_parameters, &field.ty, field.pos(), &mut edges);
+        }
+    }
+
+    for edge in edges.iter().filter(|e| e.expansive) {
+        if reachable(&edges, &edge.to, &edge.from) {
+            return Err(
+                ErrorKind::Pos(
+                    format!(
+                        "{}: instantiates itself with a growing type argument",
+                        edge.from.0
+                    ),
+                    edge.pos.clone().into(),
+                ).into(),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Collect all instances of generic declarations which are used by non-generic declarations.
+pub fn collect(env: &Environment) -> Result<Vec<Instance>> {
+    check_expansive(env)?;
+
+    let mut queue = VecDeque::new();
+
+    for decl in env.decl_iter() {
+        match ***decl {
+            RpDecl::Type(ref body) if body.type_parameters.is_empty() => {
+                find_field_uses(&body.fields, &mut queue);
+            }
+            RpDecl::Tuple(ref body) if body.type_parameters.is_empty() => {
+                find_field_uses(&body.fields, &mut queue);
+            }
+            RpDecl::Interface(ref body) if body.type_parameters.is_empty() => {
+                find_field_uses(&body.fields, &mut queue);
+
+                for sub_type in body.sub_types.values() {
+                    find_field_uses(&sub_type.fields, &mut queue);
+                }
+            }
+            RpDecl::Service(ref body) => {
+                for endpoint in body.endpoints.values() {
+                    let channels = endpoint.request.iter().chain(endpoint.response.iter());
+
+                    for channel in channels {
+                        find_uses(channel.ty(), &mut queue);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut instances: Vec<Instance> = Vec::new();
+
+    while let Some((name, arguments)) = queue.pop_front() {
+        let local_name = instance_name(&name, &arguments);
+
+        let seen = instances.iter().any(|i| {
+            i.name.package == name.package && i.local_name == local_name
+        });
+
+        if seen {
+            continue;
+        }
+
+        let (package, type_parameters, fields) = match *env.lookup(&name)? {
+            RpReg::Type(ref body) => (&body.name.package, &body.type_parameters, &body.fields),
+            RpReg::Tuple(ref body) => (&body.name.package, &body.type_parameters, &body.fields),
+            _ => return Err(format!("{}: generic interfaces are not supported", name).into()),
+        };
+
+        let substitutions: HashMap<String, RpType> = type_parameters
+            .iter()
+            .cloned()
+            .zip(arguments.iter().map(|a| relative_to(a, package)))
+            .collect();
+
+        let fields: Vec<Loc<RpField>> = fields
+            .iter()
+            .map(|f| {
+                f.as_ref().map(|f| {
+                    let mut field = f.clone();
+                    field.ty = field.ty.substitute(&substitutions);
+                    field
+                })
+            })
+            .collect();
+
+        find_field_uses(&fields, &mut queue);
+
+        instances.push(Instance {
+            name: name.clone().without_prefix(),
+            local_name: local_name,
+            fields: fields,
+        });
+    }
+
+    Ok(instances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{RpPackage, Version};
+
+    fn instances(content: &str) -> Result<Vec<String>> {
+        let mut env = Environment::empty();
+        let package = RpVersionedPackage::new(RpPackage::parse("test"), None);
+        env.import_str(content, package)?;
+        Ok(collect(&env)?.into_iter().map(|i| i.local_name).collect())
+    }
+
+    fn name(package: &[&str], version: Option<&str>, parts: &[&str]) -> RpName {
+        let package = RpPackage::new(package.iter().map(ToString::to_string).collect());
+        let version = version.map(|v| Version::parse(v).unwrap());
+
+        RpName::new(
+            None,
+            RpVersionedPackage::new(package, version),
+            parts.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    fn ty(name: RpName) -> RpType {
+        RpType::Name {
+            name: name,
+            arguments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_same_name_from_different_packages() {
+        let page = name(&["c"], None, &["Page"]);
+
+        let a = instance_name(&page, &[ty(name(&["a"], None, &["Foo"]))]);
+        let b = instance_name(&page, &[ty(name(&["b"], None, &["Foo"]))]);
+
+        assert_eq!("Page_a_Foo", a);
+        assert_eq!("Page_b_Foo", b);
+    }
+
+    #[test]
+    fn test_same_name_from_different_versions() {
+        let page = name(&["c"], None, &["Page"]);
+
+        let a = instance_name(&page, &[ty(name(&["a"], Some("1.0.0"), &["Foo"]))]);
+        let b = instance_name(&page, &[ty(name(&["a"], Some("2.0.0"), &["Foo"]))]);
+
+        assert_eq!("Page_a_1_0_0_Foo", a);
+        assert_eq!("Page_a_2_0_0_Foo", b);
+    }
+
+    #[test]
+    fn test_nested_arguments() {
+        let page = name(&["c"], None, &["Page"]);
+        let inner = RpType::Name {
+            name: name(&["a"], None, &["Page"]),
+            arguments: vec![RpType::Array { inner: Box::new(RpType::String) }],
+        };
+
+        assert_eq!("Page_a_Page_array_string", instance_name(&page, &[inner]));
+    }
+
+    #[test]
+    fn test_recursive_instances() {
+        let content = "type T<X> { c: [T<X>]; } type Foo { t: T<string>; }";
+        assert_eq!(vec!["T_string".to_string()], instances(content).unwrap());
+    }
+
+    #[test]
+    fn test_growing_instances() {
+        let content = "type T<X> { c: [T<[X]>]; } type Foo { t: T<string>; }";
+        let e = instances(content).err().expect("expected error");
+        assert!(e.to_string().contains("growing type argument"), "{}", e);
+
+        // growing through another declaration.
+        let content = "type T<X> { u: U<[X]>; } type U<Y> { t: T<Y>; } type Foo { t: T<u32>; }";
+        assert!(instances(content).is_err());
+    }
+}
@@ -8,6 +8,7 @@ extern crate reproto_manifest as manifest;
 extern crate serde;
 extern crate toml;
 
+mod instances;
 mod listeners;
 mod python_backend;
 mod python_compiler;
@@ -76,7 +77,8 @@ pub fn compile(
 ) -> Result<()> {
     let id_converter = opts.id_converter;
     let (options, listeners) = setup_listeners(&manifest.modules)?;
-    let backend = PythonBackend::new(env, options, listeners, id_converter);
+    let instances = instances::collect(&env)?;
+    let backend = PythonBackend::new(env, instances, options, listeners, id_converter);
     let compiler = backend.compiler(compiler_options)?;
     compiler.compile()
 }
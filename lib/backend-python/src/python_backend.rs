@@ -4,10 +4,11 @@ use super::{PYTHON_CONTEXT, TYPE, TYPE_SEP};
 use backend::{Code, CompilerOptions, Converter, DynamicConverter, DynamicDecode, DynamicEncode,
               Environment, FromNaming, Naming, PackageUtils, SnakeCase};
 use backend::errors::*;
-use core::{ForEachLoc, Loc, RpEnumBody, RpField, RpInterfaceBody, RpModifier, RpName,
-           RpServiceBody, RpTupleBody, RpType, RpTypeBody, WithPos};
+use core::{ForEachLoc, Loc, RpCode, RpEnumBody, RpField, RpInterfaceBody, RpModifier, RpName,
+           RpServiceBody, RpTupleBody, RpType, RpTypeBody, RpValue, WithPos};
 use genco::{Element, Quoted, Tokens};
 use genco::python::{Python, imported_alias, imported_ref};
+use instances::{Instance, instance_name};
 use listeners::Listeners;
 use python_compiler::PythonCompiler;
 use python_field::PythonField;
@@ -19,28 +20,33 @@ use std::rc::Rc;
 
 pub struct PythonBackend {
     pub env: Environment,
+    instances: Vec<Instance>,
     listeners: Box<Listeners>,
     id_converter: Option<Box<Naming>>,
     to_lower_snake: Box<Naming>,
     dict: Element<'static, Python<'static>>,
     enum_enum: Python<'static>,
+    re_search: Python<'static>,
     type_var: Tokens<'static, Python<'static>>,
 }
 
 impl PythonBackend {
     pub fn new(
         env: Environment,
+        instances: Vec<Instance>,
         _: PythonOptions,
         listeners: Box<Listeners>,
         id_converter: Option<Box<Naming>>,
     ) -> PythonBackend {
         PythonBackend {
             env: env,
+            instances: instances,
             listeners: listeners,
             id_converter: id_converter,
             to_lower_snake: SnakeCase::new().to_lower_snake(),
             dict: "dict".into(),
             enum_enum: imported_ref("enum", "Enum"),
+            re_search: imported_ref("re", "search"),
             type_var: TYPE.quoted().into(),
         }
     }
@@ -197,11 +203,25 @@ impl PythonBackend {
         repr
     }
 
+    /// Build the literal for the default value of a field.
+    fn default_value<'el>(&self, value: &RpValue) -> Result<Tokens<'el, Python<'el>>> {
+        let out = match *value {
+            RpValue::Boolean(true) => toks!["True"],
+            RpValue::Boolean(false) => toks!["False"],
+            RpValue::String(ref string) => toks![string.as_str().quoted()],
+            RpValue::Number(ref number) => toks![number.to_string()],
+            ref value => return Err(format!("unsupported default value: {}", value).into()),
+        };
+
+        Ok(out)
+    }
+
     fn optional_check<'el>(
         &self,
         var: Tokens<'el, Python<'el>>,
         index: Tokens<'el, Python<'el>>,
         toks: Tokens<'el, Python<'el>>,
+        default: Tokens<'el, Python<'el>>,
     ) -> Tokens<'el, Python<'el>> {
         let mut check = Tokens::new();
 
@@ -221,14 +241,83 @@ impl PythonBackend {
         check.nested(none_check.join_line_spacing());
 
         check.push(toks!["else:"]);
-        check.nested(toks![var.clone(), " = None"]);
+        check.nested(toks![var.clone(), " = ", default]);
 
         check.into()
     }
 
+    /// Build checks that the decoded value `var` satisfies the constraints of the field.
+    fn constraint_checks<'el>(
+        &self,
+        field: &PythonField<'el>,
+        var: Tokens<'el, Python<'el>>,
+    ) -> Tokens<'el, Python<'el>> {
+        let constraints = field.constraints;
+        let mut checks = Vec::new();
+
+        if let Some(ref min) = constraints.min {
+            checks.push((
+                toks![var.clone(), " < ", min.to_string()],
+                format!("must be greater than or equal to {}", min),
+            ));
+        }
+
+        if let Some(ref max) = constraints.max {
+            checks.push((
+                toks![var.clone(), " > ", max.to_string()],
+                format!("must be less than or equal to {}", max),
+            ));
+        }
+
+        if let Some(min_length) = constraints.min_length {
+            checks.push((
+                toks!["len(", var.clone(), ") < ", min_length.to_string()],
+                format!("length must be at least {}", min_length),
+            ));
+        }
+
+        if let Some(max_length) = constraints.max_length {
+            checks.push((
+                toks!["len(", var.clone(), ") > ", max_length.to_string()],
+                format!("length must be at most {}", max_length),
+            ));
+        }
+
+        if let Some(ref pattern) = constraints.pattern {
+            checks.push((
+                toks![
+                    "not ",
+                    self.re_search.clone(),
+                    "(",
+                    pattern.as_str().quoted(),
+                    ", ",
+                    var.clone(),
+                    ")",
+                ],
+                format!("must match pattern `{}`", pattern),
+            ));
+        }
+
+        let mut t = Tokens::new();
+
+        for (condition, message) in checks {
+            let condition = match *field.modifier {
+                RpModifier::Optional => toks![var.clone(), " is not None and ", condition],
+                _ => condition,
+            };
+
+            let message = format!("{}: {}", field.name, message).quoted();
+
+            t.push(toks!["if ", condition, ":"]);
+            t.nested(toks!["raise Exception(", message, ")"]);
+        }
+
+        t
+    }
+
     fn decode_method<'el, F>(
         &self,
-        name: &'el RpName,
+        name: Tokens<'el, Python<'el>>,
         fields: &[Loc<PythonField<'el>>],
         variable_fn: F,
     ) -> Result<Tokens<'el, Python<'el>>>
@@ -248,7 +337,12 @@ impl PythonBackend {
                     let var_toks = self.dynamic_decode(field.ty, var_name.clone()).with_pos(
                         field.pos(),
                     )?;
-                    self.optional_check(var_name.clone(), var, var_toks)
+                    let default = match field.default {
+                        Some(default) => self.default_value(default).with_pos(default.pos())?,
+                        None => toks!["None"],
+                    };
+
+                    self.optional_check(var_name.clone(), var, var_toks, default)
                 }
                 _ => {
                     let data = toks!["data[", var.clone(), "]"];
@@ -258,11 +352,11 @@ impl PythonBackend {
             };
 
             body.push(toks);
+            body.push_unless_empty(self.constraint_checks(field, toks!(var_name.clone())));
             args.append(toks!(var_name));
         }
 
         let args = args.join(", ");
-        let name = self.convert_type(name)?;
         body.push(toks!["return ", name, "(", args, ")"]);
 
         let mut decode = Tokens::new();
@@ -360,6 +454,36 @@ impl PythonBackend {
         Ok(local_name.into())
     }
 
+    /// Convert a generic declaration with the given arguments into the class of its instance.
+    fn convert_instance<'el>(
+        &self,
+        name: &'el RpName,
+        arguments: &[RpType],
+    ) -> Result<Tokens<'el, Python<'el>>> {
+        let local_name = instance_name(name, arguments);
+
+        if let Some(ref used) = name.prefix {
+            let package = self.package(&name.package).parts.join(".");
+            return Ok(
+                imported_alias(
+                    Cow::Owned(package),
+                    Cow::Owned(local_name),
+                    Cow::Borrowed(used),
+                ).into(),
+            );
+        }
+
+        Ok(local_name.into())
+    }
+
+    /// Iterate over all instances of the given generic declaration.
+    fn instances_of(&self, name: &RpName) -> Vec<&Instance> {
+        self.instances
+            .iter()
+            .filter(|i| i.name.package == name.package && i.name.parts == name.parts)
+            .collect()
+    }
+
     pub fn enum_variants<'el>(&self, body: &'el RpEnumBody) -> Result<Tokens<'el, Python<'el>>> {
         let mut args = Tokens::new();
 
@@ -418,6 +542,8 @@ impl PythonBackend {
             ty: &field.ty,
             name: field.name(),
             ident: Rc::new(ident),
+            constraints: &field.constraints,
+            default: field.default.as_ref(),
         })
     }
 
@@ -443,14 +569,39 @@ impl PythonBackend {
     }
 
     pub fn process_tuple<'el>(
-        &self,
+        &'el self,
         out: &mut PythonFileSpec<'el>,
         body: &'el RpTupleBody,
     ) -> Result<()> {
-        let mut tuple_body = Tokens::new();
+        if !body.type_parameters.is_empty() {
+            for instance in self.instances_of(&body.name) {
+                let type_name = Rc::new(instance.local_name.clone());
+                let decode_name = toks!(type_name.clone());
+                let fields = &instance.fields;
+                let class = self.tuple_class(type_name, decode_name, fields, &body.codes)?;
+                out.0.push(class);
+            }
+
+            return Ok(());
+        }
+
         let type_name = Rc::new(body.name.join(TYPE_SEP));
+        let decode_name = self.convert_type(&body.name)?;
+        out.0.push(self.tuple_class(type_name, decode_name, &body.fields, &body.codes)?);
+        Ok(())
+    }
 
-        let fields: Vec<Loc<PythonField>> = body.fields
+    /// Build the class for a tuple.
+    fn tuple_class<'el>(
+        &self,
+        type_name: Rc<String>,
+        decode_name: Tokens<'el, Python<'el>>,
+        fields: &'el [Loc<RpField>],
+        codes: &'el Vec<Loc<RpCode>>,
+    ) -> Result<Tokens<'el, Python<'el>>> {
+        let mut tuple_body = Tokens::new();
+
+        let fields: Vec<Loc<PythonField>> = fields
             .iter()
             .map(|f| f.as_ref().map(|f| self.into_python_field(f)))
             .collect();
@@ -464,13 +615,9 @@ impl PythonBackend {
             }
         }
 
-        tuple_body.push_unless_empty(Code(&body.codes, PYTHON_CONTEXT));
+        tuple_body.push_unless_empty(Code(codes, PYTHON_CONTEXT));
 
-        let decode = self.decode_method(
-            &body.name,
-            &fields,
-            |i, _| i.to_string().into(),
-        )?;
+        let decode = self.decode_method(decode_name, &fields, |i, _| i.to_string().into())?;
         tuple_body.push(decode);
 
         let encode = self.encode_tuple_method(&fields)?;
@@ -479,10 +626,7 @@ impl PythonBackend {
         let repr_method = self.repr_method(type_name.clone(), &fields);
         tuple_body.push(repr_method);
 
-        let class = self.as_class(type_name, tuple_body);
-
-        out.0.push(class);
-        Ok(())
+        Ok(self.as_class(type_name, tuple_body))
     }
 
     /// Process an enum for Python.
@@ -523,14 +667,39 @@ impl PythonBackend {
     }
 
     pub fn process_type<'el>(
-        &self,
+        &'el self,
         out: &mut PythonFileSpec<'el>,
         body: &'el RpTypeBody,
     ) -> Result<()> {
+        if !body.type_parameters.is_empty() {
+            for instance in self.instances_of(&body.name) {
+                let type_name = Rc::new(instance.local_name.clone());
+                let decode_name = toks!(type_name.clone());
+                let fields = &instance.fields;
+                let class = self.type_class(type_name, decode_name, fields, &body.codes)?;
+                out.0.push(class);
+            }
+
+            return Ok(());
+        }
+
         let type_name = Rc::new(body.name.join(TYPE_SEP));
+        let decode_name = self.convert_type(&body.name)?;
+        out.0.push(self.type_class(type_name, decode_name, &body.fields, &body.codes)?);
+        Ok(())
+    }
+
+    /// Build the class for a type.
+    fn type_class<'el>(
+        &self,
+        type_name: Rc<String>,
+        decode_name: Tokens<'el, Python<'el>>,
+        fields: &'el [Loc<RpField>],
+        codes: &'el Vec<Loc<RpCode>>,
+    ) -> Result<Tokens<'el, Python<'el>>> {
         let mut class_body = Tokens::new();
 
-        let fields: Vec<Loc<PythonField>> = body.fields
+        let fields: Vec<Loc<PythonField>> = fields
             .iter()
             .map(|f| f.as_ref().map(|f| self.into_python_field(f)))
             .collect();
@@ -546,7 +715,7 @@ impl PythonBackend {
         }
 
         let decode = self.decode_method(
-            &body.name,
+            decode_name,
             &fields,
             |_, field| toks!(field.name.quoted()),
         )?;
@@ -559,10 +728,9 @@ impl PythonBackend {
 
         let repr_method = self.repr_method(type_name.clone(), &fields);
         class_body.push(repr_method);
-        class_body.push_unless_empty(Code(&body.codes, PYTHON_CONTEXT));
+        class_body.push_unless_empty(Code(codes, PYTHON_CONTEXT));
 
-        out.0.push(self.as_class(type_name, class_body));
-        Ok(())
+        Ok(self.as_class(type_name, class_body))
     }
 
     pub fn process_interface<'el>(
@@ -570,6 +738,10 @@ impl PythonBackend {
         out: &mut PythonFileSpec<'el>,
         body: &'el RpInterfaceBody,
     ) -> Result<()> {
+        if !body.type_parameters.is_empty() {
+            return Err("generic interfaces are not supported".into());
+        }
+
         let type_name = Rc::new(body.name.join(TYPE_SEP));
         let mut type_body = Tokens::new();
 
@@ -603,7 +775,9 @@ impl PythonBackend {
                 }
             }
 
-            let decode = self.decode_method(&sub_type.name, &fields, |_, field| {
+            let decode_name = self.convert_type(&sub_type.name)?;
+
+            let decode = self.decode_method(decode_name, &fields, |_, field| {
                 toks!(field.ident.clone().quoted())
             })?;
 
@@ -709,6 +883,24 @@ impl<'el> DynamicDecode<'el> for PythonBackend {
         toks![name, ".decode(", input, ")"]
     }
 
+    fn name_arguments_decode(
+        &self,
+        input: Tokens<'el, Self::Custom>,
+        name: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, Self::Custom>> {
+        let name = self.convert_instance(name, arguments)?;
+        Ok(self.name_decode(input, name))
+    }
+
+    fn generic_decode(
+        &self,
+        _: Tokens<'el, Self::Custom>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, Self::Custom>> {
+        Err(format!("unexpected type parameter: {}", name).into())
+    }
+
     fn array_decode(
         &self,
         input: Tokens<'el, Self::Custom>,
@@ -804,6 +996,23 @@ impl<'el> DynamicEncode<'el> for PythonBackend {
         toks![input, ".encode()"]
     }
 
+    fn name_arguments_encode(
+        &self,
+        input: Tokens<'el, Self::Custom>,
+        _: &'el RpName,
+        _: &'el [RpType],
+    ) -> Result<Tokens<'el, Self::Custom>> {
+        Ok(toks![input, ".encode()"])
+    }
+
+    fn generic_encode(
+        &self,
+        _: Tokens<'el, Self::Custom>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, Self::Custom>> {
+        Err(format!("unexpected type parameter: {}", name).into())
+    }
+
     fn array_encode(
         &self,
         input: Tokens<'el, Self::Custom>,
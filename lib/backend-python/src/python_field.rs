@@ -1,4 +1,4 @@
-use core::{RpModifier, RpType};
+use core::{Loc, RpConstraints, RpModifier, RpType, RpValue};
 use std::rc::Rc;
 
 #[derive(Debug, Clone)]
@@ -7,6 +7,8 @@ pub struct PythonField<'a> {
     pub ty: &'a RpType,
     pub name: &'a str,
     pub ident: Rc<String>,
+    pub constraints: &'a RpConstraints,
+    pub default: Option<&'a Loc<RpValue>>,
 }
 
 impl<'a> PythonField<'a> {
@@ -16,6 +18,8 @@ impl<'a> PythonField<'a> {
             ty: self.ty,
             name: self.name,
             ident: Rc::new(ident),
+            constraints: self.constraints,
+            default: self.default,
         }
     }
 }
@@ -3,8 +3,8 @@
 use super::RUST_CONTEXT;
 use backend::{Code, CompilerOptions, Environment, FromNaming, Naming, PackageUtils, SnakeCase};
 use backend::errors::*;
-use core::{ForEachLoc, Loc, RpEnumBody, RpEnumOrdinal, RpField, RpInterfaceBody, RpName,
-           RpServiceBody, RpTupleBody, RpType, RpTypeBody};
+use core::{ForEachLoc, Loc, RpEnumBody, RpEnumOrdinal, RpEnumType, RpField, RpInterfaceBody,
+           RpName, RpServiceBody, RpTupleBody, RpType, RpTypeBody, RpValue, WithPos};
 use genco::{Element, IntoTokens, Quoted, Rust, Tokens};
 use genco::rust::{imported_alias, imported_alias_ref, imported_ref};
 use listeners::Listeners;
@@ -32,6 +32,25 @@ impl<'a> IntoTokens<'a, Rust<'a>> for Derives {
     }
 }
 
+/// Serializer derives for enums which are serialized as their discriminant, through
+/// `serde_repr`.
+pub struct ReprDerives;
+
+impl<'a> IntoTokens<'a, Rust<'a>> for ReprDerives {
+    fn into_tokens(self) -> Tokens<'a, Rust<'a>> {
+        "#[derive(Serialize_repr, Deserialize_repr, Debug)]".into()
+    }
+}
+
+/// A repr attribute.
+pub struct Repr(String);
+
+impl<'a> IntoTokens<'a, Rust<'a>> for Repr {
+    fn into_tokens(self) -> Tokens<'a, Rust<'a>> {
+        toks!["#[repr(", self.0, ")]"]
+    }
+}
+
 /// A serde rename annotation.
 pub struct Rename<'a>(&'a str);
 
@@ -92,17 +111,38 @@ impl RustBackend {
     fn build_impl<'el>(
         &self,
         name: Rc<String>,
+        type_parameters: &[String],
         body: Tokens<'el, Rust<'el>>,
     ) -> Tokens<'el, Rust<'el>> {
         let mut out_impl = Tokens::new();
 
-        out_impl.push(toks!["impl ", name.clone(), " {"]);
+        let generics = self.generics(type_parameters);
+
+        out_impl.push(toks![
+            "impl",
+            generics.clone(),
+            " ",
+            name.clone(),
+            generics,
+            " {",
+        ]);
         out_impl.nested(body);
         out_impl.push("}");
 
         out_impl
     }
 
+    /// Build the generics of a declaration, like `<T, U>`.
+    ///
+    /// Empty if the declaration doesn't have any type parameters.
+    fn generics(&self, type_parameters: &[String]) -> String {
+        if type_parameters.is_empty() {
+            return String::new();
+        }
+
+        format!("<{}>", type_parameters.join(", "))
+    }
+
     fn ident(&self, name: &str) -> String {
         if let Some(ref id_converter) = self.id_converter {
             id_converter.convert(name)
@@ -157,6 +197,7 @@ impl RustBackend {
     fn enum_value_fn<'a>(
         &self,
         name: Rc<String>,
+        value_type: Tokens<'a, Rust<'a>>,
         match_body: Tokens<'a, Rust<'a>>,
     ) -> Tokens<'a, Rust<'a>> {
         let mut value_fn = Tokens::new();
@@ -166,7 +207,7 @@ impl RustBackend {
         match_decl.nested(match_body);
         match_decl.push("}");
 
-        value_fn.push("pub fn value(&self) -> &'static str {");
+        value_fn.push(toks!["pub fn value(&self) -> ", value_type, " {"]);
         value_fn.nested(toks!["use self::", name, "::*;"]);
         value_fn.nested(match_decl);
         value_fn.push("}");
@@ -202,7 +243,27 @@ impl RustBackend {
                 let argument = self.into_rust_type(inner)?;
                 toks!["Vec<", argument, ">"]
             }
-            Name { ref name } => toks![self.convert_type_id(name)?],
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                let mut out = toks![self.convert_type_id(name)?];
+
+                if !arguments.is_empty() {
+                    let mut inner = Tokens::new();
+
+                    for argument in arguments {
+                        inner.append(self.into_rust_type(argument)?);
+                    }
+
+                    out.append("<");
+                    out.append(inner.join(", "));
+                    out.append(">");
+                }
+
+                out
+            }
+            Generic { ref name } => toks![name.as_str()],
             Map { ref key, ref value } => {
                 let key = self.into_rust_type(key)?;
                 let value = self.into_rust_type(value)?;
@@ -215,8 +276,35 @@ impl RustBackend {
         Ok(ty)
     }
 
-    // Build the corresponding element out of a field declaration.
-    fn field_element<'a>(&self, field: &'a RpField) -> Result<Tokens<'a, Rust<'a>>> {
+    /// Build the literal for the default value of a field.
+    fn default_value<'a>(&self, ty: &RpType, value: &RpValue) -> Result<Tokens<'a, Rust<'a>>> {
+        let out = match (ty, value) {
+            (_, &RpValue::Boolean(true)) => toks!["true"],
+            (_, &RpValue::Boolean(false)) => toks!["false"],
+            (_, &RpValue::String(ref string)) => {
+                toks![string.as_str().quoted(), ".to_string()"]
+            }
+            (&RpType::Float, &RpValue::Number(ref number)) |
+            (&RpType::Double, &RpValue::Number(ref number)) if number.decimal == 0 => {
+                toks![format!("{}.0", number)]
+            }
+            (_, &RpValue::Number(ref number)) => toks![number.to_string()],
+            (_, value) => return Err(format!("unsupported default value: {}", value).into()),
+        };
+
+        Ok(out)
+    }
+
+    /// Build the element for a single field.
+    ///
+    /// Fields with default values get a function providing the default pushed to `defaults`,
+    /// named after `owner`.
+    fn field_element<'a>(
+        &self,
+        owner: &str,
+        defaults: &mut Vec<Tokens<'a, Rust<'a>>>,
+        field: &'a RpField,
+    ) -> Result<Tokens<'a, Rust<'a>>> {
         let mut elements = Tokens::new();
 
         let ident = self.ident(field.ident());
@@ -226,6 +314,25 @@ impl RustBackend {
             elements.push(toks!["#[serde(skip_serializing_if=\"Option::is_none\")]"]);
         }
 
+        if let Some(ref default) = field.default {
+            let fn_name = Rc::new(format!(
+                "default_{}_{}",
+                owner.to_lowercase(),
+                field.ident()
+            ));
+
+            let value = self.default_value(&field.ty, default.value())
+                .with_pos(default.pos())?;
+
+            let mut default_fn = Tokens::new();
+            default_fn.push(toks!["fn ", fn_name.clone(), "() -> ", type_spec.clone(), " {"]);
+            default_fn.nested(toks!["Some(", value, ")"]);
+            default_fn.push("}");
+            defaults.push(default_fn);
+
+            elements.push(toks!["#[serde(default = \"", fn_name, "\")]"]);
+        }
+
         if field.name() != ident {
             elements.push(Rename(field.name()));
         }
@@ -254,6 +361,7 @@ impl RustBackend {
         elements.push(toks![
             "struct ",
             name,
+            self.generics(&body.type_parameters),
             "(",
             fields.join(", "),
             ");",
@@ -271,32 +379,53 @@ impl RustBackend {
         // body of value function
         let mut match_body = Tokens::new();
 
+        // numeric enums are serialized as their discriminant.
+        let repr = match body.variant_type {
+            RpEnumType::Signed { size } => Some(format!("i{}", size)),
+            RpEnumType::Unsigned { size } => Some(format!("u{}", size)),
+            RpEnumType::String | RpEnumType::Generated => None,
+        };
+
         body.variants.iter().for_each_loc(|variant| {
-            let value = if let RpEnumOrdinal::String(ref s) = variant.ordinal {
-                if s != variant.local_name.as_str() {
-                    variants.push(Rename(s.as_str()));
+            let local_name = variant.local_name.value().as_str();
+
+            let value: Element<'a, Rust<'a>> = match variant.ordinal {
+                RpEnumOrdinal::Number(ref number) => {
+                    let number = number.to_string();
+                    variants.push(toks![local_name, " = ", number.clone(), ","]);
+                    number.into()
                 }
+                RpEnumOrdinal::String(ref s) => {
+                    if s != local_name {
+                        variants.push(Rename(s.as_str()));
+                    }
 
-                s
-            } else {
-                variant.local_name.as_str()
+                    variants.push(toks![local_name, ","]);
+                    s.as_str().quoted()
+                }
+                RpEnumOrdinal::Generated => {
+                    variants.push(toks![local_name, ","]);
+                    local_name.quoted()
+                }
             };
 
-            match_body.push(toks![
-                variant.local_name.value().as_str(),
-                " => ",
-                value.quoted(),
-                ",",
-            ]);
-
-            variants.push(toks![variant.local_name.value().as_str(), ","]);
+            match_body.push(toks![local_name, " => ", value, ","]);
             Ok(()) as Result<()>
         })?;
 
         let mut out_enum = Tokens::new();
 
         out_enum.push_unless_empty(attributes);
-        out_enum.push(Derives);
+
+        let value_type = if let Some(repr) = repr {
+            out_enum.push(ReprDerives);
+            out_enum.push(Repr(repr.clone()));
+            toks![repr]
+        } else {
+            out_enum.push(Derives);
+            toks!["&'static str"]
+        };
+
         out_enum.push(toks!["pub enum ", name.clone(), " {"]);
         out_enum.nested(variants);
         out_enum.push("}");
@@ -307,7 +436,7 @@ impl RustBackend {
 
         out_impl.nested({
             let mut t = Tokens::new();
-            t.push(self.enum_value_fn(name.clone(), match_body));
+            t.push(self.enum_value_fn(name.clone(), value_type, match_body));
             t.push_unless_empty(Code(&body.codes, RUST_CONTEXT));
             t
         });
@@ -320,27 +449,40 @@ impl RustBackend {
     }
 
     pub fn process_type<'a>(&self, out: &mut RustFileSpec<'a>, body: &'a RpTypeBody) -> Result<()> {
+        let (name, attributes) = self.convert_type_name(&body.name);
+
         let mut fields = Tokens::new();
+        let mut defaults = Vec::new();
 
         for field in &body.fields {
-            fields.push(field.as_ref().and_then(|f| self.field_element(f))?);
+            fields.push(field.as_ref().and_then(|f| {
+                self.field_element(name.as_str(), &mut defaults, f)
+            })?);
         }
 
-        let (name, attributes) = self.convert_type_name(&body.name);
         let mut t = Tokens::new();
 
         t.push_unless_empty(attributes);
         t.push(Derives);
-        t.push(toks!["pub struct ", name.clone(), " {"]);
+        t.push(toks![
+            "pub struct ",
+            name.clone(),
+            self.generics(&body.type_parameters),
+            " {",
+        ]);
         t.nested(fields);
         t.push("}");
 
         out.0.push(t);
 
+        for default_fn in defaults {
+            out.0.push(default_fn);
+        }
+
         let impl_body = Code(&body.codes, RUST_CONTEXT).into_tokens();
 
         if !impl_body.is_empty() {
-            out.0.push(self.build_impl(name.clone(), impl_body));
+            out.0.push(self.build_impl(name.clone(), &body.type_parameters, impl_body));
         }
 
         Ok(())
@@ -357,7 +499,14 @@ impl RustBackend {
         t.push_unless_empty(attributes);
         t.push(Derives);
         t.push(Tag("type"));
-        t.push(toks!["pub enum ", name.clone(), " {"]);
+        t.push(toks![
+            "pub enum ",
+            name.clone(),
+            self.generics(&body.type_parameters),
+            " {",
+        ]);
+
+        let mut defaults = Vec::new();
 
         let sub_types = body.sub_types.values().map(AsRef::as_ref);
 
@@ -375,8 +524,10 @@ impl RustBackend {
 
             spec.push(toks![s.local_name.as_str(), " {"]);
 
+            let owner = format!("{}_{}", name, s.local_name);
+
             for field in body.fields.iter().chain(s.fields.iter()) {
-                spec.nested(self.field_element(field)?);
+                spec.nested(self.field_element(owner.as_str(), &mut defaults, field)?);
             }
 
             spec.push("},");
@@ -388,10 +539,14 @@ impl RustBackend {
 
         out.0.push(t);
 
+        for default_fn in defaults {
+            out.0.push(default_fn);
+        }
+
         let impl_body = Code(&body.codes, RUST_CONTEXT).into_tokens();
 
         if !impl_body.is_empty() {
-            out.0.push(self.build_impl(name.clone(), impl_body));
+            out.0.push(self.build_impl(name.clone(), &body.type_parameters, impl_body));
         }
 
         Ok(())
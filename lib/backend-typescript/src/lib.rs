@@ -0,0 +1,84 @@
+#[macro_use]
+extern crate genco;
+#[allow(unused)]
+#[macro_use]
+extern crate serde_derive;
+extern crate reproto_backend as backend;
+extern crate reproto_core as core;
+extern crate reproto_manifest as manifest;
+extern crate serde;
+extern crate toml;
+
+#[macro_use]
+mod utils;
+mod ts_field;
+mod listeners;
+mod ts_backend;
+mod ts_compiler;
+mod ts_file_spec;
+mod ts_options;
+
+use self::backend::{ArgMatches, CompilerOptions, Environment, Options};
+use self::backend::errors::*;
+use self::listeners::Listeners;
+use self::ts_backend::TsBackend;
+use self::ts_options::TsOptions;
+use manifest::{Lang, Manifest, NoModule, TryFromToml, self as m};
+use std::path::Path;
+
+const TYPE: &str = "type";
+const TYPE_SEP: &str = "_";
+const EXT: &str = "ts";
+const TS_CONTEXT: &str = "ts";
+
+#[derive(Default)]
+pub struct TsLang;
+
+impl Lang for TsLang {
+    type Module = TsModule;
+}
+
+#[derive(Debug)]
+pub enum TsModule {
+}
+
+impl TryFromToml for TsModule {
+    fn try_from_string(path: &Path, id: &str, value: String) -> m::errors::Result<Self> {
+        NoModule::illegal(path, id, value)
+    }
+
+    fn try_from_value(path: &Path, id: &str, value: toml::Value) -> m::errors::Result<Self> {
+        NoModule::illegal(path, id, value)
+    }
+}
+
+fn setup_listeners(modules: &[TsModule]) -> Result<(TsOptions, Box<Listeners>)> {
+    let listeners: Vec<Box<Listeners>> = Vec::new();
+
+    for module in modules {
+        match *module {
+        }
+    }
+
+    let mut options = TsOptions::new();
+
+    for listener in &listeners {
+        listener.configure(&mut options)?;
+    }
+
+    Ok((options, Box::new(listeners)))
+}
+
+pub fn compile(
+    env: Environment,
+    opts: Options,
+    compiler_options: CompilerOptions,
+    _matches: &ArgMatches,
+    manifest: Manifest<TsLang>,
+) -> Result<()> {
+    let id_converter = opts.id_converter;
+    let (options, listeners) = setup_listeners(&manifest.modules)?;
+    let backend = TsBackend::new(env, options, listeners, id_converter);
+    let compiler = backend.compiler(compiler_options)?;
+    compiler.compile()
+}
@@ -0,0 +1,18 @@
+use super::*;
+
+pub trait Listeners {
+    fn configure(&self, _: &mut TsOptions) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A vector of listeners is a valid listener.
+impl Listeners for Vec<Box<Listeners>> {
+    fn configure(&self, options: &mut TsOptions) -> Result<()> {
+        for listeners in self {
+            listeners.configure(options)?;
+        }
+
+        Ok(())
+    }
+}
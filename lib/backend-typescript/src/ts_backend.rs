@@ -0,0 +1,1078 @@
+use super::{TS_CONTEXT, TYPE, TYPE_SEP};
+use backend::{Code, CompilerOptions, Converter, DynamicConverter, DynamicDecode, DynamicEncode,
+              Environment, Naming, PackageUtils};
+use backend::errors::*;
+use core::{ForEachLoc, Loc, RpEnumBody, RpField, RpInterfaceBody, RpModifier, RpName, RpTupleBody,
+           RpType, RpTypeBody};
+use genco::{Element, JavaScript, Quoted, Tokens};
+use genco::js::imported_alias;
+use listeners::Listeners;
+use std::borrow::Cow;
+use std::rc::Rc;
+use ts_compiler::TsCompiler;
+use ts_field::TsField;
+use ts_file_spec::TsFileSpec;
+use ts_options::TsOptions;
+use utils::{is_defined, is_not_defined};
+
+pub struct TsBackend {
+    pub env: Environment,
+    listeners: Box<Listeners>,
+    id_converter: Option<Box<Naming>>,
+    type_var: Tokens<'static, JavaScript<'static>>,
+    values: Tokens<'static, JavaScript<'static>>,
+}
+
+impl TsBackend {
+    pub fn new(
+        env: Environment,
+        _: TsOptions,
+        listeners: Box<Listeners>,
+        id_converter: Option<Box<Naming>>,
+    ) -> TsBackend {
+        TsBackend {
+            env: env,
+            listeners: listeners,
+            id_converter: id_converter,
+            type_var: TYPE.quoted().into(),
+            values: "values".into(),
+        }
+    }
+
+    pub fn compiler(&self, options: CompilerOptions) -> Result<TsCompiler> {
+        Ok(TsCompiler {
+            out_path: options.out_path,
+            backend: self,
+        })
+    }
+
+    /// Convert the given type into its TypeScript counterpart.
+    fn into_ts_type<'el>(&self, ty: &'el RpType) -> Result<Tokens<'el, JavaScript<'el>>> {
+        use self::RpType::*;
+
+        let out = match *ty {
+            Signed { size: _ } |
+            Unsigned { size: _ } => toks!["number"],
+            Float | Double => toks!["number"],
+            Boolean => toks!["boolean"],
+            String | DateTime | Bytes => toks!["string"],
+            Any => toks!["any"],
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                let mut out = self.convert_type(name)?;
+
+                if !arguments.is_empty() {
+                    let mut inner = Tokens::new();
+
+                    for argument in arguments {
+                        inner.append(self.into_ts_type(argument)?);
+                    }
+
+                    out.append("<");
+                    out.append(inner.join(", "));
+                    out.append(">");
+                }
+
+                out
+            }
+            Generic { ref name } => toks![name.as_str()],
+            Array { ref inner } => toks!["Array<", self.into_ts_type(inner)?, ">"],
+            Map { ref key, ref value } => {
+                // index signatures are only permitted for strings and numbers.
+                let key = match **key {
+                    Signed { size: _ } |
+                    Unsigned { size: _ } => "number",
+                    Float | Double => "number",
+                    _ => "string",
+                };
+
+                toks!["{[key: ", key, "]: ", self.into_ts_type(value)?, "}"]
+            }
+        };
+
+        Ok(out)
+    }
+
+    /// Build the generics of a declaration, like `<T, U>`.
+    ///
+    /// Empty if the declaration doesn't have any type parameters.
+    fn generics(&self, type_parameters: &[String]) -> String {
+        if type_parameters.is_empty() {
+            return String::new();
+        }
+
+        format!("<{}>", type_parameters.join(", "))
+    }
+
+    /// Build the arguments for the functions that encode or decode each type parameter.
+    ///
+    /// `signature` builds the type of the function for the given parameter.
+    fn type_parameter_functions<'el, F>(
+        &self,
+        out: &mut Tokens<'el, JavaScript<'el>>,
+        prefix: &str,
+        type_parameters: &[String],
+        signature: F,
+    ) where
+        F: Fn(&str) -> String,
+    {
+        for parameter in type_parameters {
+            out.append(format!("{}{}: {}", prefix, parameter, signature(parameter)));
+        }
+    }
+
+    /// Type of a field, taking optionality into account.
+    fn field_type<'el>(&self, field: &TsField<'el>) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let ty = self.into_ts_type(field.ty)?;
+
+        match *field.modifier {
+            RpModifier::Optional => Ok(toks![ty, " | null"]),
+            _ => Ok(ty),
+        }
+    }
+
+    /// Build a function that throws an exception if the given value `toks` is None.
+    fn throw_if_null<'el, S>(&self, toks: S, field: &TsField) -> Tokens<'el, JavaScript<'el>>
+    where
+        S: Into<Tokens<'el, JavaScript<'el>>>,
+    {
+        let required_error = format!("{}: is a required field", field.name).quoted();
+        ts![if is_not_defined(toks), ts![throw required_error]]
+    }
+
+    fn encode_method<'el>(
+        &self,
+        fields: &[Loc<TsField<'el>>],
+        type_parameters: &[String],
+        extra: Option<Tokens<'el, JavaScript<'el>>>,
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut body = Tokens::new();
+
+        body.push("const data: any = {};");
+
+        if let Some(extra) = extra {
+            body.push(extra);
+        }
+
+        let mut assign = Tokens::new();
+
+        fields.for_each_loc(|field| {
+            let var_string = field.name.quoted();
+            let field_toks = toks!["this.", field.ident.clone()];
+            let value_toks = self.dynamic_encode(field.ty, field_toks.clone())?;
+
+            match *field.modifier {
+                RpModifier::Optional => {
+                    let toks = ts![if is_defined(field_toks),
+                                      toks!["data[", var_string, "] = ", value_toks, ";"]];
+                    assign.push(toks);
+                }
+                _ => {
+                    assign.push(self.throw_if_null(field_toks, field));
+                    let toks = toks!["data[", var_string, "] = ", value_toks, ";"];
+                    assign.push(toks);
+                }
+            }
+
+            Ok(()) as Result<()>
+        })?;
+
+        if !assign.is_empty() {
+            body.push(assign.join_line_spacing());
+        }
+
+        body.push(ts![return "data"]);
+
+        let mut arguments = Tokens::new();
+        self.type_parameter_functions(&mut arguments, "encode", type_parameters, |p| {
+            format!("(v: {}) => any", p)
+        });
+
+        Ok({
+            let mut t = Tokens::new();
+            t.push(toks!["encode(", arguments.join(", "), "): any {"]);
+            t.nested(body.join_line_spacing());
+            t.push("}");
+            t
+        })
+    }
+
+    fn encode_tuple_method<'el>(
+        &self,
+        fields: &[Loc<TsField<'el>>],
+        type_parameters: &[String],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut values = Tokens::new();
+
+        let mut body = Tokens::new();
+
+        fields.for_each_loc(|field| {
+            let toks = toks!["this.", field.ident.clone()];
+            body.push(self.throw_if_null(toks.clone(), field));
+            values.push(self.dynamic_encode(field.ty, toks)?);
+            Ok(()) as Result<()>
+        })?;
+
+        body.push(ts![@return [ values ]]);
+
+        let mut arguments = Tokens::new();
+        self.type_parameter_functions(&mut arguments, "encode", type_parameters, |p| {
+            format!("(v: {}) => any", p)
+        });
+
+        let mut encode = Tokens::new();
+        encode.push(toks!["encode(", arguments.join(", "), "): any {"]);
+        encode.nested(body.join_line_spacing());
+        encode.push("}");
+        Ok(encode)
+    }
+
+    /// Static encode method, permitting all named types to be encoded through
+    /// `<Name>.encode(value)`.
+    fn static_encode_method<'el>(
+        &self,
+        type_name: Rc<String>,
+        type_parameters: &[String],
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let generics = self.generics(type_parameters);
+
+        let mut arguments = Tokens::new();
+        arguments.append(toks!["value: ", type_name, generics.clone()]);
+        self.type_parameter_functions(&mut arguments, "encode", type_parameters, |p| {
+            format!("(v: {}) => any", p)
+        });
+
+        let mut forwarded = Tokens::new();
+
+        for parameter in type_parameters {
+            forwarded.append(format!("encode{}", parameter));
+        }
+
+        let mut encode = Tokens::new();
+        encode.push(toks!["static encode", generics, "(", arguments.join(", "), "): any {"]);
+        encode.nested(ts![return toks!["value.encode(", forwarded.join(", "), ")"]]);
+        encode.push("}");
+        encode
+    }
+
+    fn decode_method<'el, F>(
+        &self,
+        fields: &[Loc<TsField<'el>>],
+        type_parameters: &[String],
+        type_name: Rc<String>,
+        variable_fn: F,
+    ) -> Result<Tokens<'el, JavaScript<'el>>>
+    where
+        F: Fn(usize, &TsField<'el>) -> Element<'el, JavaScript<'el>>,
+    {
+        let mut arguments = Tokens::new();
+        let mut assign = Tokens::new();
+
+        for (i, field) in fields.iter().enumerate() {
+            let var_name = Rc::new(format!("v_{}", field.ident.clone()));
+            let var = variable_fn(i, field);
+
+            let toks = field.as_ref().and_then(|field| match *field.modifier {
+                RpModifier::Optional => {
+                    let var_name = toks![var_name.clone()];
+                    let var_toks = self.dynamic_decode(field.ty, var_name.clone())?;
+
+                    let mut check = Tokens::new();
+
+                    check.push(toks!["let ", var_name.clone(), " = data[", var, "];"]);
+                    check.push(ts![if is_defined(var_name.clone()),
+                                      toks![var_name.clone(), " = ", var_toks, ";"],
+                                      toks![var_name, " = null", ";"]]);
+
+                    Ok(check.join_line_spacing().into()) as Result<Tokens<'el, JavaScript<'el>>>
+                }
+                _ => {
+                    let var_toks = toks!["data[", var.clone(), "]"];
+                    let var_toks = self.dynamic_decode(field.ty, var_toks.into())?;
+
+                    let mut check = Tokens::new();
+
+                    let var_name = toks![var_name.clone()];
+
+                    check.push(toks!["const ", var_name.clone(), " = ", var_toks, ";"]);
+                    check.push(ts![if is_not_defined(var_name),
+                                   ts![throw var, " + ", ": required field".quoted()]]);
+
+                    Ok(check.join_line_spacing().into()) as Result<Tokens<'el, JavaScript<'el>>>
+                }
+            })?;
+
+            assign.push(toks);
+            arguments.append(var_name);
+        }
+
+        let mut body = Tokens::new();
+
+        if !assign.is_empty() {
+            body.push(assign.join_line_spacing());
+        }
+
+        body.push(ts![@return new type_name.clone(), arguments]);
+
+        let generics = self.generics(type_parameters);
+
+        let mut decode_arguments = Tokens::new();
+        decode_arguments.append("data: any");
+        self.type_parameter_functions(&mut decode_arguments, "decode", type_parameters, |p| {
+            format!("(v: any) => {}", p)
+        });
+
+        let mut decode = Tokens::new();
+        decode.push(toks![
+            "static decode",
+            generics.clone(),
+            "(",
+            decode_arguments.join(", "),
+            "): ",
+            type_name,
+            generics,
+            " {",
+        ]);
+        decode.nested(body.join_line_spacing());
+        decode.push("}");
+        Ok(decode)
+    }
+
+    fn field_by_name<'el>(_i: usize, field: &TsField<'el>) -> Element<'el, JavaScript<'el>> {
+        field.name.quoted()
+    }
+
+    fn field_by_index<'el>(i: usize, _field: &TsField<'el>) -> Element<'el, JavaScript<'el>> {
+        i.to_string().into()
+    }
+
+    fn field_ident(&self, field: &RpField) -> String {
+        if let Some(ref id_converter) = self.id_converter {
+            id_converter.convert(field.ident())
+        } else {
+            field.ident().to_owned()
+        }
+    }
+
+    /// Build field declarations for a class.
+    fn build_fields<'el>(
+        &self,
+        fields: &[Loc<TsField<'el>>],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut out = Tokens::new();
+
+        for field in fields {
+            out.push(toks![field.ident.clone(), ": ", self.field_type(field)?, ";"]);
+        }
+
+        Ok(out)
+    }
+
+    fn build_constructor<'el>(
+        &self,
+        fields: &[Loc<TsField<'el>>],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut arguments = Tokens::new();
+        let mut assignments = Tokens::new();
+
+        for field in fields {
+            arguments.append(toks![field.ident.clone(), ": ", self.field_type(field)?]);
+            assignments.push(toks![
+                "this.",
+                field.ident.clone(),
+                " = ",
+                field.ident.clone(),
+                ";",
+            ]);
+        }
+
+        let mut ctor = Tokens::new();
+        ctor.push(toks!["constructor(", arguments.join(", "), ") {"]);
+        ctor.nested(assignments);
+        ctor.push("}");
+        Ok(ctor)
+    }
+
+    /// Build the body of a class, shared by types, tuples, and sub-types.
+    fn build_class<'el>(
+        &self,
+        type_name: Rc<String>,
+        type_parameters: &[String],
+        fields: &[Loc<TsField<'el>>],
+        prefix: Option<Tokens<'el, JavaScript<'el>>>,
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut class_body = Tokens::new();
+
+        let mut declarations = Tokens::new();
+
+        if let Some(prefix) = prefix {
+            declarations.push(prefix);
+        }
+
+        declarations.push_unless_empty(self.build_fields(fields)?);
+        class_body.push_unless_empty(declarations);
+        class_body.push(self.build_constructor(fields)?);
+        class_body.push(self.static_encode_method(type_name, type_parameters));
+        Ok(class_body)
+    }
+
+    fn into_ts_field<'el>(&self, field: &'el RpField) -> TsField<'el> {
+        let ident = self.field_ident(&field);
+
+        TsField {
+            modifier: &field.modifier,
+            ty: &field.ty,
+            name: field.name(),
+            ident: Rc::new(ident),
+        }
+    }
+
+    pub fn process_tuple<'el>(
+        &self,
+        out: &mut TsFileSpec<'el>,
+        body: &'el RpTupleBody,
+    ) -> Result<()> {
+        let tuple_name = Rc::new(body.name.join(TYPE_SEP));
+
+        let fields: Vec<Loc<TsField>> = body.fields
+            .iter()
+            .map(|f| f.as_ref().map(|f| self.into_ts_field(f)))
+            .collect();
+
+        let mut class_body =
+            self.build_class(tuple_name.clone(), &body.type_parameters, &fields, None)?;
+
+        class_body.push(self.decode_method(
+            &fields,
+            &body.type_parameters,
+            tuple_name.clone(),
+            Self::field_by_index,
+        )?);
+
+        class_body.push(self.encode_tuple_method(&fields, &body.type_parameters)?);
+        class_body.push_unless_empty(Code(&body.codes, TS_CONTEXT));
+
+        let mut class = Tokens::new();
+
+        class.push(toks![
+            "export class ",
+            tuple_name,
+            self.generics(&body.type_parameters),
+            " {",
+        ]);
+        class.nested(class_body.join_line_spacing());
+        class.push("}");
+
+        out.0.push(class);
+        Ok(())
+    }
+
+    /// Convert enum to TypeScript.
+    ///
+    /// Enums are represented as a union of string literals, accompanied by a namespace containing
+    /// the known values and functions to decode and encode them.
+    pub fn process_enum<'el>(
+        &self,
+        out: &mut TsFileSpec<'el>,
+        body: &'el Loc<RpEnumBody>,
+    ) -> Result<()> {
+        let type_name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut literals = Tokens::new();
+        let mut constants = Tokens::new();
+        let mut members = Tokens::new();
+
+        body.variants.iter().for_each_loc(|variant| {
+            let ordinal = self.ordinal(variant)?;
+
+            literals.append(ordinal.clone());
+            constants.push(toks![
+                "export const ",
+                variant.local_name.as_str(),
+                ": ",
+                type_name.clone(),
+                " = ",
+                ordinal,
+                ";",
+            ]);
+            members.append(variant.local_name.as_str());
+
+            Ok(()) as Result<()>
+        })?;
+
+        let literals = if literals.is_empty() {
+            toks!["never"]
+        } else {
+            literals.join(" | ")
+        };
+
+        let mut namespace_body = Tokens::new();
+
+        namespace_body.push(constants);
+
+        namespace_body.push(toks![
+            "export const ",
+            self.values.clone(),
+            ": ",
+            type_name.clone(),
+            "[] = ",
+            ts!([members]),
+            ";",
+        ]);
+
+        namespace_body.push({
+            let cond = toks![self.values.clone(), ".indexOf(data) === -1"];
+
+            let mut body = Tokens::new();
+            body.push(ts![if cond, ts![throw "no matching value: ".quoted(), " + data"]]);
+            body.push(ts![return "data"]);
+
+            let mut decode = Tokens::new();
+            decode.push(toks!["export function decode(data: any): ", type_name.clone(), " {"]);
+            decode.nested(body.join_line_spacing());
+            decode.push("}");
+            decode
+        });
+
+        namespace_body.push({
+            let mut encode = Tokens::new();
+            encode.push(toks!["export function encode(value: ", type_name.clone(), "): any {"]);
+            encode.nested(ts![return "value"]);
+            encode.push("}");
+            encode
+        });
+
+        namespace_body.push_unless_empty(Code(&body.codes, TS_CONTEXT));
+
+        let mut elements = Tokens::new();
+
+        elements.push(toks!["export type ", type_name.clone(), " = ", literals, ";"]);
+
+        elements.push({
+            let mut namespace = Tokens::new();
+            namespace.push(toks!["export namespace ", type_name.clone(), " {"]);
+            namespace.nested(namespace_body.join_line_spacing());
+            namespace.push("}");
+            namespace
+        });
+
+        out.0.push(elements.join_line_spacing());
+        Ok(())
+    }
+
+    pub fn process_type<'el>(
+        &self,
+        out: &mut TsFileSpec<'el>,
+        body: &'el RpTypeBody,
+    ) -> Result<()> {
+        let fields: Vec<Loc<TsField>> = body.fields
+            .iter()
+            .map(|f| f.as_ref().map(|f| self.into_ts_field(f)))
+            .collect();
+
+        let type_name = Rc::new(body.name.join(TYPE_SEP));
+
+        let mut class_body =
+            self.build_class(type_name.clone(), &body.type_parameters, &fields, None)?;
+
+        class_body.push(self.decode_method(
+            &fields,
+            &body.type_parameters,
+            type_name.clone(),
+            Self::field_by_name,
+        )?);
+
+        class_body.push(self.encode_method(&fields, &body.type_parameters, None)?);
+        class_body.push_unless_empty(Code(&body.codes, TS_CONTEXT));
+
+        let mut class = Tokens::new();
+
+        class.push(toks![
+            "export class ",
+            type_name,
+            self.generics(&body.type_parameters),
+            " {",
+        ]);
+        class.nested(class_body.join_line_spacing());
+        class.push("}");
+
+        out.0.push(class);
+        Ok(())
+    }
+
+    /// Convert interface to TypeScript.
+    ///
+    /// Interfaces are represented as a discriminated union over all sub-types, where each sub-type
+    /// carries a literal `type` field.
+    pub fn process_interface<'el>(
+        &self,
+        out: &mut TsFileSpec<'el>,
+        body: &'el RpInterfaceBody,
+    ) -> Result<()> {
+        if !body.type_parameters.is_empty() {
+            return Err(
+                format!("{}: generic interfaces are not supported", body.name).into(),
+            );
+        }
+
+        let mut classes = Tokens::new();
+        let interface_type_name = Rc::new(body.name.join(TYPE_SEP));
+
+        let interface_fields: Vec<Loc<TsField>> = body.fields
+            .iter()
+            .map(|f| f.as_ref().map(|f| self.into_ts_field(f)))
+            .collect();
+
+        let mut variants = Tokens::new();
+
+        let sub_types = body.sub_types.values().map(|l| l.loc_ref());
+
+        sub_types.for_each_loc(|sub_type| {
+            let type_name = Rc::new(sub_type.name.join(TYPE_SEP));
+            let sub_type_name = Rc::new(sub_type.name().to_owned());
+
+            let fields: Vec<Loc<TsField>> = interface_fields
+                .iter()
+                .cloned()
+                .chain(sub_type.fields.iter().map(|f| {
+                    f.as_ref().map(|f| self.into_ts_field(f))
+                }))
+                .collect();
+
+            let discriminant = toks![
+                "readonly ",
+                TYPE,
+                ": ",
+                sub_type_name.clone().quoted(),
+                " = ",
+                sub_type_name.clone().quoted(),
+                ";",
+            ];
+
+            let mut class_body =
+                self.build_class(type_name.clone(), &[], &fields, Some(discriminant))?;
+
+            class_body.push(self.decode_method(
+                &fields,
+                &[],
+                type_name.clone(),
+                Self::field_by_name,
+            )?);
+
+            let type_toks = toks![
+                "data[",
+                self.type_var.clone(),
+                "] = ",
+                sub_type_name.quoted(),
+                ";",
+            ];
+
+            class_body.push(self.encode_method(&fields, &[], Some(type_toks))?);
+            class_body.push_unless_empty(Code(&sub_type.codes, TS_CONTEXT));
+
+            classes.push({
+                let mut tokens = Tokens::new();
+
+                tokens.push(toks!["export class ", type_name.clone(), " {"]);
+                tokens.nested(class_body.join_line_spacing());
+                tokens.push("}");
+
+                tokens
+            });
+
+            variants.append(type_name);
+            Ok(()) as Result<()>
+        })?;
+
+        let variants = if variants.is_empty() {
+            toks!["never"]
+        } else {
+            variants.join(" | ")
+        };
+
+        let mut namespace_body = Tokens::new();
+
+        namespace_body.push(self.interface_decode_method(&body)?);
+
+        namespace_body.push({
+            let mut encode = Tokens::new();
+            encode.push(toks![
+                "export function encode(value: ",
+                interface_type_name.clone(),
+                "): any {",
+            ]);
+            encode.nested(ts![return "value.encode()"]);
+            encode.push("}");
+            encode
+        });
+
+        namespace_body.push_unless_empty(Code(&body.codes, TS_CONTEXT));
+
+        let mut elements = Tokens::new();
+
+        elements.push(toks![
+            "export type ",
+            interface_type_name.clone(),
+            " = ",
+            variants,
+            ";",
+        ]);
+
+        elements.push({
+            let mut namespace = Tokens::new();
+            namespace.push(toks!["export namespace ", interface_type_name.clone(), " {"]);
+            namespace.nested(namespace_body.join_line_spacing());
+            namespace.push("}");
+            namespace
+        });
+
+        elements.push(classes.join_line_spacing());
+
+        out.0.push(elements.join_line_spacing());
+        Ok(())
+    }
+}
+
+impl PackageUtils for TsBackend {}
+
+impl<'el> Converter<'el> for TsBackend {
+    type Custom = JavaScript<'el>;
+
+    fn convert_type(&self, name: &'el RpName) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let registered = self.env.lookup(name)?;
+
+        let local_name = registered.local_name(name, |p| p.join(TYPE_SEP), |c| c.join(TYPE_SEP));
+
+        if let Some(ref used) = name.prefix {
+            let package = self.package(&name.package).parts.join(".");
+            return Ok(
+                imported_alias(
+                    Cow::Owned(package),
+                    Cow::Owned(local_name),
+                    Cow::Borrowed(used),
+                ).into(),
+            );
+        }
+
+        Ok(local_name.into())
+    }
+}
+
+impl<'el> DynamicConverter<'el> for TsBackend {
+    fn is_native(&self, ty: &RpType) -> bool {
+        use self::RpType::*;
+
+        match *ty {
+            Signed { size: _ } |
+            Unsigned { size: _ } => true,
+            Float | Double => true,
+            String => true,
+            Any => true,
+            Boolean => true,
+            Array { ref inner } => self.is_native(inner),
+            Map { ref key, ref value } => self.is_native(key) && self.is_native(value),
+            _ => false,
+        }
+    }
+
+    fn map_key_var(&self) -> Tokens<'el, JavaScript<'el>> {
+        toks!["k"]
+    }
+
+    fn map_value_var(&self) -> Tokens<'el, JavaScript<'el>> {
+        toks!["data[k]"]
+    }
+
+    fn array_inner_var(&self) -> Tokens<'el, JavaScript<'el>> {
+        toks!["v"]
+    }
+}
+
+impl<'el> DynamicDecode<'el> for TsBackend {
+    fn name_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        toks![name, ".decode(", input, ")"]
+    }
+
+    /// Decoding a type with type arguments.
+    ///
+    /// A function decoding each argument is passed along to the decode method of the type.
+    fn name_arguments_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut out = Tokens::new();
+        out.append(input);
+
+        for argument in arguments {
+            let decode = self.dynamic_decode(argument, toks!["v"])?;
+            out.append(toks!["(v: any) => ", decode]);
+        }
+
+        Ok(toks![self.convert_type(name)?, ".decode(", out.join(", "), ")"])
+    }
+
+    fn generic_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        Ok(toks![format!("decode{}", name), "(", input, ")"])
+    }
+
+    /// Decoding an Array in TypeScript.
+    ///
+    /// Maps over each decoded value using `Array.map(...)`, decoding each variable.
+    fn array_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        inner: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        toks![input, ".map((v: any) => ", inner, ")"]
+    }
+
+    /// Decoding a map in TypeScript.
+    fn map_decode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        _key: Tokens<'el, JavaScript<'el>>,
+        value: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        t.append("((data: any) => {");
+        t.append(" const o: any = {};");
+        t.append(" for (const k in data) {");
+        t.append(toks![" o[k] = ", value, ";"]);
+        t.append(" }");
+        t.append(" return o;");
+        t.append(toks![" })(", input, ")"]);
+
+        t
+    }
+
+    fn assign_type_var(&self, data: &'el str, type_var: &'el str) -> Tokens<'el, JavaScript<'el>> {
+        toks![
+            "const ",
+            type_var,
+            " = ",
+            data,
+            "[",
+            self.type_var.clone(),
+            "];",
+        ]
+    }
+
+    fn check_type_var(
+        &self,
+        data: &'el str,
+        type_var: &'el str,
+        name: &'el Loc<String>,
+        type_name: Tokens<'el, Self::Custom>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut body = Tokens::new();
+        let cond = toks![type_var, " === ", name.as_str().quoted()];
+        body.push(ts![if cond, ts![return type_name, ".decode(", data, ")"]]);
+        body
+    }
+
+    fn raise_bad_type(&self, type_var: &'el str) -> Tokens<'el, JavaScript<'el>> {
+        ts![throw "bad type: ".quoted(), " + ", type_var]
+    }
+
+    fn new_decode_method(
+        &self,
+        data: &'el str,
+        body: Tokens<'el, Self::Custom>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut decode = Tokens::new();
+        decode.push(toks!["export function decode(", data, ": any) {"]);
+        decode.nested(body);
+        decode.push("}");
+        decode
+    }
+}
+
+impl<'el> DynamicEncode<'el> for TsBackend {
+    fn name_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        toks![name, ".encode(", input, ")"]
+    }
+
+    /// Encoding a type with type arguments.
+    ///
+    /// A function encoding each argument is passed along to the encode method of the type.
+    fn name_arguments_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        let mut out = Tokens::new();
+        out.append(input);
+
+        for argument in arguments {
+            let encode = self.dynamic_encode(argument, toks!["v"])?;
+            out.append(toks!["(v: any) => ", encode]);
+        }
+
+        Ok(toks![self.convert_type(name)?, ".encode(", out.join(", "), ")"])
+    }
+
+    fn generic_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, JavaScript<'el>>> {
+        Ok(toks![format!("encode{}", name), "(", input, ")"])
+    }
+
+    fn array_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        inner: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        toks![input, ".map((v: any) => ", inner, ")"]
+    }
+
+    fn map_encode(
+        &self,
+        input: Tokens<'el, JavaScript<'el>>,
+        _: Tokens<'el, JavaScript<'el>>,
+        value: Tokens<'el, JavaScript<'el>>,
+    ) -> Tokens<'el, JavaScript<'el>> {
+        let mut t = Tokens::new();
+
+        t.append("((data: any) => {");
+        t.append(" const o: any = {};");
+        t.append(" for (const k in data) {");
+        t.append(toks![" o[k] = ", value, ";"]);
+        t.append(" }");
+        t.append(" return o;");
+        t.append(toks![" })(", input, ")"]);
+
+        t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{RpDecl, RpPackage, RpVersionedPackage};
+
+    fn backend(content: &str) -> TsBackend {
+        let mut env = Environment::empty();
+        let package = RpVersionedPackage::new(RpPackage::parse("io.test"), None);
+        env.import_str(content, package).unwrap();
+
+        let listeners: Vec<Box<Listeners>> = vec![];
+        TsBackend::new(env, TsOptions::new(), Box::new(listeners), None)
+    }
+
+    fn render<'el>(toks: Tokens<'el, JavaScript<'el>>) -> String {
+        toks.to_file().unwrap().trim().to_string()
+    }
+
+    /// Process all declarations and render the resulting file.
+    fn process(backend: &TsBackend) -> String {
+        let mut out = TsFileSpec::default();
+
+        for (_, file) in backend.env.for_each_file() {
+            for decl in file.for_each_decl() {
+                match **decl {
+                    RpDecl::Type(ref body) => backend.process_type(&mut out, body).unwrap(),
+                    RpDecl::Tuple(ref body) => backend.process_tuple(&mut out, body).unwrap(),
+                    RpDecl::Enum(ref body) => backend.process_enum(&mut out, body).unwrap(),
+                    _ => {}
+                }
+            }
+        }
+
+        render(out.0.join_line_spacing())
+    }
+
+    #[test]
+    fn test_is_defined() {
+        assert_eq!("a !== null && a !== undefined", render(is_defined("a")));
+        assert_eq!("a === null || a === undefined", render(is_not_defined("a")));
+    }
+
+    #[test]
+    fn test_into_ts_type() {
+        use self::RpType::*;
+
+        let backend = backend("");
+
+        let unsigned = Unsigned { size: 32 };
+        let array = Array { inner: Box::new(Double) };
+        let number_map = Map {
+            key: Box::new(Signed { size: 64 }),
+            value: Box::new(Boolean),
+        };
+        let string_map = Map {
+            key: Box::new(String),
+            value: Box::new(Any),
+        };
+
+        let ty = |ty: &RpType| render(backend.into_ts_type(ty).unwrap());
+
+        assert_eq!("string", ty(&String));
+        assert_eq!("string", ty(&DateTime));
+        assert_eq!("number", ty(&unsigned));
+        assert_eq!("Array<number>", ty(&array));
+        assert_eq!("{[key: number]: boolean}", ty(&number_map));
+        assert_eq!("{[key: string]: any}", ty(&string_map));
+    }
+
+    #[test]
+    fn test_generics() {
+        let backend = backend("");
+        assert_eq!("", backend.generics(&[]));
+        assert_eq!("<T, U>", backend.generics(&["T".to_string(), "U".to_string()]));
+    }
+
+    #[test]
+    fn test_type_fields() {
+        let backend = backend("type Foo { a?: string; b: [u32]; c: Bar; } type Bar {}");
+        let out = process(&backend);
+
+        assert!(out.contains("export class Foo {"));
+        assert!(out.contains("a: string | null;"));
+        assert!(out.contains("b: Array<number>;"));
+        assert!(out.contains("c: Bar;"));
+        assert!(out.contains("constructor(a: string | null, b: Array<number>, c: Bar) {"));
+        assert!(out.contains("static decode(data: any): Foo {"));
+    }
+
+    #[test]
+    fn test_tuple() {
+        let backend = backend("tuple Pair { a: string; b: u32; }");
+        let out = process(&backend);
+
+        assert!(out.contains("export class Pair {"));
+        assert!(out.contains("constructor(a: string, b: number) {"));
+    }
+
+    #[test]
+    fn test_enum() {
+        let backend = backend("enum Kind as string { A as \"a\"; B as \"b\"; }");
+        let out = process(&backend);
+
+        assert!(out.contains("export type Kind = "));
+        assert!(out.contains("export namespace Kind {"));
+        assert!(out.contains("export const A: Kind = "));
+        assert!(out.contains("export const values: Kind[] = [A, B];"));
+        assert!(out.contains("export function decode(data: any): Kind {"));
+        assert!(out.contains("export function encode(value: Kind): any {"));
+    }
+}
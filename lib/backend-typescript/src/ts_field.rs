@@ -0,0 +1,10 @@
+use core::{RpModifier, RpType};
+use std::rc::Rc;
+
+#[derive(Clone)]
+pub struct TsField<'a> {
+    pub modifier: &'a RpModifier,
+    pub ty: &'a RpType,
+    pub name: &'a str,
+    pub ident: Rc<String>,
+}
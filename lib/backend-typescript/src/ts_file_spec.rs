@@ -0,0 +1,21 @@
+//! File spec collecting results from backends
+
+use backend::IntoBytes;
+use backend::errors::*;
+use genco::{JavaScript, Tokens};
+use ts_compiler::TsCompiler;
+
+pub struct TsFileSpec<'el>(pub Tokens<'el, JavaScript<'el>>);
+
+impl<'el> Default for TsFileSpec<'el> {
+    fn default() -> Self {
+        TsFileSpec(Tokens::new())
+    }
+}
+
+impl<'el> IntoBytes<TsCompiler<'el>> for TsFileSpec<'el> {
+    fn into_bytes(self, _: &TsCompiler<'el>) -> Result<Vec<u8>> {
+        let out = self.0.join_line_spacing().to_file()?;
+        Ok(out.into_bytes())
+    }
+}
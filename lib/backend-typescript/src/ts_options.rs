@@ -0,0 +1,9 @@
+pub struct TsOptions {
+    pub build_constructor: bool,
+}
+
+impl TsOptions {
+    pub fn new() -> TsOptions {
+        TsOptions { build_constructor: true }
+    }
+}
@@ -1,8 +1,8 @@
 //! # Converter for core data structures into processor-specific ones.
 
-use core::{RpName, RpVariant};
+use core::{RpName, RpOrdinalValue, RpVariant};
 use errors::*;
-use genco::{Custom, Quoted, Tokens};
+use genco::{Custom, Element, Quoted, Tokens};
 use std::rc::Rc;
 
 pub trait Converter<'el> {
@@ -16,6 +16,13 @@ pub trait Converter<'el> {
 
     /// Build an ordinal value.
     fn ordinal<'a>(&self, variant: &'a RpVariant) -> Result<Tokens<'el, Self::Custom>> {
-        Ok(Rc::new(variant.ordinal().to_string()).quoted().into())
+        use self::RpOrdinalValue::*;
+
+        let out: Element<'el, Self::Custom> = match variant.ordinal() {
+            String(string) => Rc::new(string.to_string()).quoted(),
+            Number(number) => number.to_string().into(),
+        };
+
+        Ok(out.into())
     }
 }
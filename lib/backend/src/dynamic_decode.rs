@@ -2,7 +2,7 @@
 
 use base_decode::BaseDecode;
 use converter::Converter;
-use core::{Loc, RpInterfaceBody, RpType};
+use core::{Loc, RpInterfaceBody, RpName, RpType};
 use dynamic_converter::DynamicConverter;
 use errors::*;
 use genco::Tokens;
@@ -36,6 +36,21 @@ where
         name: Tokens<'el, Self::Custom>,
     ) -> Tokens<'el, Self::Custom>;
 
+    /// Decode a named type which is given type arguments.
+    fn name_arguments_decode(
+        &self,
+        input: Tokens<'el, Self::Custom>,
+        name: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, Self::Custom>>;
+
+    /// Decode a value whose type is the type parameter `name`.
+    fn generic_decode(
+        &self,
+        input: Tokens<'el, Self::Custom>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, Self::Custom>>;
+
     fn array_decode(
         &self,
         input: Tokens<'el, Self::Custom>,
@@ -74,10 +89,18 @@ where
             Boolean => input,
             Bytes => input,
             Any => input,
-            Name { ref name } => {
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                if !arguments.is_empty() {
+                    return self.name_arguments_decode(input, name, arguments);
+                }
+
                 let name = self.convert_type(name)?;
                 self.name_decode(input, name)
             }
+            Generic { ref name } => self.generic_decode(input, name)?,
             Array { ref inner } => {
                 let inner_var = self.array_inner_var();
                 let inner = self.dynamic_decode(inner, inner_var)?;
@@ -1,7 +1,7 @@
 //! # Helper trait for building a dynamic-language encode method
 
 use base_encode::BaseEncode;
-use core::RpType;
+use core::{RpName, RpType};
 use dynamic_converter::DynamicConverter;
 use errors::*;
 use genco::Tokens;
@@ -16,6 +16,21 @@ where
         name: Tokens<'el, Self::Custom>,
     ) -> Tokens<'el, Self::Custom>;
 
+    /// Encode a named type which is given type arguments.
+    fn name_arguments_encode(
+        &self,
+        input: Tokens<'el, Self::Custom>,
+        name: &'el RpName,
+        arguments: &'el [RpType],
+    ) -> Result<Tokens<'el, Self::Custom>>;
+
+    /// Encode a value whose type is the type parameter `name`.
+    fn generic_encode(
+        &self,
+        input: Tokens<'el, Self::Custom>,
+        name: &'el str,
+    ) -> Result<Tokens<'el, Self::Custom>>;
+
     fn array_encode(
         &self,
         input: Tokens<'el, Self::Custom>,
@@ -53,10 +68,18 @@ where
             DateTime => self.datetime_encode(input),
             Any => input,
             Boolean => input,
-            Name { ref name } => {
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                if !arguments.is_empty() {
+                    return self.name_arguments_encode(input, name, arguments);
+                }
+
                 let name = self.convert_type(name)?;
                 self.name_encode(input, name)
             }
+            Generic { ref name } => self.generic_encode(input, name)?,
             Array { ref inner } => {
                 let v = self.array_inner_var();
                 let inner = self.dynamic_encode(inner, v)?;
@@ -2,15 +2,16 @@ use super::into_model::IntoModel;
 use super::naming::{FromNaming, Naming, SnakeCase};
 use super::scope::Scope;
 use ast::UseDecl;
-use core::{Loc, Object, Options, PathObject, RpDecl, RpFile, RpName, RpPackage, RpReg,
-           RpRequiredPackage, RpVersionedPackage, VersionReq, WithPos};
+use core::{BytesObject, Loc, Object, Options, PathObject, Pos, RpDecl, RpField, RpFile, RpName,
+           RpPackage, RpReg, RpRequiredPackage, RpType, RpVersionedPackage, VersionReq, WithPos};
 use errors::*;
 use linked_hash_map::LinkedHashMap;
 use parser;
-use repository::{Resolved, Resolver};
+use repository::{Resolved, Resolver, Resolvers};
 use std::collections::{BTreeMap, HashMap, LinkedList, btree_map};
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::vec;
 
 /// Iterate over all files in the environment.
@@ -69,6 +70,8 @@ pub struct Environment {
     types: LinkedHashMap<RpName, RpReg>,
     /// Files and associated declarations.
     files: BTreeMap<RpVersionedPackage, RpFile>,
+    /// Imported packages which have been deprecated or yanked, and why.
+    deprecations: Vec<(RpVersionedPackage, String)>,
 }
 
 /// Environment containing all loaded declarations.
@@ -80,9 +83,36 @@ impl Environment {
             visited: HashMap::new(),
             types: LinkedHashMap::new(),
             files: BTreeMap::new(),
+            deprecations: Vec::new(),
         }
     }
 
+    /// Build an environment which does not resolve any packages.
+    ///
+    /// Specifications have to be imported into it explicitly, like with `import_str`.
+    pub fn empty() -> Environment {
+        Environment::new(None, Box::new(Resolvers::new(vec![])))
+    }
+
+    /// Import a specification from a string.
+    pub fn import_str(
+        &mut self,
+        content: &str,
+        package: RpVersionedPackage,
+    ) -> Result<RpVersionedPackage> {
+        self.import_object(Self::str_object(content), Some(package))
+    }
+
+    /// Load a specification from a string into an `RpFile`, without registering its types.
+    pub fn load_str(&mut self, content: &str, package: &RpVersionedPackage) -> Result<RpFile> {
+        self.load_object(Self::str_object(content), package)
+    }
+
+    fn str_object(content: &str) -> Box<Object> {
+        let bytes = Arc::new(content.as_bytes().to_vec());
+        Box::new(BytesObject::new("<string>".to_string(), bytes))
+    }
+
     /// Lookup the declaration matching the given name.
     ///
     /// Returns the registered reference, if present.
@@ -102,8 +132,17 @@ impl Environment {
         path: P,
         package: Option<RpVersionedPackage>,
     ) -> Result<RpVersionedPackage> {
-        let object = PathObject::new(None, path);
+        self.import_object(PathObject::new(None, path), package)
+    }
 
+    /// Import an object into the environment.
+    ///
+    /// This is used for content which doesn't come from a resolver, like a file being edited.
+    pub fn import_object<O: Into<Box<Object>>>(
+        &mut self,
+        object: O,
+        package: Option<RpVersionedPackage>,
+    ) -> Result<RpVersionedPackage> {
         let package = package.unwrap_or_else(|| RpVersionedPackage::new(RpPackage::empty(), None));
         let required = RpRequiredPackage::new(package.package.clone(), VersionReq::any());
 
@@ -130,10 +169,19 @@ impl Environment {
         // find all matching objects from the resolver.
         let files = self.resolver.resolve(required)?;
 
-        if let Some(Resolved { version, object }) = files.into_iter().last() {
+        if let Some(Resolved {
+            version,
+            object,
+            deprecated,
+        }) = files.into_iter().last()
+        {
             debug!("loading: {}", object);
 
             let package = RpVersionedPackage::new(required.package.clone(), version);
+
+            if let Some(deprecated) = deprecated {
+                self.deprecations.push((package.clone(), deprecated));
+            }
             let file = self.load_object(object, &package)?;
 
             candidates.entry(package).or_insert_with(Vec::new).push(
@@ -157,8 +205,83 @@ impl Environment {
         Ok(result)
     }
 
+    /// Get all imported packages which have been deprecated or yanked, and why.
+    pub fn deprecations(&self) -> &[(RpVersionedPackage, String)] {
+        &self.deprecations
+    }
+
     /// Verify all declarations.
     pub fn verify(&mut self) -> Result<()> {
+        for reg in self.types.values() {
+            match *reg {
+                RpReg::Type(ref body) => self.verify_fields(body.fields.iter())?,
+                RpReg::Tuple(ref body) => self.verify_fields(body.fields.iter())?,
+                RpReg::Interface(ref body) => self.verify_fields(body.fields.iter())?,
+                RpReg::SubType(_, ref sub_type) => self.verify_fields(sub_type.fields.iter())?,
+                RpReg::Service(ref body) => {
+                    for endpoint in body.endpoints.values() {
+                        let channels = endpoint.request.iter().chain(endpoint.response.iter());
+
+                        for channel in channels {
+                            self.verify_type(channel.ty(), channel.pos())?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify the types of all the given fields.
+    fn verify_fields<'a, I>(&self, fields: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Loc<RpField>>,
+    {
+        for field in fields {
+            self.verify_type(&field.ty, field.pos())?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify that the given type references existing declarations with the correct number of
+    /// type arguments.
+    fn verify_type(&self, ty: &RpType, pos: &Pos) -> Result<()> {
+        match *ty {
+            RpType::Name {
+                ref name,
+                ref arguments,
+            } => {
+                let type_parameters = self.lookup(name).with_pos(pos)?.type_parameters();
+
+                if type_parameters.len() != arguments.len() {
+                    return Err(
+                        ErrorKind::Pos(
+                            format!(
+                                "`{}` expects {} type argument(s), but {} were given",
+                                name,
+                                type_parameters.len(),
+                                arguments.len()
+                            ),
+                            pos.into(),
+                        ).into(),
+                    );
+                }
+
+                for argument in arguments {
+                    self.verify_type(argument, pos)?;
+                }
+            }
+            RpType::Array { ref inner } => self.verify_type(inner, pos)?,
+            RpType::Map { ref key, ref value } => {
+                self.verify_type(key, pos)?;
+                self.verify_type(value, pos)?;
+            }
+            _ => {}
+        }
+
         Ok(())
     }
 
@@ -44,6 +44,10 @@ error_chain! {
             description("enum value conflict")
         }
 
+        EnumOrdinalConflict(pos: ErrorPos, other: ErrorPos) {
+            description("enum ordinal conflict")
+        }
+
         FieldConflict(message: String, source: ErrorPos, target: ErrorPos) {
             description("field conflict")
             display("{}", message)
@@ -30,7 +30,23 @@ impl IntoModel for Type {
             Boolean => RpType::Boolean,
             String => RpType::String,
             DateTime => RpType::DateTime,
-            Name { name } => RpType::Name { name: name.into_model(scope)? },
+            Name { name, arguments } => {
+                if let Some(parameter) = type_parameter(scope, &name) {
+                    if !arguments.is_empty() {
+                        return Err(
+                            format!("type parameter `{}` does not take arguments", parameter)
+                                .into(),
+                        );
+                    }
+
+                    RpType::Generic { name: parameter }
+                } else {
+                    RpType::Name {
+                        name: name.into_model(scope)?,
+                        arguments: arguments.into_model(scope)?,
+                    }
+                }
+            }
             Array { inner } => RpType::Array { inner: inner.into_model(scope)? },
             Map { key, value } => RpType::Map {
                 key: key.into_model(scope)?,
@@ -44,6 +60,39 @@ impl IntoModel for Type {
     }
 }
 
+/// Check if the given name refers to a type parameter in the current scope.
+fn type_parameter(scope: &Scope, name: &Name) -> Option<String> {
+    match *name {
+        Name::Absolute {
+            prefix: None,
+            ref parts,
+        } if parts.len() == 1 && scope.is_type_parameter(&parts[0]) => Some(parts[0].clone()),
+        _ => None,
+    }
+}
+
+/// Convert type parameters, making sure that they are unique.
+fn type_parameters<'input>(parameters: Vec<Loc<&'input str>>) -> Result<Vec<String>> {
+    let mut out: Vec<String> = Vec::new();
+
+    for parameter in parameters {
+        let (parameter, pos) = parameter.take_pair();
+
+        if out.iter().any(|p| p.as_str() == parameter) {
+            return Err(
+                ErrorKind::Pos(
+                    format!("type parameter `{}` already declared", parameter),
+                    pos.into(),
+                ).into(),
+            );
+        }
+
+        out.push(parameter.to_string());
+    }
+
+    Ok(out)
+}
+
 impl<'input> IntoModel for Decl<'input> {
     type Output = RpDecl;
 
@@ -70,18 +119,20 @@ impl<'input> IntoModel for EnumBody<'input> {
     fn into_model(self, scope: &Scope) -> Result<Self::Output> {
         let mut variants: Vec<Rc<Loc<RpVariant>>> = Vec::new();
 
-        let (fields, codes, _options, decls) = members_into_model(scope, self.members)?;
+        let (fields, codes, options, decls) = members_into_model(scope, self.members)?;
 
         if fields.len() > 0 {
             return Err("enums can't have fields".into());
         }
 
+        let example = options.find_one_string("example")?;
+
         let ty = self.ty.into_model(scope)?;
 
         let variant_type = if let Some(ty) = ty {
             ty.and_then(|ty| {
                 ty.as_enum_type().ok_or_else(
-                    || "expected string or absent".into(),
+                    || "expected string, integer, or absent".into(),
                 ) as Result<RpEnumType>
             })?
         } else {
@@ -105,6 +156,12 @@ impl<'input> IntoModel for EnumBody<'input> {
                 );
             }
 
+            if let Some(other) = variants.iter().find(|v| v.ordinal() == variant.ordinal()) {
+                return Err(
+                    ErrorKind::EnumOrdinalConflict((&pos).into(), other.pos().into()).into(),
+                );
+            }
+
             variants.push(Rc::new(Loc::new(variant, pos)));
         }
 
@@ -116,6 +173,7 @@ impl<'input> IntoModel for EnumBody<'input> {
             variant_type: variant_type,
             variants: variants,
             codes: codes,
+            example: example,
         })
     }
 }
@@ -136,6 +194,10 @@ impl<'input, 'a> IntoModel for (EnumVariant<'input>, &'a RpEnumType) {
 
             argument.and_then(|value| value.to_ordinal())?
         } else {
+            if ty.is_numeric() {
+                return Err(format!("expected an ordinal of type {}", ty).into());
+            }
+
             RpEnumOrdinal::Generated
         };
 
@@ -144,6 +206,7 @@ impl<'input, 'a> IntoModel for (EnumVariant<'input>, &'a RpEnumType) {
             local_name: variant.name.clone().map(str::to_string),
             comment: variant.comment.into_iter().map(ToOwned::to_owned).collect(),
             ordinal: ordinal,
+            options: variant.options.into_model(scope)?,
         })
     }
 }
@@ -158,16 +221,178 @@ impl<'input> IntoModel for Field<'input> {
             scope.field_naming().map(|n| n.convert(name))
         });
 
+        let ty = self.ty.into_model(scope)?;
+        let options = self.options.into_model(scope)?;
+        let constraints = field_constraints(&ty, &options)?;
+        let default = self.default.into_model(scope)?;
+
+        if let Some(ref default) = default {
+            if self.modifier != RpModifier::Optional {
+                return Err(
+                    ErrorKind::Pos(
+                        "default values are only permitted on optional fields".to_string(),
+                        default.pos().into(),
+                    ).into(),
+                );
+            }
+
+            check_default(&ty, default)?;
+        }
+
         Ok(RpField {
             modifier: self.modifier,
             name: self.name.to_string(),
             comment: self.comment.into_iter().map(ToOwned::to_owned).collect(),
-            ty: self.ty.into_model(scope)?,
+            ty: ty,
             field_as: field_as,
+            constraints: constraints,
+            default: default,
+            options: options,
         })
     }
 }
 
+/// Check that the default value of a field can be assigned to its type.
+fn check_default(ty: &RpType, default: &Loc<RpValue>) -> Result<()> {
+    use self::RpType::*;
+
+    let assignable = match (ty, default.value()) {
+        (&Boolean, &RpValue::Boolean(_)) => true,
+        (&String, &RpValue::String(_)) => true,
+        (&Float, &RpValue::Number(_)) => true,
+        (&Double, &RpValue::Number(_)) => true,
+        (&Signed { size }, &RpValue::Number(ref number)) => {
+            if !number.fits_signed(size) {
+                let message = format!("default value {} does not fit in type `{}`", number, ty);
+                return Err(ErrorKind::Pos(message, default.pos().into()).into());
+            }
+
+            true
+        }
+        (&Unsigned { size }, &RpValue::Number(ref number)) => {
+            if !number.fits_unsigned(size) {
+                let message = format!("default value {} does not fit in type `{}`", number, ty);
+                return Err(ErrorKind::Pos(message, default.pos().into()).into());
+            }
+
+            true
+        }
+        (&Boolean, _) | (&String, _) | (&Float, _) | (&Double, _) | (&Signed { .. }, _) |
+        (&Unsigned { .. }, _) => false,
+        _ => {
+            let message = format!("default values are not supported for type `{}`", ty);
+            return Err(ErrorKind::Pos(message, default.pos().into()).into());
+        }
+    };
+
+    if !assignable {
+        let message = format!("default value {} is not assignable to type `{}`", **default, ty);
+        return Err(ErrorKind::Pos(message, default.pos().into()).into());
+    }
+
+    Ok(())
+}
+
+/// Build the constraints of a field from its options, checking that they apply to its type.
+///
+/// Other options are left for the backends to interpret.
+fn field_constraints<O>(ty: &RpType, options: &O) -> Result<RpConstraints>
+where
+    O: Options,
+{
+    use self::RpType::*;
+
+    let numeric = match *ty {
+        Signed { .. } | Unsigned { .. } | Float | Double => true,
+        _ => false,
+    };
+
+    let sized = match *ty {
+        String | Array { .. } | Map { .. } => true,
+        _ => false,
+    };
+
+    let not_applicable = |name: &str, pos: &Pos| -> Error {
+        ErrorKind::Pos(
+            format!("option `{}` does not apply to fields of type `{}`", name, ty),
+            pos.into(),
+        ).into()
+    };
+
+    let mut constraints = RpConstraints::default();
+
+    for name in &["min", "max"] {
+        if let Some(number) = options.find_one_u32(name)? {
+            let (number, pos) = number.take_pair();
+
+            if !numeric {
+                return Err(not_applicable(name, &pos));
+            }
+
+            if *name == "min" {
+                constraints.min = Some(number);
+            } else {
+                constraints.max = Some(number);
+            }
+        }
+    }
+
+    for name in &["min_length", "max_length"] {
+        if let Some(number) = options.find_one_u32(name)? {
+            let (number, pos) = number.take_pair();
+
+            if !sized {
+                return Err(not_applicable(name, &pos));
+            }
+
+            let length = if number.fits_unsigned(64) {
+                number.to_usize()
+            } else {
+                None
+            };
+
+            let length = length.ok_or_else(|| {
+                Error::from(ErrorKind::Pos(
+                    format!("option `{}` must be a non-negative integer", name),
+                    (&pos).into(),
+                ))
+            })?;
+
+            if *name == "min_length" {
+                constraints.min_length = Some(length);
+            } else {
+                constraints.max_length = Some(length);
+            }
+        }
+    }
+
+    if let Some(pattern) = options.find_one_string("pattern")? {
+        let (pattern, pos) = pattern.take_pair();
+
+        if *ty != String {
+            return Err(not_applicable("pattern", &pos));
+        }
+
+        constraints.pattern = Some(pattern);
+    }
+
+    if let (Some(min), Some(max)) = (constraints.min.as_ref(), constraints.max.as_ref()) {
+        if min.to_f64() > max.to_f64() {
+            return Err(format!("`min` ({}) is greater than `max` ({})", min, max).into());
+        }
+    }
+
+    if let (Some(min), Some(max)) = (constraints.min_length, constraints.max_length) {
+        if min > max {
+            return Err(
+                format!("`min_length` ({}) is greater than `max_length` ({})", min, max).into(),
+            );
+        }
+    }
+
+    Ok(constraints)
+}
+
 impl<'input> IntoModel for File<'input> {
     type Output = RpFile;
 
@@ -194,7 +419,12 @@ impl<'input> IntoModel for InterfaceBody<'input> {
     fn into_model(self, scope: &Scope) -> Result<Self::Output> {
         use std::collections::btree_map::Entry::*;
 
-        let (fields, codes, _options, decls) = members_into_model(scope, self.members)?;
+        let type_parameters = type_parameters(self.type_parameters)?;
+        let scope = &scope.with_type_parameters(type_parameters.clone());
+
+        let (fields, codes, options, decls) = members_into_model(scope, self.members)?;
+
+        let example = options.find_one_string("example")?;
 
         let mut sub_types: BTreeMap<String, Rc<Loc<RpSubType>>> = BTreeMap::new();
 
@@ -223,9 +453,11 @@ impl<'input> IntoModel for InterfaceBody<'input> {
             local_name: self.name.to_string(),
             comment: self.comment.into_iter().map(ToOwned::to_owned).collect(),
             decls: decls,
+            type_parameters: type_parameters,
             fields: fields,
             codes: codes,
             sub_types: sub_types,
+            example: example,
         })
     }
 }
@@ -520,6 +752,7 @@ impl<'input> IntoModel for SubType<'input> {
         }
 
         let names = all_names(self.alias, &self.name, scope)?;
+        let example = options.find_one_string("example")?;
         let comment = self.comment.into_iter().map(ToOwned::to_owned).collect();
 
         return Ok(RpSubType {
@@ -530,6 +763,8 @@ impl<'input> IntoModel for SubType<'input> {
             fields: fields,
             codes: codes,
             names: names,
+            example: example,
+            options: options,
         });
 
         /// Extract all names provided.
@@ -584,15 +819,22 @@ impl<'input> IntoModel for TupleBody<'input> {
     type Output = RpTupleBody;
 
     fn into_model(self, scope: &Scope) -> Result<Self::Output> {
-        let (fields, codes, _options, decls) = members_into_model(scope, self.members)?;
+        let type_parameters = type_parameters(self.type_parameters)?;
+        let scope = &scope.with_type_parameters(type_parameters.clone());
+
+        let (fields, codes, options, decls) = members_into_model(scope, self.members)?;
+
+        let example = options.find_one_string("example")?;
 
         Ok(RpTupleBody {
             name: scope.as_name(),
             local_name: self.name.to_string(),
             comment: self.comment.into_iter().map(ToOwned::to_owned).collect(),
             decls: decls,
+            type_parameters: type_parameters,
             fields: fields,
             codes: codes,
+            example: example,
         })
     }
 }
@@ -601,6 +843,9 @@ impl<'input> IntoModel for TypeBody<'input> {
     type Output = RpTypeBody;
 
     fn into_model(self, scope: &Scope) -> Result<Self::Output> {
+        let type_parameters = type_parameters(self.type_parameters)?;
+        let scope = &scope.with_type_parameters(type_parameters.clone());
+
         let (fields, codes, options, decls) = members_into_model(scope, self.members)?;
 
         let reserved: HashSet<Loc<String>> = options
@@ -608,14 +853,18 @@ impl<'input> IntoModel for TypeBody<'input> {
             .into_iter()
             .collect();
 
+        let example = options.find_one_string("example")?;
+
         Ok(RpTypeBody {
             name: scope.as_name(),
             local_name: self.name.to_string(),
             comment: self.comment.into_iter().map(ToOwned::to_owned).collect(),
             decls: decls,
+            type_parameters: type_parameters,
             fields: fields,
             codes: codes,
             reserved: reserved,
+            example: example,
         })
     }
 }
@@ -20,6 +20,8 @@ enum Inner {
         root: Rc<Root>,
         name: String,
         parent: Rc<Inner>,
+        /// Type parameters declared by this scope.
+        type_parameters: Vec<String>,
     },
 }
 
@@ -71,10 +73,44 @@ impl Scope {
                 root: root,
                 name: name.as_ref().to_owned(),
                 parent: self.inner.clone(),
+                type_parameters: Vec::new(),
             }),
         }
     }
 
+    /// Build a scope which declares the given type parameters.
+    ///
+    /// Type parameters are only visible in the scope that declares them, not in nested
+    /// declarations.
+    pub fn with_type_parameters(&self, type_parameters: Vec<String>) -> Scope {
+        match *self.inner {
+            Inner::Root { .. } => Scope { inner: self.inner.clone() },
+            Inner::Child {
+                ref root,
+                ref name,
+                ref parent,
+                ..
+            } => Scope {
+                inner: Rc::new(Inner::Child {
+                    root: root.clone(),
+                    name: name.clone(),
+                    parent: parent.clone(),
+                    type_parameters: type_parameters,
+                }),
+            },
+        }
+    }
+
+    /// Check if the given name is a type parameter of the current scope.
+    pub fn is_type_parameter(&self, name: &str) -> bool {
+        match *self.inner {
+            Inner::Root { .. } => false,
+            Inner::Child { ref type_parameters, .. } => {
+                type_parameters.iter().any(|p| p.as_str() == name)
+            }
+        }
+    }
+
     pub fn lookup_prefix(&self, prefix: &String) -> Option<&RpVersionedPackage> {
         match *self.inner {
             Inner::Root { ref root, .. } |
@@ -164,4 +200,18 @@ mod tests {
 
         assert_eq!(vec!["bar".to_owned(), "foo".to_owned()], parts);
     }
+
+    #[test]
+    pub fn test_type_parameters() {
+        let package = RpVersionedPackage::new(RpPackage::empty(), None);
+        let prefixes = HashMap::new();
+        let s = Scope::new(None, package, prefixes, None, None);
+
+        let s2 = s.child("Page").with_type_parameters(vec!["T".to_owned()]);
+        let s3 = s2.child("Inner");
+
+        assert!(s2.is_type_parameter("T"));
+        assert!(!s3.is_type_parameter("T"));
+        assert_eq!(vec!["Page".to_owned()], s2.as_name().parts);
+    }
 }
@@ -24,6 +24,7 @@ mod option_entry;
 mod options;
 mod pos;
 mod rp_channel;
+mod rp_constraints;
 mod rp_decl;
 mod rp_endpoint;
 mod rp_enum_body;
@@ -63,10 +64,11 @@ pub use self::options::Options;
 pub use self::pos::Pos;
 pub use self::rp_channel::RpChannel;
 pub use self::rp_code::RpCode;
+pub use self::rp_constraints::RpConstraints;
 pub use self::rp_decl::RpDecl;
 pub use self::rp_endpoint::RpEndpoint;
 pub use self::rp_enum_body::RpEnumBody;
-pub use self::rp_enum_ordinal::RpEnumOrdinal;
+pub use self::rp_enum_ordinal::{RpEnumOrdinal, RpOrdinalValue};
 pub use self::rp_enum_type::RpEnumType;
 pub use self::rp_field::RpField;
 pub use self::rp_file::RpFile;
@@ -0,0 +1,24 @@
+//! Constraints on the value of a field.
+
+use super::RpNumber;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RpConstraints {
+    /// Inclusive lower bound of a number.
+    pub min: Option<RpNumber>,
+    /// Inclusive upper bound of a number.
+    pub max: Option<RpNumber>,
+    /// Minimum length of a string, array, or map.
+    pub min_length: Option<usize>,
+    /// Maximum length of a string, array, or map.
+    pub max_length: Option<usize>,
+    /// Regular expression that a string must contain a match for.
+    pub pattern: Option<String>,
+}
+
+impl RpConstraints {
+    pub fn is_empty(&self) -> bool {
+        self.min.is_none() && self.max.is_none() && self.min_length.is_none() &&
+            self.max_length.is_none() && self.pattern.is_none()
+    }
+}
@@ -8,4 +8,6 @@ decl_body!(pub struct RpEnumBody {
     pub variant_type: RpEnumType,
     pub variants: Vec<Rc<Loc<RpVariant>>>,
     pub codes: Vec<Loc<RpCode>>,
+    /// Example payload, as provided by the `example` option.
+    pub example: Option<Loc<String>>,
 });
@@ -1,9 +1,32 @@
 //! Data Models for the final model stage stage.
 
+use super::RpNumber;
+use std::fmt;
+
 #[derive(Debug, Clone, Serialize)]
 pub enum RpEnumOrdinal {
     /// Value is specified expliticly.
     String(String),
+    /// Value is a number, specified explicitly.
+    Number(RpNumber),
     /// Value is automatically derived from the name of the variant.
     Generated,
 }
+
+/// The value of an ordinal, as it appears when serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpOrdinalValue<'a> {
+    String(&'a str),
+    Number(&'a RpNumber),
+}
+
+impl<'a> fmt::Display for RpOrdinalValue<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::RpOrdinalValue::*;
+
+        match *self {
+            String(string) => write!(f, "\"{}\"", string),
+            Number(number) => write!(f, "{}", number),
+        }
+    }
+}
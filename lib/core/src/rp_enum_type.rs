@@ -1,11 +1,13 @@
 //! Model for enum types
 
-use super::{RpField, RpModifier, RpType, RpValue};
+use super::{RpConstraints, RpField, RpModifier, RpType, RpValue};
 use std::fmt;
 
 #[derive(Debug, Clone, Serialize)]
 pub enum RpEnumType {
     String,
+    Signed { size: usize },
+    Unsigned { size: usize },
     Generated,
 }
 
@@ -15,6 +17,18 @@ impl RpEnumType {
 
         match (self, value) {
             (&String, &RpValue::String(_)) => true,
+            (&Signed { size }, &RpValue::Number(ref number)) => number.fits_signed(size),
+            (&Unsigned { size }, &RpValue::Number(ref number)) => number.fits_unsigned(size),
+            _ => false,
+        }
+    }
+
+    /// Check if the ordinals of the enum are numeric.
+    pub fn is_numeric(&self) -> bool {
+        use self::RpEnumType::*;
+
+        match *self {
+            Signed { .. } | Unsigned { .. } => true,
             _ => false,
         }
     }
@@ -24,6 +38,8 @@ impl RpEnumType {
 
         match *self {
             String => RpType::String,
+            Signed { size } => RpType::Signed { size: size },
+            Unsigned { size } => RpType::Unsigned { size: size },
             Generated => RpType::String,
         }
     }
@@ -35,6 +51,9 @@ impl RpEnumType {
             comment: vec![],
             ty: self.as_type(),
             field_as: None,
+            constraints: RpConstraints::default(),
+            default: None,
+            options: vec![],
         }
     }
 }
@@ -45,6 +64,8 @@ impl fmt::Display for RpEnumType {
 
         match *self {
             String => write!(f, "string"),
+            Signed { ref size } => write!(f, "i{}", size),
+            Unsigned { ref size } => write!(f, "u{}", size),
             Generated => write!(f, "generated"),
         }
     }
@@ -1,6 +1,6 @@
 //! Data Models for fields
 
-use super::{RpModifier, RpType};
+use super::{Loc, RpConstraints, RpModifier, RpOptionDecl, RpType, RpValue};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RpField {
@@ -11,6 +11,12 @@ pub struct RpField {
     pub ty: RpType,
     /// Alias of field in JSON.
     pub field_as: Option<String>,
+    /// Constraints on the value of the field.
+    pub constraints: RpConstraints,
+    /// Value to use if an optional field is absent.
+    pub default: Option<Loc<RpValue>>,
+    /// Options of the field, including the ones which are only interpreted by backends.
+    pub options: Vec<Loc<RpOptionDecl>>,
 }
 
 impl RpField {
@@ -6,9 +6,13 @@ use std::rc::Rc;
 use std::slice;
 
 decl_body!(pub struct RpInterfaceBody {
+    /// Names of the type parameters of the declaration.
+    pub type_parameters: Vec<String>,
     pub fields: Vec<Loc<RpField>>,
     pub codes: Vec<Loc<RpCode>>,
     pub sub_types: BTreeMap<String, Rc<Loc<RpSubType>>>,
+    /// Example payload, as provided by the `example` option.
+    pub example: Option<Loc<String>>,
 });
 
 /// Iterator over fields.
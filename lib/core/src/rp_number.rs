@@ -32,6 +32,35 @@ impl RpNumber {
         self.digits.checked_div(&m).and_then(|r| r.to_u64())
     }
 
+    pub fn to_i64(&self) -> Option<i64> {
+        let m = self.multiple();
+
+        self.digits.checked_div(&m).and_then(|r| r.to_i64())
+    }
+
+    /// Check if the number is an integer which fits in a signed integer of the given size.
+    pub fn fits_signed(&self, size: usize) -> bool {
+        if self.decimal != 0 || size == 0 || size > 64 {
+            return false;
+        }
+
+        let max = (BigInt::from(1) << (size - 1)) - BigInt::from(1);
+        let min = -(BigInt::from(1) << (size - 1));
+
+        self.digits >= min && self.digits <= max
+    }
+
+    /// Check if the number is an integer which fits in an unsigned integer of the given size.
+    pub fn fits_unsigned(&self, size: usize) -> bool {
+        if self.decimal != 0 || size == 0 || size > 64 {
+            return false;
+        }
+
+        let max = (BigInt::from(1) << size) - BigInt::from(1);
+
+        !self.digits.is_negative() && self.digits <= max
+    }
+
     pub fn to_u32(&self) -> Option<u32> {
         self.to_u64().map(|v| v as u32)
     }
@@ -147,6 +176,30 @@ mod test_numbers {
         assert_eq!(Some(-1043.21), n.to_f64());
     }
 
+    #[test]
+    fn test_fits() {
+        let n: RpNumber = (-1).into();
+        assert!(n.fits_signed(32));
+        assert!(!n.fits_unsigned(32));
+
+        let n = RpNumber {
+            digits: 4294967295u64.into(),
+            decimal: 0,
+        };
+
+        assert!(n.fits_unsigned(32));
+        assert!(!n.fits_signed(32));
+        assert!(n.fits_signed(64));
+
+        let n = RpNumber {
+            digits: 125.into(),
+            decimal: 2,
+        };
+
+        assert!(!n.fits_signed(64));
+        assert!(!n.fits_unsigned(64));
+    }
+
     #[test]
     fn test_display() {
         let n = RpNumber {
@@ -48,6 +48,19 @@ impl RpReg {
         }
     }
 
+    /// Get the type parameters of the registered declaration.
+    pub fn type_parameters(&self) -> &[String] {
+        use self::RpReg::*;
+
+        match *self {
+            Type(ref target) => &target.type_parameters,
+            Tuple(ref target) => &target.type_parameters,
+            Interface(ref target) => &target.type_parameters,
+            SubType(ref parent, _) => &parent.type_parameters,
+            _ => &[],
+        }
+    }
+
     pub fn fields<'a>(&'a self) -> Result<Box<Iterator<Item = &Loc<RpField>> + 'a>> {
         use self::RpReg::*;
 
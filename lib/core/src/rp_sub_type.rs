@@ -1,6 +1,6 @@
 //! Model for sub-types
 
-use super::{Loc, RpCode, RpDecl, RpField, RpName};
+use super::{Loc, RpCode, RpDecl, RpField, RpName, RpOptionDecl};
 use std::rc::Rc;
 
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +13,10 @@ pub struct RpSubType {
     pub fields: Vec<Loc<RpField>>,
     pub codes: Vec<Loc<RpCode>>,
     pub names: Vec<Loc<String>>,
+    /// Example payload, as provided by the `example` option.
+    pub example: Option<Loc<String>>,
+    /// Options of the sub-type, including the ones which are only interpreted by backends.
+    pub options: Vec<Loc<RpOptionDecl>>,
 }
 
 impl RpSubType {
@@ -4,8 +4,12 @@ use super::{Loc, RpCode, RpField};
 use std::slice;
 
 decl_body!(pub struct RpTupleBody {
+    /// Names of the type parameters of the declaration.
+    pub type_parameters: Vec<String>,
     pub fields: Vec<Loc<RpField>>,
     pub codes: Vec<Loc<RpCode>>,
+    /// Example payload, as provided by the `example` option.
+    pub example: Option<Loc<String>>,
 });
 
 /// Iterator over fields.
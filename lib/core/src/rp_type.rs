@@ -1,6 +1,7 @@
 //! Type of a model.
 
 use super::{RpEnumType, RpName};
+use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -16,7 +17,13 @@ pub enum RpType {
     DateTime,
     Bytes,
     Any,
-    Name { name: RpName },
+    Name {
+        name: RpName,
+        /// Arguments to the type parameters of the named type.
+        arguments: Vec<RpType>,
+    },
+    /// Reference to a type parameter of the enclosing declaration.
+    Generic { name: String },
     Array { inner: Box<RpType> },
     Map {
         key: Box<RpType>,
@@ -31,10 +38,34 @@ impl RpType {
 
         match *self {
             String => Some(RpEnumType::String),
+            Signed { size } => Some(RpEnumType::Signed { size: size }),
+            Unsigned { size } => Some(RpEnumType::Unsigned { size: size }),
             _ => None,
         }
     }
 
+    /// Substitute all type parameters with the given arguments.
+    pub fn substitute(&self, arguments: &HashMap<String, RpType>) -> RpType {
+        use self::RpType::*;
+
+        match *self {
+            Generic { ref name } => arguments.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Name {
+                ref name,
+                arguments: ref inner,
+            } => Name {
+                name: name.clone(),
+                arguments: inner.iter().map(|a| a.substitute(arguments)).collect(),
+            },
+            Array { ref inner } => Array { inner: Box::new(inner.substitute(arguments)) },
+            Map { ref key, ref value } => Map {
+                key: Box::new(key.substitute(arguments)),
+                value: Box::new(value.substitute(arguments)),
+            },
+            ref ty => ty.clone(),
+        }
+    }
+
     /// Localize type.
     ///
     /// Strips version of any type which is _not_ imported.
@@ -55,7 +86,13 @@ impl RpType {
         use self::RpType::*;
 
         match self {
-            Name { name } => Name { name: f(name) },
+            Name { name, arguments } => Name {
+                name: f(name),
+                arguments: arguments
+                    .into_iter()
+                    .map(|a| a.with_name(f.clone()))
+                    .collect(),
+            },
             Array { inner } => Array { inner: Box::new(inner.with_name(f)) },
             Map { key, value } => Map {
                 key: Box::new(key.with_name(f.clone())),
@@ -78,7 +115,20 @@ impl fmt::Display for RpType {
             Boolean => write!(f, "boolean"),
             String => write!(f, "string"),
             DateTime => write!(f, "datetime"),
-            Name { ref name } => write!(f, "{}", name),
+            Name {
+                ref name,
+                ref arguments,
+            } => {
+                write!(f, "{}", name)?;
+
+                if !arguments.is_empty() {
+                    let arguments: Vec<_> = arguments.iter().map(ToString::to_string).collect();
+                    write!(f, "<{}>", arguments.join(", "))?;
+                }
+
+                Ok(())
+            }
+            Generic { ref name } => write!(f, "{}", name),
             Array { ref inner } => write!(f, "[{}]", inner),
             Map { ref key, ref value } => write!(f, "{{{}: {}}}", key, value),
             Any => write!(f, "any"),
@@ -5,10 +5,14 @@ use std::collections::HashSet;
 use std::slice;
 
 decl_body!(pub struct RpTypeBody {
+    /// Names of the type parameters of the declaration.
+    pub type_parameters: Vec<String>,
     pub fields: Vec<Loc<RpField>>,
     pub codes: Vec<Loc<RpCode>>,
     // Set of fields which are reserved for this type.
     pub reserved: HashSet<Loc<String>>,
+    /// Example payload, as provided by the `example` option.
+    pub example: Option<Loc<String>>,
 });
 
 /// Iterator over fields.
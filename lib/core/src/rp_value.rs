@@ -4,7 +4,7 @@ use super::{Loc, RpEnumOrdinal, RpNumber};
 use errors::*;
 use std::fmt;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(tag = "type", content = "value", rename_all = "snake_case")]
 pub enum RpValue {
     String(String),
@@ -37,6 +37,7 @@ impl RpValue {
     pub fn to_ordinal(self) -> Result<RpEnumOrdinal> {
         let ordinal = match self {
             RpValue::String(value) => RpEnumOrdinal::String(value),
+            RpValue::Number(value) => RpEnumOrdinal::Number(value),
             _ => return Err(ErrorKind::InvalidOrdinal.into()),
         };
 
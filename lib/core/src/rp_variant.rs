@@ -1,6 +1,6 @@
 //! Variant in an enum
 
-use super::{Loc, RpEnumOrdinal, RpName};
+use super::{Loc, RpEnumOrdinal, RpName, RpOptionDecl, RpOrdinalValue};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RpVariant {
@@ -8,15 +8,18 @@ pub struct RpVariant {
     pub local_name: Loc<String>,
     pub comment: Vec<String>,
     pub ordinal: RpEnumOrdinal,
+    /// Options of the variant, which are only interpreted by backends.
+    pub options: Vec<Loc<RpOptionDecl>>,
 }
 
 impl RpVariant {
-    pub fn ordinal(&self) -> &str {
+    pub fn ordinal(&self) -> RpOrdinalValue {
         use self::RpEnumOrdinal::*;
 
         match self.ordinal {
-            String(ref string) => string.as_str(),
-            Generated => self.local_name.as_str(),
+            String(ref string) => RpOrdinalValue::String(string.as_str()),
+            Number(ref number) => RpOrdinalValue::Number(number),
+            Generated => RpOrdinalValue::String(self.local_name.as_str()),
         }
     }
 }
@@ -83,9 +83,19 @@ pub struct Lexer<'input> {
     buffer: String,
     code_block: Option<(usize, usize)>,
     code_close: Option<(usize, usize)>,
+    comments: bool,
 }
 
 impl<'input> Lexer<'input> {
+    /// Retain regular comments as `Token::Comment` trivia instead of skipping them.
+    ///
+    /// The parser does not accept comments, so this is only useful for tools that need to
+    /// preserve them, like formatters.
+    pub fn with_comments(mut self) -> Lexer<'input> {
+        self.comments = true;
+        self
+    }
+
     /// Advance the source iterator.
     #[inline]
     fn step(&mut self) {
@@ -433,13 +443,15 @@ impl<'input> Lexer<'input> {
         }
     }
 
-    fn line_comment(&mut self) {
-        let start = self.step_n(2);
-        let _ = take_until!(self, start, '\n' | '\r');
+    fn line_comment(&mut self, start: usize) -> (usize, Token<'input>, usize) {
+        let content_start = self.step_n(2);
+        let (_, content) = take_until!(self, content_start, '\n' | '\r');
+        let end = content_start + content.len();
+        (start, Token::Comment(&self.source_str[start..end]), end)
     }
 
-    // block comments have no semantics and are completely ignored.
-    fn block_comment(&mut self) {
+    // block comments have no semantics and are ignored unless comments are retained.
+    fn block_comment(&mut self, start: usize) -> (usize, Token<'input>, usize) {
         self.step_n(2);
 
         while let Some((_, a, b)) = self.two() {
@@ -451,6 +463,9 @@ impl<'input> Lexer<'input> {
 
             self.step();
         }
+
+        let end = self.pos();
+        (start, Token::Comment(&self.source_str[start..end]), end)
     }
 
     fn normal_mode_next(&mut self) -> Option<Result<(usize, Token<'input>, usize)>> {
@@ -480,11 +495,21 @@ impl<'input> Lexer<'input> {
             if let Some((start, a, b)) = self.two() {
                 let token = match (a, b) {
                     ('/', '/') => {
-                        self.line_comment();
+                        let comment = self.line_comment(start);
+
+                        if self.comments {
+                            return Some(Ok(comment));
+                        }
+
                         continue;
                     }
                     ('/', '*') => {
-                        self.block_comment();
+                        let comment = self.block_comment(start);
+
+                        if self.comments {
+                            return Some(Ok(comment));
+                        }
+
                         continue;
                     }
                     ('{', '{') => {
@@ -512,6 +537,8 @@ impl<'input> Lexer<'input> {
                     ']' => Token::RightBracket,
                     '(' => Token::LeftParen,
                     ')' => Token::RightParen,
+                    '<' => Token::LeftAngle,
+                    '>' => Token::RightAngle,
                     ';' => Token::SemiColon,
                     ':' => Token::Colon,
                     ',' => Token::Comma,
@@ -566,6 +593,7 @@ pub fn lex(input: &str) -> Lexer {
         buffer: String::new(),
         code_block: None,
         code_close: None,
+        comments: false,
     }
 }
 
@@ -641,6 +669,19 @@ pub mod tests {
         assert_eq!(expected, tokenize("foo::Bar.Baz").unwrap());
     }
 
+    #[test]
+    pub fn test_type_arguments() {
+        let expected = vec![
+            (0, TypeIdentifier("Page"), 4),
+            (4, LeftAngle, 5),
+            (5, TypeIdentifier("Foo"), 8),
+            (8, RightAngle, 9),
+            (10, RightArrow, 12),
+        ];
+
+        assert_eq!(expected, tokenize("Page<Foo> ->").unwrap());
+    }
+
     #[test]
     pub fn test_strings() {
         let expected = vec![(0, String("foo\nbar".to_owned()), 10)];
@@ -680,6 +721,23 @@ pub mod tests {
         assert_eq!(vec![(16, Identifier("hello"), 21)], tokens.unwrap());
     }
 
+    #[test]
+    pub fn test_retained_comments() {
+        let tokens: Vec<_> = lex("// hello\nworld /* block */ ///doc")
+            .with_comments()
+            .collect::<Result<_>>()
+            .unwrap();
+
+        let expected = vec![
+            (0, Comment("// hello"), 8),
+            (9, Identifier("world"), 14),
+            (15, Comment("/* block */"), 26),
+            (27, DocComment(vec!["doc"]), 33),
+        ];
+
+        assert_eq!(expected, tokens);
+    }
+
     #[test]
     pub fn test_identifier_stripping() {
         let a = &tokenize("my_version").unwrap()[0].1;
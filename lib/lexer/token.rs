@@ -6,6 +6,8 @@ pub enum Token<'input> {
     TypeIdentifier(&'input str),
     PackageDocComment(Vec<&'input str>),
     DocComment(Vec<&'input str>),
+    /// A regular line or block comment, only emitted when comments are retained.
+    Comment(&'input str),
     Number(RpNumber),
     LeftCurly,
     RightCurly,
@@ -13,6 +15,8 @@ pub enum Token<'input> {
     RightBracket,
     LeftParen,
     RightParen,
+    LeftAngle,
+    RightAngle,
     SemiColon,
     Colon,
     Equal,
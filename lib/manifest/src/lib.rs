@@ -152,11 +152,14 @@ impl TryFromToml for RpRequiredPackage {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Language {
+    Go,
     Java,
     Js,
     Json,
+    Proto,
     Python,
     Rust,
+    TypeScript,
 }
 
 impl Language {
@@ -164,11 +167,14 @@ impl Language {
         use self::Language::*;
 
         let language = match input {
+            "go" => Go,
             "java" => Java,
             "js" => Js,
             "json" => Json,
+            "proto" => Proto,
             "python" => Python,
             "rust" => Rust,
+            "typescript" => TypeScript,
             _ => return None,
         };
 
@@ -277,6 +283,10 @@ where
 pub struct Doc {
     /// Syntax theme to use.
     pub syntax_theme: Option<String>,
+    /// Embed the source of specifications, and link to it from declarations.
+    ///
+    /// Defaults to `true`.
+    pub source: Option<bool>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -310,6 +320,10 @@ pub struct Repository {
     pub index: Option<String>,
     /// URL to use to objects storage.
     pub objects: Option<String>,
+    /// Only use deployments which are signed by one of the trusted keys.
+    pub require_signatures: bool,
+    /// Public keys that deployments are trusted to be signed with.
+    pub trusted_keys: Vec<String>,
 }
 
 /// The first part when the manifest was read.
@@ -392,6 +406,8 @@ pub fn load_repository(
     repository.no_repository = take_field(value, "no_repository")?;
     repository.index = take_field(value, "index")?;
     repository.objects = take_field(value, "objects")?;
+    repository.require_signatures = take_field(value, "require_signatures")?;
+    repository.trusted_keys = take_field(value, "trusted_keys")?;
     Ok(())
 }
 
@@ -655,5 +671,7 @@ mod tests {
             Some("file:///objects"),
             manifest.repository.objects.as_ref().map(String::as_str)
         );
+        assert_eq!(true, manifest.repository.require_signatures);
+        assert_eq!(vec!["my-key"], manifest.repository.trusted_keys);
     }
 }
@@ -193,6 +193,72 @@ mod tests {
         assert_eq!(1, file.decls.len());
     }
 
+    #[test]
+    fn test_numeric_enum() {
+        let file = parse_file("enum Foo as u32 { A as 1; B as 2; }");
+        assert_eq!(1, file.decls.len());
+
+        if let Decl::Enum(ref body) = *file.decls[0] {
+            assert_eq!(
+                Some(&Type::Unsigned { size: 32 }),
+                body.ty.as_ref().map(|t| t.value())
+            );
+
+            let arguments: Vec<_> = body.variants
+                .iter()
+                .map(|v| v.argument.as_ref().map(|a| a.value()))
+                .collect();
+
+            assert_eq!(
+                vec![Some(&Value::Number(1.into())), Some(&Value::Number(2.into()))],
+                arguments
+            );
+
+            return;
+        }
+
+        panic!("expected enum");
+    }
+
+    #[test]
+    fn test_variant_options() {
+        let file = parse_file("enum Foo as string { A; B { option proto_field = 1; } }");
+
+        if let Decl::Enum(ref body) = *file.decls[0] {
+            let options: Vec<_> = body.variants.iter().map(|v| v.options.len()).collect();
+            assert_eq!(vec![0, 1], options);
+            assert_eq!("proto_field", body.variants[1].options[0].name);
+            return;
+        }
+
+        panic!("expected enum");
+    }
+
+    #[test]
+    fn test_field_options() {
+        let member = parse_member("foo: string { option min_length = 1; }").take();
+
+        if let Member::Field(field) = member {
+            assert_eq!(1, field.options.len());
+            assert_eq!("min_length", field.options[0].name);
+            return;
+        }
+
+        panic!("field did not match");
+    }
+
+    #[test]
+    fn test_field_default() {
+        let member = parse_member("foo?: u32 = 42;").take();
+
+        if let Member::Field(field) = member {
+            assert_eq!(Some(&Value::Number(42.into())), field.default.as_ref().map(|d| d.value()));
+            return;
+        }
+
+        panic!("field did not match");
+    }
+
     #[test]
     fn test_strings() {
         assert_value_eq!(Value::String("foo\nbar".to_owned()), "\"foo\\nbar\"");
@@ -243,7 +309,41 @@ mod tests {
         };
 
         assert_type_spec_eq!(Type::String, "string");
-        assert_type_spec_eq!(Type::Name { name: c }, "Hello::World");
+        assert_type_spec_eq!(
+            Type::Name {
+                name: c,
+                arguments: vec![],
+            },
+            "Hello::World"
+        );
+    }
+
+    #[test]
+    fn test_type_arguments() {
+        let page = Name::Absolute {
+            prefix: None,
+            parts: vec!["Page".to_owned()],
+        };
+
+        let expected = Type::Name {
+            name: page,
+            arguments: vec![Type::Array { inner: Box::new(Type::String) }],
+        };
+
+        assert_type_spec_eq!(expected, "Page<[string]>");
+    }
+
+    #[test]
+    fn test_type_parameters() {
+        let file = parse_file("type Page<T> { items: [T]; next?: string; }");
+
+        if let Decl::Type(ref body) = *file.decls[0] {
+            let parameters: Vec<_> = body.type_parameters.iter().map(|p| **p).collect();
+            assert_eq!(vec!["T"], parameters);
+            return;
+        }
+
+        panic!("declaration did not match");
     }
 
     #[test]
@@ -1,6 +1,7 @@
 //! Errors for repository
 
-use core::RpPackage;
+use checksum::Checksum;
+use core::{RpPackage, Version};
 use core::errors as core;
 use hyper;
 use serde_json;
@@ -49,5 +50,25 @@ error_chain! {
         PoisonError {
             description("mutex poisoned")
         }
+
+        ChecksumMismatch(expected: Checksum, actual: Checksum) {
+            description("checksum mismatch")
+            display("checksum mismatch, expected {} but got {}", expected, actual)
+        }
+
+        Unsigned(package: RpPackage, version: Version) {
+            description("deployment is not signed")
+            display("{}@{}: deployment is not signed", package, version)
+        }
+
+        UntrustedKey(package: RpPackage, version: Version, key: String) {
+            description("deployment is signed with an untrusted key")
+            display("{}@{}: deployment is signed with an untrusted key: {}", package, version, key)
+        }
+
+        BadSignature(package: RpPackage, version: Version) {
+            description("deployment has a bad signature")
+            display("{}@{}: deployment has a bad signature", package, version)
+        }
     }
 }
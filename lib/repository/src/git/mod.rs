@@ -1,6 +1,12 @@
+#[cfg(not(feature = "native-git"))]
 mod git_repo;
+#[cfg(feature = "native-git")]
+mod native_git_repo;
 
+#[cfg(not(feature = "native-git"))]
 pub use self::git_repo::GitRepo;
+#[cfg(feature = "native-git")]
+pub use self::native_git_repo::GitRepo;
 use errors::*;
 use sha256;
 use std::path::Path;
@@ -0,0 +1,133 @@
+//! Abstraction over git repositories.
+//! Uses libgit2 to keep a repo in-sync, without relying on a git command being available.
+
+use errors::*;
+use git2::{self, Repository, ResetType};
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Local reference that the remote revision is fetched into.
+const FETCHED_REF: &'static str = "refs/reproto/fetched";
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct GitRepo {
+    work_tree: PathBuf,
+    remote: Url,
+    revspec: String,
+}
+
+fn git_error(e: git2::Error) -> Error {
+    format!("git: {}", e.message()).into()
+}
+
+impl GitRepo {
+    pub fn with_remote<P: AsRef<Path>>(path: P, remote: Url, revspec: String) -> Result<GitRepo> {
+        let path = path.as_ref();
+
+        let git_repo = GitRepo {
+            work_tree: path.to_owned(),
+            remote: remote,
+            revspec: revspec,
+        };
+
+        if !path.is_dir() {
+            trace!("Initializing git repo in {}", path.display());
+            fs::create_dir_all(path)?;
+            Repository::init(path).map_err(git_error)?;
+            git_repo.update()?;
+        }
+
+        Ok(git_repo)
+    }
+
+    pub fn path(&self) -> &Path {
+        self.work_tree.as_ref()
+    }
+
+    fn open(&self) -> Result<Repository> {
+        Repository::open(&self.work_tree).map_err(git_error)
+    }
+
+    pub fn reset(&self, revspec: &str) -> Result<()> {
+        let repo = self.open()?;
+        let object = repo.revparse_single(revspec).map_err(git_error)?;
+        repo.reset(&object, ResetType::Hard, None).map_err(git_error)?;
+        Ok(())
+    }
+
+    /// Update the repository.
+    pub fn update(&self) -> Result<()> {
+        info!("Updating {}", self.remote);
+
+        // query parameters are used to select the revision, and are not part of the remote.
+        let mut url = self.remote.clone();
+        url.set_query(None);
+
+        let repo = self.open()?;
+        let mut remote = repo.remote_anonymous(url.as_ref()).map_err(git_error)?;
+
+        let refspec = format!("+{}:{}", self.revspec, FETCHED_REF);
+        remote.fetch(&[&refspec], None, None).map_err(git_error)?;
+
+        self.reset(FETCHED_REF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use git2::Signature;
+    use std::fs::File;
+    use std::io::Write;
+
+    /// Set up a repository with a single commit, containing a single file.
+    fn setup_origin(path: &Path) {
+        let repo = Repository::init(path).unwrap();
+
+        File::create(path.join("config.json"))
+            .unwrap()
+            .write_all(b"{}\n")
+            .unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("config.json")).unwrap();
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("reproto", "reproto@localhost").unwrap();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "initial commit",
+            &tree,
+            &[],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_file_remote() {
+        let dir = TempDir::new("reproto-git").unwrap();
+
+        let origin = dir.path().join("origin");
+        fs::create_dir_all(&origin).unwrap();
+        setup_origin(&origin);
+
+        let remote = Url::from_file_path(&origin).unwrap();
+        let checkout = dir.path().join("checkout");
+
+        let git_repo = GitRepo::with_remote(&checkout, remote, "refs/heads/master".to_string())
+            .unwrap();
+
+        assert!(git_repo.path().join("config.json").is_file());
+
+        // updating an up-to-date repository is a no-op.
+        git_repo.update().unwrap();
+        assert!(git_repo.path().join("config.json").is_file());
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers shared by the components talking to a reproto server over HTTP.
+
+use errors::*;
+use hyper::{Client, Request, StatusCode};
+use hyper::client::HttpConnector;
+use hyper::header::{Authorization, Bearer};
+use hyper_tls::HttpsConnector;
+use tokio_core::reactor::Handle;
+
+/// Number of threads used for DNS resolution.
+const DNS_THREADS: usize = 4;
+
+/// Build a client which supports both `http` and `https` URLs.
+pub fn client(handle: &Handle) -> Result<Client<HttpsConnector<HttpConnector>>> {
+    let connector = HttpsConnector::new(DNS_THREADS, handle).map_err(|e| {
+        format!("failed to set up TLS: {}", e)
+    })?;
+
+    Ok(Client::configure().connector(connector).build(handle))
+}
+
+/// Authenticate the request with the given bearer token, if any.
+pub fn authorize(request: &mut Request, token: Option<&String>) {
+    if let Some(token) = token {
+        request.headers_mut().set(
+            Authorization(Bearer { token: token.clone() }),
+        );
+    }
+}
+
+/// Build an error for an unsuccessful response.
+pub fn bad_response(status: StatusCode, body: Vec<u8>) -> Error {
+    if let Ok(body) = String::from_utf8(body) {
+        return format!("bad response: {}: {}", status, body).into();
+    }
+
+    format!("bad response: {}", status).into()
+}
+
+/// A stub HTTP server, to test clients against.
+#[cfg(test)]
+pub mod stub {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use url::Url;
+
+    /// Serve the given responses, one per connection, with a URL of the given path.
+    ///
+    /// The returned handle joins into the requests that were received, headers and body.
+    pub fn serve(
+        path: &str,
+        responses: Vec<(&'static str, String)>,
+    ) -> (Url, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}{}", listener.local_addr().unwrap(), path);
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                requests.push(read_request(&mut stream));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                ).unwrap();
+            }
+
+            requests
+        });
+
+        (Url::parse(&url).unwrap(), handle)
+    }
+
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+
+            if line.trim().is_empty() {
+                break;
+            }
+
+            if line.to_lowercase().starts_with("content-length:") {
+                content_length = line[15..].trim().parse().unwrap();
+            }
+
+            request.push_str(&line);
+        }
+
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+        request
+    }
+}
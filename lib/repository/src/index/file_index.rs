@@ -5,6 +5,8 @@ use index::{Deployment, Index};
 use objects::{FileObjects, Objects};
 use relative_path::RelativePath;
 use serde_json;
+use signing::Signature;
+use std::collections::LinkedList;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
@@ -126,8 +128,11 @@ impl FileIndex {
 
 impl Index for FileIndex {
     fn resolve(&self, package: &RpPackage, version_req: &VersionReq) -> Result<Vec<Deployment>> {
-        self.read_package(package, |d| version_req.matches(&d.version))
-            .map(|r| r.0)
+        // yanked versions are only resolved if they are required exactly.
+        self.read_package(package, |d| {
+            version_req.matches(&d.version) &&
+                (!d.yanked || *version_req == VersionReq::exact(&d.version))
+        }).map(|r| r.0)
     }
 
     fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>> {
@@ -144,6 +149,7 @@ impl Index for FileIndex {
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<&Signature>,
         force: bool,
     ) -> Result<()> {
         let (mut deployments, other_match) = self.read_package(package, |d| d.version != *version)?;
@@ -154,7 +160,13 @@ impl Index for FileIndex {
             }
         }
 
-        deployments.push(Deployment::new(version.clone(), checksum.clone()));
+        let mut deployment = Deployment::new(version.clone(), checksum.clone());
+
+        if let Some(signature) = signature {
+            deployment = deployment.with_signature(signature.clone());
+        }
+
+        deployments.push(deployment);
         deployments.sort_by(|a, b| a.version.cmp(&b.version));
         self.write_package(package, deployments)?;
         Ok(())
@@ -166,6 +178,59 @@ impl Index for FileIndex {
         )
     }
 
+    fn yank(&self, package: &RpPackage, version: &Version, message: Option<&str>) -> Result<()> {
+        let (mut deployments, _) = self.read_package(package, |_| true)?;
+
+        let mut found = false;
+
+        for deployment in deployments.iter_mut().filter(|d| d.version == *version) {
+            deployment.yanked = true;
+            deployment.deprecated = message.map(ToOwned::to_owned);
+            found = true;
+        }
+
+        if !found {
+            return Err(format!("{}@{}: not published", package, version).into());
+        }
+
+        self.write_package(package, deployments)?;
+        Ok(())
+    }
+
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        let mut packages = Vec::new();
+        let mut queue = LinkedList::new();
+        queue.push_back((self.path.clone(), Vec::new()));
+
+        while let Some((path, parts)) = queue.pop_front() {
+            if !parts.is_empty() && path.join(METADATA_JSON).is_file() {
+                packages.push(RpPackage::new(parts.clone()));
+            }
+
+            for entry in fs::read_dir(&path)? {
+                let path = entry?.path();
+
+                if !path.is_dir() {
+                    continue;
+                }
+
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    // skip hidden directories, like the one of a git checkout.
+                    if name.starts_with('.') {
+                        continue;
+                    }
+
+                    let mut parts = parts.clone();
+                    parts.push(name.to_string());
+                    queue.push_back((path.clone(), parts));
+                }
+            }
+        }
+
+        packages.sort();
+        Ok(packages)
+    }
+
     fn objects_from_index(&self, relative_path: &RelativePath) -> Result<Box<Objects>> {
         let path = relative_path.to_path(&self.path);
         Ok(Box::new(FileObjects::new(&path)))
@@ -186,15 +251,24 @@ pub fn init_file_index<P: AsRef<Path> + ?Sized>(path: &P) -> Result<()> {
     let config_path = path.join(CONFIG_JSON);
 
     if !config_path.is_file() {
-        let mut f = File::create(config_path)?;
-        let config = Config { objects: DEFAULT_OBJECTS.to_owned() };
-        let config_content = serde_json::to_value(&config)?;
-        writeln!(f, "{:#}", config_content)?;
+        let config = Config {
+            objects: DEFAULT_OBJECTS.to_owned(),
+        };
+
+        write_config(path, &config)?;
     }
 
     Ok(())
 }
 
+/// Write the configuration to the given path.
+fn write_config(path: &Path, config: &Config) -> Result<()> {
+    let mut f = File::create(path.join(CONFIG_JSON))?;
+    let config_content = serde_json::to_value(config)?;
+    writeln!(f, "{:#}", config_content)?;
+    Ok(())
+}
+
 /// Read the configuration from the given path.
 fn read_config<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Config> {
     let path = path.as_ref();
@@ -224,3 +298,114 @@ fn read_config<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Config> {
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+
+    fn index() -> (TempDir, FileIndex) {
+        let dir = TempDir::new("reproto-index").unwrap();
+        init_file_index(dir.path()).unwrap();
+        let index = FileIndex::new(dir.path()).unwrap();
+        (dir, index)
+    }
+
+    fn put(index: &FileIndex, package: &str, version: &str) {
+        let package = RpPackage::parse(package);
+        let version = Version::parse(version).unwrap();
+        let checksum = Checksum::new(vec![0u8; 32]);
+        index.put_version(&checksum, &package, &version, None, false).unwrap();
+    }
+
+    #[test]
+    fn test_packages() {
+        let (dir, index) = index();
+        assert!(index.packages().unwrap().is_empty());
+
+        put(&index, "io.reproto", "1.0.0");
+        put(&index, "io.reproto.sub", "1.0.0");
+        put(&index, "com.example", "1.0.0");
+        put(&index, "com.example", "2.0.0");
+
+        // hidden directories are ignored, even if they look like packages.
+        let hidden = dir.path().join(".git").join("hidden");
+        fs::create_dir_all(&hidden).unwrap();
+        File::create(hidden.join(METADATA_JSON)).unwrap();
+
+        let expected = vec![
+            RpPackage::parse("com.example"),
+            RpPackage::parse("io.reproto"),
+            RpPackage::parse("io.reproto.sub"),
+        ];
+
+        assert_eq!(expected, index.packages().unwrap());
+    }
+
+    #[test]
+    fn test_put_version() {
+        let (_dir, index) = index();
+        let package = RpPackage::parse("io.reproto");
+        let version = Version::parse("1.0.0").unwrap();
+        let checksum = Checksum::new(vec![1u8; 32]);
+
+        put(&index, "io.reproto", "2.0.0");
+        put(&index, "io.reproto", "1.0.0");
+
+        let all = index.all(&package).unwrap();
+        let versions: Vec<_> = all.into_iter().map(|d| d.version).collect();
+        assert_eq!(vec![version.clone(), Version::parse("2.0.0").unwrap()], versions);
+
+        assert!(index.put_version(&checksum, &package, &version, None, false).is_err());
+        index.put_version(&checksum, &package, &version, None, true).unwrap();
+
+        let deployments = index.get_deployments(&package, &version).unwrap();
+        assert_eq!(1, deployments.len());
+        assert_eq!(checksum, deployments[0].object);
+    }
+
+    fn versions(deployments: &[Deployment]) -> Vec<String> {
+        deployments.iter().map(|d| d.version.to_string()).collect()
+    }
+
+    #[test]
+    fn test_yank() {
+        let (_dir, index) = index();
+        let package = RpPackage::parse("io.reproto");
+        let yanked = Version::parse("1.1.0").unwrap();
+
+        put(&index, "io.reproto", "1.0.0");
+        put(&index, "io.reproto", "1.1.0");
+        index.yank(&package, &yanked, Some("broken")).unwrap();
+
+        let resolve = |req: &VersionReq| versions(&index.resolve(&package, req).unwrap());
+
+        assert_eq!(vec!["1.0.0"], resolve(&VersionReq::parse("^1").unwrap()));
+        assert!(resolve(&VersionReq::parse(">=1.1.0").unwrap()).is_empty());
+
+        // yanked versions are still resolved when required exactly.
+        assert_eq!(vec!["1.1.0"], resolve(&VersionReq::exact(&yanked)));
+        assert_eq!(vec!["1.1.0"], resolve(&VersionReq::parse("=1.1.0").unwrap()));
+
+        let deployments = index.get_deployments(&package, &yanked).unwrap();
+        assert_eq!(1, deployments.len());
+        assert!(deployments[0].yanked);
+        assert_eq!(Some("broken"), deployments[0].deprecated.as_ref().map(String::as_str));
+
+        // yanked versions are kept, since they might be locked.
+        assert_eq!(vec!["1.0.0", "1.1.0"], versions(&index.all(&package).unwrap()));
+    }
+
+    #[test]
+    fn test_yank_missing() {
+        let (_dir, index) = index();
+        let package = RpPackage::parse("io.reproto");
+
+        put(&index, "io.reproto", "1.0.0");
+
+        let missing = Version::parse("1.1.0").unwrap();
+        assert!(index.yank(&package, &missing, None).is_err());
+    }
+}
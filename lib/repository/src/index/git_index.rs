@@ -5,6 +5,7 @@ use git::GitRepo;
 use index::{Deployment, Index, file_index};
 use objects::{FileObjects, GitObjects, Objects};
 use relative_path::RelativePath;
+use signing::Signature;
 use std::rc::Rc;
 use update::Update;
 use url::Url;
@@ -34,7 +35,14 @@ impl Index for GitIndex {
         self.file_index.all(package)
     }
 
-    fn put_version(&self, _: &Checksum, _: &RpPackage, _: &Version, _: bool) -> Result<()> {
+    fn put_version(
+        &self,
+        _: &Checksum,
+        _: &RpPackage,
+        _: &Version,
+        _: Option<&Signature>,
+        _: bool,
+    ) -> Result<()> {
         Err(ErrorKind::NoPublishIndex(self.url.to_string()).into())
     }
 
@@ -42,6 +50,14 @@ impl Index for GitIndex {
         self.file_index.get_deployments(package, version)
     }
 
+    fn yank(&self, _: &RpPackage, _: &Version, _: Option<&str>) -> Result<()> {
+        Err(ErrorKind::NoPublishIndex(self.url.to_string()).into())
+    }
+
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        self.file_index.packages()
+    }
+
     fn objects_url(&self) -> Result<&str> {
         self.file_index.objects_url()
     }
@@ -0,0 +1,285 @@
+//! ## Load an index from a reproto server over HTTP
+
+use checksum::Checksum;
+use core::{RpPackage, Version, VersionReq};
+use errors::*;
+use futures::{Future, Stream};
+use http;
+use hyper::{self, Method, Request, StatusCode};
+use hyper::header::{ContentLength, ContentType};
+use index::{Deployment, Index};
+use objects::Objects;
+use relative_path::RelativePath;
+use serde::de::DeserializeOwned;
+use serde_json;
+use signing::Signature;
+use std::cell::RefCell;
+use tokio_core::reactor::Core;
+use url::Url;
+
+/// Body of a request to publish a new version.
+#[derive(Debug, Serialize)]
+struct PutVersion<'a> {
+    object: &'a Checksum,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<&'a Signature>,
+}
+
+pub struct HttpIndex {
+    url: Url,
+    /// Objects are stored on the same server as the index.
+    objects_url: String,
+    core: RefCell<Core>,
+    /// Bearer token to authenticate with.
+    token: Option<String>,
+}
+
+impl HttpIndex {
+    pub fn new(mut url: Url, core: Core) -> Result<HttpIndex> {
+        // make sure that relative URLs are joined with the full path.
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
+        }
+
+        let objects_url = url.join("objects/")?.to_string();
+
+        Ok(HttpIndex {
+            url: url,
+            objects_url: objects_url,
+            core: RefCell::new(core),
+            token: None,
+        })
+    }
+
+    /// Authenticate all requests with the given bearer token.
+    pub fn with_token(self, token: String) -> HttpIndex {
+        HttpIndex {
+            token: Some(token),
+            ..self
+        }
+    }
+
+    fn index_url(&self, path: &str) -> Result<Url> {
+        Ok(self.url.join("index/")?.join(path)?)
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Vec<u8>>,
+    ) -> Result<(Vec<u8>, StatusCode)> {
+        let uri = url.to_string().parse::<hyper::Uri>()?;
+
+        let mut request = Request::new(method, uri);
+        http::authorize(&mut request, self.token.as_ref());
+
+        if let Some(body) = body {
+            request.headers_mut().set(ContentLength(body.len() as u64));
+            request.headers_mut().set(ContentType::json());
+            request.set_body(body);
+        }
+
+        let mut core = self.core.try_borrow_mut().map_err(
+            |_| "index is already in use",
+        )?;
+
+        let client = http::client(&core.handle())?;
+
+        let work = client
+            .request(request)
+            .map_err::<Error, _>(Into::into)
+            .and_then(|res| {
+                let status = res.status().clone();
+
+                res.body()
+                    .concat2()
+                    .map_err::<Error, _>(Into::into)
+                    .map(move |body| (body.to_vec(), status))
+            });
+
+        core.run(work)
+    }
+
+    fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T> {
+        let (body, status) = self.request(Method::Get, url, None)?;
+
+        if !status.is_success() {
+            return Err(http::bad_response(status, body));
+        }
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+}
+
+impl Index for HttpIndex {
+    fn resolve(&self, package: &RpPackage, version_req: &VersionReq) -> Result<Vec<Deployment>> {
+        let mut url = self.index_url(&package.to_string())?;
+        url.query_pairs_mut().append_pair(
+            "version_req",
+            &version_req.to_string(),
+        );
+        self.get(url)
+    }
+
+    fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>> {
+        self.get(self.index_url(&package.to_string())?)
+    }
+
+    fn put_version(
+        &self,
+        checksum: &Checksum,
+        package: &RpPackage,
+        version: &Version,
+        signature: Option<&Signature>,
+        force: bool,
+    ) -> Result<()> {
+        let mut url = self.index_url(&format!("{}/{}", package, version))?;
+
+        if force {
+            url.query_pairs_mut().append_pair("force", "true");
+        }
+
+        let body = serde_json::to_vec(&PutVersion {
+            object: checksum,
+            signature: signature,
+        })?;
+        let (body, status) = self.request(Method::Put, url, Some(body))?;
+
+        if status == StatusCode::Conflict {
+            return Err(format!("{}@{}: already published", package, version).into());
+        }
+
+        if !status.is_success() {
+            return Err(http::bad_response(status, body));
+        }
+
+        Ok(())
+    }
+
+    fn get_deployments(&self, package: &RpPackage, version: &Version) -> Result<Vec<Deployment>> {
+        self.get(self.index_url(&format!("{}/{}", package, version))?)
+    }
+
+    fn yank(&self, package: &RpPackage, version: &Version, message: Option<&str>) -> Result<()> {
+        let mut url = self.index_url(&format!("{}/{}", package, version))?;
+
+        if let Some(message) = message {
+            url.query_pairs_mut().append_pair("message", message);
+        }
+
+        let (body, status) = self.request(Method::Delete, url, None)?;
+
+        if status == StatusCode::NotFound {
+            return Err(format!("{}@{}: not published", package, version).into());
+        }
+
+        if !status.is_success() {
+            return Err(http::bad_response(status, body));
+        }
+
+        Ok(())
+    }
+
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        self.get(self.index_url("")?)
+    }
+
+    fn objects_url(&self) -> Result<&str> {
+        Ok(self.objects_url.as_str())
+    }
+
+    fn objects_from_index(&self, _: &RelativePath) -> Result<Box<Objects>> {
+        Err("objects relative to an HTTP index are not supported".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::stub::serve;
+
+    fn index(url: Url) -> HttpIndex {
+        HttpIndex::new(url, Core::new().unwrap()).unwrap()
+    }
+
+    fn checksum() -> Checksum {
+        Checksum::new(vec![0u8; 32])
+    }
+
+    #[test]
+    fn test_packages() {
+        let body = "[\"io.other\", \"io.reproto\"]".to_string();
+        let (url, server) = serve("/prefix", vec![("200 OK", body)]);
+
+        let packages = index(url).packages().unwrap();
+        let expected = vec![RpPackage::parse("io.other"), RpPackage::parse("io.reproto")];
+        assert_eq!(expected, packages);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /prefix/index/ HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let body = format!("[{{\"version\": \"1.2.0\", \"object\": \"{}\"}}]", checksum());
+        let (url, server) = serve("/prefix", vec![("200 OK", body)]);
+
+        let package = RpPackage::parse("io.reproto");
+        let version_req = VersionReq::parse("^1").unwrap();
+        let deployments = index(url).resolve(&package, &version_req).unwrap();
+
+        assert_eq!(1, deployments.len());
+        assert_eq!(Version::parse("1.2.0").unwrap(), deployments[0].version);
+        assert_eq!(checksum(), deployments[0].object);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /prefix/index/io.reproto?version_req="));
+    }
+
+    #[test]
+    fn test_put_version() {
+        let (url, server) = serve("/prefix", vec![("200 OK", String::new())]);
+
+        let index = index(url).with_token("secret".to_string());
+        let package = RpPackage::parse("io.reproto");
+        let version = Version::parse("1.0.0").unwrap();
+        index.put_version(&checksum(), &package, &version, None, true).unwrap();
+
+        let requests = server.join().unwrap();
+        let request = &requests[0];
+
+        assert!(request.starts_with("PUT /prefix/index/io.reproto/1.0.0?force=true HTTP/1.1\r\n"));
+        assert!(request.contains("Authorization: Bearer secret\r\n"));
+        assert!(request.ends_with(&format!("{{\"object\":\"{}\"}}", checksum())));
+    }
+
+    #[test]
+    fn test_put_version_conflict() {
+        let (url, server) = serve("/prefix", vec![("409 Conflict", String::new())]);
+
+        let package = RpPackage::parse("io.reproto");
+        let version = Version::parse("1.0.0").unwrap();
+        let result = index(url).put_version(&checksum(), &package, &version, None, false);
+
+        let error = result.err().expect("expected conflict");
+        assert!(error.to_string().contains("already published"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_bad_response() {
+        let (url, server) = serve("/prefix", vec![("500 Internal Server Error", "oops".to_string())]);
+
+        let error = index(url).packages().err().expect("expected error");
+        assert!(error.to_string().contains("oops"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_objects_url() {
+        let url = Url::parse("http://localhost/prefix").unwrap();
+        assert_eq!("http://localhost/prefix/objects/", index(url).objects_url().unwrap());
+    }
+}
@@ -1,15 +1,19 @@
 mod file_index;
 mod git_index;
+mod http_index;
 
-pub use self::file_index::init_file_index;
+pub use self::file_index::{FileIndex, init_file_index};
 use self::git_index::GitIndex;
+pub use self::http_index::HttpIndex;
 use checksum::Checksum;
 use core::{RpPackage, Version, VersionReq};
 use git;
 use objects::Objects;
 use relative_path::RelativePath;
+use signing::Signature;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use tokio_core::reactor::Core;
 use update::Update;
 use url::Url;
 
@@ -17,12 +21,29 @@ use url::Url;
 pub struct IndexConfig {
     /// Root path when checking out local repositories.
     pub repo_dir: PathBuf,
+    /// Bearer token to authenticate with remote indexes.
+    pub token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Deployment {
     pub version: Version,
     pub object: Checksum,
+    /// Signature of the deployment, if it was signed when published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+    /// If the deployment has been yanked.
+    ///
+    /// Yanked deployments are only resolved if they are required exactly.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub yanked: bool,
+    /// Deprecation message, to warn about when the deployment is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl Deployment {
@@ -30,6 +51,16 @@ impl Deployment {
         Deployment {
             version: version,
             object: object,
+            signature: None,
+            yanked: false,
+            deprecated: None,
+        }
+    }
+
+    pub fn with_signature(self, signature: Signature) -> Deployment {
+        Deployment {
+            signature: Some(signature),
+            ..self
         }
     }
 }
@@ -50,11 +81,20 @@ pub trait Index {
         checksum: &Checksum,
         package: &RpPackage,
         version: &Version,
+        signature: Option<&Signature>,
         force: bool,
     ) -> Result<()>;
 
     fn get_deployments(&self, package: &RpPackage, version: &Version) -> Result<Vec<Deployment>>;
 
+    /// Yank a published version, with an optional deprecation message.
+    fn yank(&self, package: &RpPackage, version: &Version, message: Option<&str>) -> Result<()>;
+
+    /// Get all packages which have deployments in the index.
+    ///
+    /// The returned packages are sorted.
+    fn packages(&self) -> Result<Vec<RpPackage>>;
+
     /// Get an objects URL as configured in the index.
     ///
     /// If relative, will cause objects to be loaded from the same repository as the index.
@@ -80,7 +120,14 @@ impl Index for NoIndex {
         Ok(vec![])
     }
 
-    fn put_version(&self, _: &Checksum, _: &RpPackage, _: &Version, _: bool) -> Result<()> {
+    fn put_version(
+        &self,
+        _: &Checksum,
+        _: &RpPackage,
+        _: &Version,
+        _: Option<&Signature>,
+        _: bool,
+    ) -> Result<()> {
         Err(ErrorKind::EmptyIndex.into())
     }
 
@@ -88,6 +135,14 @@ impl Index for NoIndex {
         Ok(vec![])
     }
 
+    fn yank(&self, _: &RpPackage, _: &Version, _: Option<&str>) -> Result<()> {
+        Err(ErrorKind::EmptyIndex.into())
+    }
+
+    fn packages(&self) -> Result<Vec<RpPackage>> {
+        Ok(vec![])
+    }
+
     /// Get an objects URL as configured in the index.
     ///
     /// If relative, will cause objects to be loaded from the same repository as the index.
@@ -131,6 +186,19 @@ where
     Ok(Box::new(index))
 }
 
+/// Load an index from a reproto server.
+pub fn index_from_http(config: IndexConfig, url: &Url) -> Result<Box<Index>> {
+    let core = Core::new()?;
+
+    let mut index = HttpIndex::new(url.clone(), core)?;
+
+    if let Some(token) = config.token {
+        index = index.with_token(token);
+    }
+
+    Ok(Box::new(index))
+}
+
 pub fn index_from_url(config: IndexConfig, url: &Url) -> Result<Box<Index>> {
     let mut scheme = url.scheme().split("+");
 
@@ -144,6 +212,7 @@ pub fn index_from_url(config: IndexConfig, url: &Url) -> Result<Box<Index>> {
                 .map(|i| Box::new(i) as Box<Index>)
         }
         "git" => index_from_git(config, scheme, url),
+        "http" | "https" => index_from_http(config, url),
         scheme => Err(format!("bad scheme: {}", scheme).into()),
     }.chain_err(|| format!("loading index from url: {}", url))
 }
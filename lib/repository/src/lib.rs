@@ -14,29 +14,39 @@ extern crate url;
 extern crate hex;
 extern crate tokio_core;
 extern crate hyper;
+extern crate hyper_tls;
 extern crate futures;
 extern crate ring;
 extern crate relative_path;
+extern crate untrusted;
+#[cfg(feature = "native-git")]
+extern crate git2;
 
 mod checksum;
 mod git;
 mod hex_slice;
+mod http;
 mod index;
+mod lock;
 mod metadata;
 mod objects;
 mod repository;
 mod resolver;
 mod sha256;
+mod signing;
+mod sigv4;
 pub mod errors;
 mod update;
 
 pub use self::checksum::Checksum;
 pub use self::git::GitRepo;
-pub use self::index::{Index, IndexConfig, NoIndex, index_from_path, index_from_url,
-                      init_file_index};
-pub use self::objects::{FileObjects, NoObjects, Objects, ObjectsConfig, objects_from_path,
-                        objects_from_url};
-pub use self::repository::Repository;
+pub use self::index::{Deployment, FileIndex, HttpIndex, Index, IndexConfig, NoIndex,
+                      index_from_path, index_from_url, init_file_index};
+pub use self::lock::{Lock, Locked, read_lock};
+pub use self::objects::{FileObjects, NoObjects, Objects, ObjectsConfig, S3Objects,
+                        objects_from_path, objects_from_url};
+pub use self::repository::{Fsck, Repository};
 pub use self::resolver::{Paths, Resolved, ResolvedByPrefix, Resolver, Resolvers};
 pub use self::sha256::{Sha256 as Digest, to_sha256 as to_checksum};
+pub use self::signing::{Signature, SigningKey, generate_key, parse_public_key, read_key};
 pub use self::update::Update;
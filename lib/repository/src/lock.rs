@@ -0,0 +1,228 @@
+//! Lockfile, recording exactly which versions of packages a project was built with.
+
+use checksum::Checksum;
+use core::{RpPackage, RpRequiredPackage, Version, VersionReq};
+use errors::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use toml;
+
+const HEADER: &'static str = "# This file is generated by reproto, do not edit it by hand.\n";
+
+/// The locked resolution of a single package requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locked {
+    pub version: Version,
+    pub object: Checksum,
+}
+
+/// Serialized form of a lockfile.
+#[derive(Debug, Serialize, Deserialize)]
+struct LockFile {
+    #[serde(default)]
+    package: Vec<LockEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LockEntry {
+    package: RpPackage,
+    version_req: String,
+    version: Version,
+    object: Checksum,
+}
+
+#[derive(Debug, Default)]
+pub struct Lock {
+    packages: BTreeMap<RpRequiredPackage, Locked>,
+    /// If the lock has been modified since it was read.
+    changed: bool,
+}
+
+impl Lock {
+    pub fn new() -> Lock {
+        Lock::default()
+    }
+
+    /// Get the locked resolution for the given requirement.
+    pub fn get(&self, required: &RpRequiredPackage) -> Option<&Locked> {
+        self.packages.get(required)
+    }
+
+    /// Lock the given requirement to a resolution.
+    pub fn insert(&mut self, required: RpRequiredPackage, locked: Locked) {
+        if self.packages.get(&required) == Some(&locked) {
+            return;
+        }
+
+        self.packages.insert(required, locked);
+        self.changed = true;
+    }
+
+    /// Check if the lock has been modified since it was read.
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Write the lock to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let package = self.packages
+            .iter()
+            .map(|(required, locked)| {
+                LockEntry {
+                    package: required.package.clone(),
+                    version_req: required.version_req.to_string(),
+                    version: locked.version.clone(),
+                    object: locked.object.clone(),
+                }
+            })
+            .collect();
+
+        let content = toml::to_string(&LockFile { package: package }).map_err(|e| {
+            format!("{}: failed to serialize lock: {}", path.display(), e)
+        })?;
+
+        let mut f = File::create(path)?;
+        f.write_all(HEADER.as_bytes())?;
+        f.write_all(content.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Read the lock from the given path.
+///
+/// An empty lock is returned if the file does not exist.
+pub fn read_lock(path: &Path) -> Result<Lock> {
+    if !path.is_file() {
+        return Ok(Lock::new());
+    }
+
+    let mut f = File::open(path)?;
+    let mut content = String::new();
+    f.read_to_string(&mut content)?;
+
+    let lock_file: LockFile = toml::from_str(content.as_str()).map_err(|e| {
+        format!("{}: bad lock: {}", path.display(), e)
+    })?;
+
+    let mut packages = BTreeMap::new();
+
+    for entry in lock_file.package {
+        let version_req = VersionReq::parse(&entry.version_req).map_err(|e| {
+            format!(
+                "{}: bad version requirement: {}: {}",
+                path.display(),
+                entry.version_req,
+                e
+            )
+        })?;
+
+        let required = RpRequiredPackage::new(entry.package, version_req);
+
+        packages.insert(
+            required,
+            Locked {
+                version: entry.version,
+                object: entry.object,
+            },
+        );
+    }
+
+    Ok(Lock {
+        packages: packages,
+        changed: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+
+    fn required(package: &str, version_req: &str) -> RpRequiredPackage {
+        RpRequiredPackage::new(
+            RpPackage::parse(package),
+            VersionReq::parse(version_req).unwrap(),
+        )
+    }
+
+    fn locked(version: &str, byte: u8) -> Locked {
+        Locked {
+            version: Version::parse(version).unwrap(),
+            object: Checksum::new(vec![byte; 32]),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = TempDir::new("reproto-lock").unwrap();
+        let path = dir.path().join("reproto.lock");
+
+        let mut lock = Lock::new();
+        lock.insert(required("io.reproto", "^1"), locked("1.2.0", 1));
+        lock.insert(required("io.reproto", "=1.0.0"), locked("1.0.0", 2));
+        lock.insert(required("com.example", "*"), locked("0.1.0", 3));
+        lock.write(&path).unwrap();
+
+        let mut content = String::new();
+        File::open(&path).unwrap().read_to_string(&mut content).unwrap();
+        assert!(content.starts_with(HEADER));
+
+        let read = read_lock(&path).unwrap();
+        assert!(!read.is_changed());
+        assert_eq!(lock.packages, read.packages);
+
+        assert_eq!(
+            Some(&locked("1.2.0", 1)),
+            read.get(&required("io.reproto", "^1"))
+        );
+        assert_eq!(None, read.get(&required("io.reproto", "^2")));
+    }
+
+    #[test]
+    fn test_missing_lock() {
+        let dir = TempDir::new("reproto-lock").unwrap();
+        let lock = read_lock(&dir.path().join("reproto.lock")).unwrap();
+
+        assert!(lock.packages.is_empty());
+        assert!(!lock.is_changed());
+    }
+
+    #[test]
+    fn test_bad_lock() {
+        let dir = TempDir::new("reproto-lock").unwrap();
+        let path = dir.path().join("reproto.lock");
+
+        File::create(&path)
+            .unwrap()
+            .write_all(b"[[package]]\npackage = \"io.reproto\"\n")
+            .unwrap();
+
+        assert!(read_lock(&path).is_err());
+    }
+
+    #[test]
+    fn test_changed() {
+        let mut lock = Lock::new();
+        assert!(!lock.is_changed());
+
+        lock.insert(required("io.reproto", "^1"), locked("1.0.0", 1));
+        assert!(lock.is_changed());
+
+        let dir = TempDir::new("reproto-lock").unwrap();
+        let path = dir.path().join("reproto.lock");
+        lock.write(&path).unwrap();
+
+        let mut lock = read_lock(&path).unwrap();
+
+        // inserting the same resolution again is not a change.
+        lock.insert(required("io.reproto", "^1"), locked("1.0.0", 1));
+        assert!(!lock.is_changed());
+
+        lock.insert(required("io.reproto", "^1"), locked("1.1.0", 2));
+        assert!(lock.is_changed());
+    }
+}
@@ -1,14 +1,17 @@
 //! ## Load objects through a local cache directory
 
-use super::Objects;
+use super::{FileObjects, Objects};
 use checksum::Checksum;
 use core::{Object, PathObject};
 use errors::*;
 use hex_slice::HexSlice;
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::{self, Read};
-use std::path::PathBuf;
+use sha256;
+use std::path::{Path, PathBuf};
 use std::time::{self, Duration};
+use url::Url;
 
 pub struct CachedObjects<T> {
     objects_cache: PathBuf,
@@ -17,12 +20,32 @@ pub struct CachedObjects<T> {
 }
 
 impl<T: Objects> CachedObjects<T> {
-    pub fn new(objects_cache: PathBuf, missing_cache_time: Duration, inner: T) -> CachedObjects<T> {
-        CachedObjects {
+    /// Cache the objects of the remote at `url` in a directory under `cache_dir`.
+    ///
+    /// Every remote has its own directory, since the cache is shared between all remotes and
+    /// garbage collection must only prune the objects of one of them.
+    pub fn new(
+        cache_dir: &Path,
+        url: &Url,
+        missing_cache_time: Duration,
+        inner: T,
+    ) -> Result<CachedObjects<T>> {
+        let tail = {
+            let mut tail = sha256::Sha256::new();
+            tail.update(url.to_string().as_bytes());
+            tail.finish()
+        }?;
+
+        let objects_cache = match url.host() {
+            Some(host) => cache_dir.join(format!("{}-{}", host, tail)),
+            _ => cache_dir.join(format!("unknown-{}", tail)),
+        };
+
+        Ok(CachedObjects {
             objects_cache: objects_cache,
             missing_cache_time: missing_cache_time,
             inner: inner,
-        }
+        })
     }
 
     fn cache_path(&self, checksum: &Checksum) -> Result<PathBuf> {
@@ -42,6 +65,36 @@ impl<T: Objects> CachedObjects<T> {
         )))
     }
 
+    /// Objects which have been cached locally.
+    ///
+    /// Uses the same layout as `FileObjects`.
+    fn cached_objects(&self) -> FileObjects {
+        FileObjects::new(&self.objects_cache)
+    }
+
+    /// Remove all missing cache entries which have expired.
+    fn prune_missing(&self) -> Result<()> {
+        let missing = self.objects_cache.join("missing");
+
+        if !missing.is_dir() {
+            return Ok(());
+        }
+
+        let now = time::SystemTime::now();
+
+        for entry in fs::read_dir(&missing)? {
+            let path = entry?.path();
+            let age = now.duration_since(fs::metadata(&path)?.modified()?)?;
+
+            if age >= self.missing_cache_time {
+                debug!("cache: removing missing entry: {}", path.display());
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check if there is a local missing cached file, and assume that the remote file is missing
     /// if it is present, or younger than `missing_cache_time`.
     ///
@@ -134,4 +187,83 @@ impl<T: Objects> Objects for CachedObjects<T> {
 
         return Ok(None);
     }
+
+    fn has_object(&mut self, checksum: &Checksum) -> Result<bool> {
+        if self.cache_path(checksum)?.is_file() {
+            return Ok(true);
+        }
+
+        if self.check_missing(checksum)?.0 {
+            return Ok(false);
+        }
+
+        self.inner.has_object(checksum)
+    }
+
+    fn fsck(&self) -> Result<Vec<(Checksum, Checksum)>> {
+        let mut corrupt = self.cached_objects().fsck()?;
+        corrupt.extend(self.inner.fsck()?);
+        Ok(corrupt)
+    }
+
+    fn gc(&mut self, keep: &BTreeSet<Checksum>) -> Result<Vec<Checksum>> {
+        self.prune_missing()?;
+
+        let mut removed = self.cached_objects().gc(keep)?;
+        removed.extend(self.inner.gc(keep)?);
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use sha256::to_sha256;
+
+    fn objects(cache: &TempDir, remote: &TempDir, url: &str) -> CachedObjects<FileObjects> {
+        let url = Url::parse(url).unwrap();
+        let inner = FileObjects::new(remote.path());
+        CachedObjects::new(cache.path(), &url, Duration::new(60, 0), inner).unwrap()
+    }
+
+    fn put(objects: &mut CachedObjects<FileObjects>, content: &[u8]) -> Checksum {
+        let checksum = to_sha256(content).unwrap();
+        objects.put_object(&checksum, &mut &content[..], false).unwrap();
+        // fetch the object to populate the cache.
+        assert!(objects.get_object(&checksum).unwrap().is_some());
+        checksum
+    }
+
+    #[test]
+    fn test_gc_shared_cache() {
+        let cache = TempDir::new("reproto-cache").unwrap();
+        let remote_a = TempDir::new("reproto-objects").unwrap();
+        let remote_b = TempDir::new("reproto-objects").unwrap();
+
+        let mut a = objects(&cache, &remote_a, "http://a.example.com/objects");
+        let mut b = objects(&cache, &remote_b, "http://b.example.com/objects");
+
+        let kept = put(&mut a, b"kept");
+        let removed = put(&mut a, b"removed");
+        let other = put(&mut b, b"other");
+
+        assert!(a.cache_path(&kept).unwrap().is_file());
+        assert!(b.cache_path(&other).unwrap().is_file());
+
+        let mut keep = BTreeSet::new();
+        keep.insert(kept.clone());
+
+        let gc = a.gc(&keep).unwrap();
+        assert!(gc.contains(&removed));
+        assert!(!gc.contains(&other));
+
+        assert!(a.cache_path(&kept).unwrap().is_file());
+        assert!(!a.cache_path(&removed).unwrap().is_file());
+        // objects cached for another remote are left alone.
+        assert!(b.cache_path(&other).unwrap().is_file());
+        assert!(b.get_object(&other).unwrap().is_some());
+    }
 }
@@ -5,6 +5,8 @@ use checksum::Checksum;
 use core::{Object, PathObject};
 use errors::*;
 use hex_slice::HexSlice;
+use sha256::to_sha256;
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
@@ -25,6 +27,55 @@ impl FileObjects {
         let path = path.join(format!("{}", HexSlice::new(&checksum[1..2])));
         Ok(path.join(format!("{}", HexSlice::new(&checksum))))
     }
+
+    /// List all stored objects, and their paths.
+    ///
+    /// Files which are not named after their checksum (like temporary files) are skipped.
+    fn objects(&self) -> Result<Vec<(Checksum, PathBuf)>> {
+        let mut out = Vec::new();
+
+        if !self.path.is_dir() {
+            return Ok(out);
+        }
+
+        for first in fs::read_dir(&self.path)? {
+            let first = first?.path();
+
+            if !first.is_dir() {
+                continue;
+            }
+
+            for second in fs::read_dir(&first)? {
+                let second = second?.path();
+
+                if !second.is_dir() {
+                    continue;
+                }
+
+                for entry in fs::read_dir(&second)? {
+                    let path = entry?.path();
+
+                    if !path.is_file() {
+                        continue;
+                    }
+
+                    let checksum = match path.file_name().and_then(|n| n.to_str()) {
+                        Some(name) => Checksum::from_str(name).ok(),
+                        None => None,
+                    };
+
+                    if let Some(checksum) = checksum {
+                        if self.checksum_path(&checksum)? == path {
+                            out.push((checksum, path));
+                        }
+                    }
+                }
+            }
+        }
+
+        out.sort();
+        Ok(out)
+    }
 }
 
 impl Objects for FileObjects {
@@ -60,4 +111,89 @@ impl Objects for FileObjects {
 
         Ok(None)
     }
+
+    fn has_object(&mut self, checksum: &Checksum) -> Result<bool> {
+        Ok(self.checksum_path(checksum)?.is_file())
+    }
+
+    fn fsck(&self) -> Result<Vec<(Checksum, Checksum)>> {
+        let mut corrupt = Vec::new();
+
+        for (checksum, path) in self.objects()? {
+            let actual = to_sha256(File::open(&path)?)?;
+
+            if actual != checksum {
+                debug!("corrupt object: {}", path.display());
+                corrupt.push((checksum, actual));
+            }
+        }
+
+        Ok(corrupt)
+    }
+
+    fn gc(&mut self, keep: &BTreeSet<Checksum>) -> Result<Vec<Checksum>> {
+        let mut removed = Vec::new();
+
+        for (checksum, path) in self.objects()? {
+            if keep.contains(&checksum) {
+                continue;
+            }
+
+            debug!("removing: {}", path.display());
+            fs::remove_file(&path)?;
+            removed.push(checksum);
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use std::io::Write;
+
+    fn put(objects: &mut FileObjects, content: &[u8]) -> Checksum {
+        let checksum = to_sha256(content).unwrap();
+        objects.put_object(&checksum, &mut &content[..], false).unwrap();
+        checksum
+    }
+
+    #[test]
+    fn test_fsck() {
+        let dir = TempDir::new("reproto-objects").unwrap();
+        let mut objects = FileObjects::new(dir.path());
+
+        let good = put(&mut objects, b"good");
+        let bad = put(&mut objects, b"bad");
+
+        File::create(objects.checksum_path(&bad).unwrap())
+            .unwrap()
+            .write_all(b"corrupted")
+            .unwrap();
+
+        let corrupt = objects.fsck().unwrap();
+        assert_eq!(1, corrupt.len());
+        assert_eq!(bad, corrupt[0].0);
+        assert!(good != corrupt[0].0);
+    }
+
+    #[test]
+    fn test_gc() {
+        let dir = TempDir::new("reproto-objects").unwrap();
+        let mut objects = FileObjects::new(dir.path());
+
+        let kept = put(&mut objects, b"kept");
+        let removed = put(&mut objects, b"removed");
+
+        let mut keep = BTreeSet::new();
+        keep.insert(kept.clone());
+
+        assert_eq!(vec![removed.clone()], objects.gc(&keep).unwrap());
+        assert!(objects.has_object(&kept).unwrap());
+        assert!(!objects.has_object(&removed).unwrap());
+    }
 }
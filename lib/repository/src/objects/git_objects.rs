@@ -35,7 +35,17 @@ impl Objects for GitObjects {
         self.file_objects.get_object(checksum)
     }
 
+    fn has_object(&mut self, checksum: &Checksum) -> Result<bool> {
+        self.file_objects.has_object(checksum)
+    }
+
     fn update(&self) -> Result<Vec<Update>> {
         Ok(vec![Update::GitRepo(&self.git_repo)])
     }
+
+    /// Objects are only verified, the repository is managed remotely and is not garbage
+    /// collected.
+    fn fsck(&self) -> Result<Vec<(Checksum, Checksum)>> {
+        self.file_objects.fsck()
+    }
 }
@@ -7,8 +7,9 @@ use errors::*;
 use futures::{Future, Stream};
 use futures::future::{err, ok};
 use hex_slice::HexSlice;
+use http;
 use hyper;
-use hyper::{Client, Method, Request, StatusCode};
+use hyper::{Method, Request, StatusCode};
 use hyper::header::ContentLength;
 use std::io::Read;
 use std::sync::Arc;
@@ -18,6 +19,8 @@ use url::Url;
 pub struct HttpObjects {
     url: Url,
     core: Core,
+    /// Bearer token to authenticate with.
+    token: Option<String>,
 }
 
 impl HttpObjects {
@@ -25,6 +28,15 @@ impl HttpObjects {
         HttpObjects {
             url: url,
             core: core,
+            token: None,
+        }
+    }
+
+    /// Authenticate all requests with the given bearer token.
+    pub fn with_token(self, token: String) -> HttpObjects {
+        HttpObjects {
+            token: Some(token),
+            ..self
         }
     }
 
@@ -36,10 +48,14 @@ impl HttpObjects {
 
     fn handle_request(
         &mut self,
-        request: Request,
+        mut request: Request,
     ) -> Box<Future<Item = (Vec<u8>, StatusCode), Error = Error>> {
-        let handle = self.core.handle();
-        let client = Client::new(&handle);
+        http::authorize(&mut request, self.token.as_ref());
+
+        let client = match http::client(&self.core.handle()) {
+            Ok(client) => client,
+            Err(e) => return Box::new(err(e)),
+        };
 
         let body_and_status = client
             .request(request)
@@ -75,11 +91,7 @@ impl Objects for HttpObjects {
 
         let work = self.handle_request(request).and_then(|(body, status)| {
             if !status.is_success() {
-                if let Ok(body) = String::from_utf8(body) {
-                    return err(format!("bad response: {}: {}", status, body).into());
-                }
-
-                return err(format!("bad response: {}", status).into());
+                return err(http::bad_response(status, body));
             }
 
             ok(())
@@ -104,11 +116,7 @@ impl Objects for HttpObjects {
                 return ok(None);
             }
 
-            if let Ok(body) = String::from_utf8(body) {
-                return err(format!("bad response: {}: {}", status, body).into());
-            }
-
-            return err(format!("bad response: {}", status).into());
+            err(http::bad_response(status, body))
         });
 
         let out = self.core.run(work)?;
@@ -117,4 +125,24 @@ impl Objects for HttpObjects {
             Box::new(BytesObject::new(name, out)) as Box<Object>
         }))
     }
+
+    fn has_object(&mut self, checksum: &Checksum) -> Result<bool> {
+        let url = self.checksum_url(checksum)?;
+
+        let request = Request::new(Method::Head, url);
+
+        let work = self.handle_request(request).and_then(|(body, status)| {
+            if status.is_success() {
+                return ok(true);
+            }
+
+            if status == StatusCode::NotFound {
+                return ok(false);
+            }
+
+            err(http::bad_response(status, body))
+        });
+
+        self.core.run(work)
+    }
 }
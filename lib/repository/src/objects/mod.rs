@@ -2,18 +2,22 @@ mod file_objects;
 mod git_objects;
 mod http_objects;
 mod cached_objects;
+mod s3_objects;
 
 pub use self::cached_objects::CachedObjects;
 pub use self::file_objects::FileObjects;
 pub use self::git_objects::GitObjects;
 pub use self::http_objects::HttpObjects;
+pub use self::s3_objects::S3Objects;
 use checksum::Checksum;
 use core::Object;
 use errors::*;
 use git;
+use std::collections::BTreeSet;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use sigv4::Credentials;
 use std::time::Duration;
 use tokio_core::reactor::Core;
 use update::Update;
@@ -25,6 +29,8 @@ pub struct ObjectsConfig {
     pub repo_dir: PathBuf,
     pub cache_dir: Option<PathBuf>,
     pub missing_cache_time: Option<Duration>,
+    /// Bearer token to authenticate with remote objects.
+    pub token: Option<String>,
 }
 
 pub trait Objects {
@@ -38,10 +44,29 @@ pub trait Objects {
     /// filesystem.
     fn get_object(&mut self, checksum: &Checksum) -> Result<Option<Box<Object>>>;
 
+    /// Check if the object with the given checksum exists, without fetching its content.
+    fn has_object(&mut self, checksum: &Checksum) -> Result<bool> {
+        Ok(self.get_object(checksum)?.is_some())
+    }
+
     /// Update local caches related to the object store.
     fn update(&self) -> Result<Vec<Update>> {
         Ok(vec![])
     }
+
+    /// Verify that the content of every locally stored object matches its checksum.
+    ///
+    /// Returns the expected and actual checksum of every corrupt object.
+    fn fsck(&self) -> Result<Vec<(Checksum, Checksum)>> {
+        Ok(vec![])
+    }
+
+    /// Remove every locally stored object which is not in `keep`, and prune expired caches.
+    ///
+    /// Returns the checksums of all removed objects.
+    fn gc(&mut self, _keep: &BTreeSet<Checksum>) -> Result<Vec<Checksum>> {
+        Ok(vec![])
+    }
 }
 
 pub struct NoObjects;
@@ -54,6 +79,10 @@ impl Objects for NoObjects {
     fn get_object(&mut self, _: &Checksum) -> Result<Option<Box<Object>>> {
         Err(ErrorKind::EmptyObjects.into())
     }
+
+    fn has_object(&mut self, _: &Checksum) -> Result<bool> {
+        Err(ErrorKind::EmptyObjects.into())
+    }
 }
 
 /// Load objects from a path.
@@ -94,25 +123,51 @@ where
     Ok(Box::new(objects))
 }
 
+/// Wrap the given objects in a local cache, if a cache directory is configured.
+fn cached<T: 'static + Objects>(
+    config: ObjectsConfig,
+    url: &Url,
+    objects: T,
+) -> Result<Box<Objects>> {
+    if let Some(cache_dir) = config.cache_dir {
+        let missing_cache_time = config.missing_cache_time.unwrap_or_else(
+            || Duration::new(60, 0),
+        );
+
+        let objects = CachedObjects::new(&cache_dir, url, missing_cache_time, objects)?;
+        return Ok(Box::new(objects));
+    }
+
+    Ok(Box::new(objects))
+}
+
 /// Load objects from an HTTP url.
 pub fn objects_from_http(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>> {
     let core = Core::new()?;
 
-    let http_objects = HttpObjects::new(url.clone(), core);
+    let mut http_objects = HttpObjects::new(url.clone(), core);
 
-    if let Some(cache_dir) = config.cache_dir {
-        let missing_cache_time = config.missing_cache_time.unwrap_or_else(
-            || Duration::new(60, 0),
-        );
+    if let Some(token) = config.token.clone() {
+        http_objects = http_objects.with_token(token);
+    }
+
+    cached(config, url, http_objects)
+}
+
+/// Load objects from an S3-compatible object store, through an `s3://bucket/prefix` url.
+///
+/// Requests are signed with credentials from the `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`
+/// environment variables, if they are set.
+pub fn objects_from_s3(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>> {
+    let core = Core::new()?;
+
+    let mut s3_objects = S3Objects::from_url(url, core)?;
 
-        return Ok(Box::new(CachedObjects::new(
-            cache_dir,
-            missing_cache_time,
-            http_objects,
-        )));
+    if let Some(credentials) = Credentials::from_env() {
+        s3_objects = s3_objects.with_credentials(credentials);
     }
 
-    Ok(Box::new(http_objects))
+    cached(config, url, s3_objects)
 }
 
 /// Load objects from an URL.
@@ -124,7 +179,8 @@ pub fn objects_from_url(config: ObjectsConfig, url: &Url) -> Result<Box<Objects>
     match first {
         "file" => objects_from_path(Path::new(url.path())).map(|o| Box::new(o) as Box<Objects>),
         "git" => objects_from_git(config, scheme, url),
-        "http" => objects_from_http(config, url),
+        "http" | "https" => objects_from_http(config, url),
+        "s3" => objects_from_s3(config, url),
         scheme => Err(format!("bad scheme: {}", scheme).into()),
     }.chain_err(|| format!("load objects from url: {}", url))
 }
@@ -0,0 +1,371 @@
+//! ## Load objects from an S3-compatible object store
+
+use super::Objects;
+use checksum::Checksum;
+use core::{BytesObject, Object};
+use errors::*;
+use futures::{Future, Stream};
+use hex_slice::HexSlice;
+use http;
+use hyper::{self, Method, Request, StatusCode};
+use hyper::header::{ContentLength, Host};
+use sigv4::{self, Credentials, SignedRequest};
+use std::io::Read;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio_core::reactor::Core;
+use url::Url;
+use url::percent_encoding::percent_decode;
+
+/// Default region, if none is specified.
+const DEFAULT_REGION: &'static str = "us-east-1";
+
+pub struct S3Objects {
+    /// Endpoint of the S3 API, using path-style addressing of buckets.
+    endpoint: Url,
+    bucket: String,
+    /// Prefix of all objects in the bucket, with a trailing slash unless empty.
+    prefix: String,
+    region: String,
+    /// Credentials to sign requests with, requests are anonymous if absent.
+    credentials: Option<Credentials>,
+    core: Core,
+}
+
+impl S3Objects {
+    /// Set up objects from an URL like `s3://bucket/prefix`.
+    ///
+    /// The `region` and `endpoint` query parameters select the region and the endpoint of the S3
+    /// API, like `s3://bucket/prefix?endpoint=http://localhost:9000` for a local MinIO.
+    /// The endpoint defaults to the one of AWS for the given region.
+    pub fn from_url(url: &Url, core: Core) -> Result<S3Objects> {
+        let bucket = url.host_str()
+            .ok_or_else(|| format!("missing bucket in url: {}", url))?
+            .to_string();
+
+        let prefix = url.path().trim_matches('/');
+
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", prefix)
+        };
+
+        let mut region = None;
+        let mut endpoint = None;
+
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "region" => region = Some(value.into_owned()),
+                "endpoint" => endpoint = Some(Url::parse(value.as_ref())?),
+                _ => {}
+            }
+        }
+
+        let region = region.unwrap_or_else(|| DEFAULT_REGION.to_string());
+
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint,
+            None => Url::parse(&format!("https://s3.{}.amazonaws.com/", region))?,
+        };
+
+        Ok(S3Objects {
+            endpoint: endpoint,
+            bucket: bucket,
+            prefix: prefix,
+            region: region,
+            credentials: None,
+            core: core,
+        })
+    }
+
+    /// Sign all requests with the given credentials.
+    pub fn with_credentials(self, credentials: Credentials) -> S3Objects {
+        S3Objects {
+            credentials: Some(credentials),
+            ..self
+        }
+    }
+
+    fn checksum_url(&self, checksum: &Checksum) -> Result<Url> {
+        let key = format!(
+            "{}/{}{}",
+            self.bucket,
+            self.prefix,
+            HexSlice::new(checksum)
+        );
+
+        Ok(self.endpoint.join(&key)?)
+    }
+
+    fn request(
+        &mut self,
+        method: Method,
+        checksum: &Checksum,
+        body: Vec<u8>,
+    ) -> Result<(Vec<u8>, StatusCode)> {
+        let url = self.checksum_url(checksum)?;
+
+        let host = url.host_str()
+            .ok_or_else(|| format!("missing host in url: {}", url))?
+            .to_string();
+
+        let host_header = match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.clone(),
+        };
+
+        let mut request = Request::new(method.clone(), url.to_string().parse::<hyper::Uri>()?);
+
+        let payload_hash = sigv4::payload_hash(&body);
+        let amz_date = sigv4::amz_date(SystemTime::now())?;
+
+        if let Some(ref credentials) = self.credentials {
+            let method = method.to_string();
+
+            let mut headers = vec![
+                ("host", host_header.as_str()),
+                ("x-amz-content-sha256", payload_hash.as_str()),
+                ("x-amz-date", amz_date.as_str()),
+            ];
+
+            if let Some(ref session_token) = credentials.session_token {
+                headers.push(("x-amz-security-token", session_token.as_str()));
+                request.headers_mut().set_raw(
+                    "x-amz-security-token",
+                    session_token.clone(),
+                );
+            }
+
+            // the path of the url is already encoded, but is encoded again while signing.
+            let path = percent_decode(url.path().as_bytes()).decode_utf8_lossy();
+
+            let signed = SignedRequest {
+                method: method.as_str(),
+                path: path.as_ref(),
+                headers: headers,
+                payload_hash: payload_hash.as_str(),
+            };
+
+            let authorization =
+                sigv4::authorization(credentials, &self.region, "s3", &amz_date, &signed);
+
+            request.headers_mut().set_raw("Authorization", authorization);
+        }
+
+        request.headers_mut().set(Host::new(host, url.port()));
+        request.headers_mut().set_raw(
+            "x-amz-content-sha256",
+            payload_hash,
+        );
+        request.headers_mut().set_raw("x-amz-date", amz_date);
+
+        if !body.is_empty() {
+            request.headers_mut().set(ContentLength(body.len() as u64));
+            request.set_body(body);
+        }
+
+        let client = http::client(&self.core.handle())?;
+
+        let work = client
+            .request(request)
+            .map_err::<Error, _>(Into::into)
+            .and_then(|res| {
+                let status = res.status().clone();
+
+                res.body()
+                    .concat2()
+                    .map_err::<Error, _>(Into::into)
+                    .map(move |body| (body.to_vec(), status))
+            });
+
+        self.core.run(work)
+    }
+}
+
+impl Objects for S3Objects {
+    fn put_object(&mut self, checksum: &Checksum, source: &mut Read, _force: bool) -> Result<()> {
+        let mut buffer = Vec::new();
+        source.read_to_end(&mut buffer)?;
+
+        let (body, status) = self.request(Method::Put, checksum, buffer)?;
+
+        if !status.is_success() {
+            return Err(http::bad_response(status, body));
+        }
+
+        Ok(())
+    }
+
+    fn get_object(&mut self, checksum: &Checksum) -> Result<Option<Box<Object>>> {
+        let name = self.checksum_url(checksum)?.to_string();
+
+        let (body, status) = self.request(Method::Get, checksum, vec![])?;
+
+        if status == StatusCode::NotFound {
+            return Ok(None);
+        }
+
+        if !status.is_success() {
+            return Err(http::bad_response(status, body));
+        }
+
+        let object = BytesObject::new(name, Arc::new(body));
+        Ok(Some(Box::new(object) as Box<Object>))
+    }
+
+    fn has_object(&mut self, checksum: &Checksum) -> Result<bool> {
+        let (body, status) = self.request(Method::Head, checksum, vec![])?;
+
+        if status == StatusCode::NotFound {
+            return Ok(false);
+        }
+
+        if !status.is_success() {
+            return Err(http::bad_response(status, body));
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::stub::serve;
+
+    #[test]
+    fn test_from_url() {
+        let checksum = Checksum::new(vec![0u8; 32]);
+        let zeros = HexSlice::new(&checksum).to_string();
+
+        let url = Url::parse("s3://bucket/some/prefix/").unwrap();
+        let objects = S3Objects::from_url(&url, Core::new().unwrap()).unwrap();
+
+        assert_eq!(
+            format!("https://s3.us-east-1.amazonaws.com/bucket/some/prefix/{}", zeros),
+            objects.checksum_url(&checksum).unwrap().to_string()
+        );
+
+        let url = Url::parse("s3://bucket?endpoint=http://localhost:9000&region=local").unwrap();
+        let objects = S3Objects::from_url(&url, Core::new().unwrap()).unwrap();
+
+        assert_eq!("local", objects.region);
+        assert_eq!(
+            format!("http://localhost:9000/bucket/{}", zeros),
+            objects.checksum_url(&checksum).unwrap().to_string()
+        );
+    }
+
+    fn credentials() -> Credentials {
+        Credentials {
+            access_key: "AKID".to_string(),
+            secret_key: "secret".to_string(),
+            session_token: None,
+        }
+    }
+
+    /// Set up objects with a prefix that has to be encoded, against the given endpoint.
+    fn objects(endpoint: &Url) -> S3Objects {
+        let url = format!("s3://bucket/some%20prefix?endpoint={}", endpoint);
+        let url = Url::parse(&url).unwrap();
+        let objects = S3Objects::from_url(&url, Core::new().unwrap()).unwrap();
+        objects.with_credentials(credentials())
+    }
+
+    /// Find the value of the given header in a request.
+    fn header<'a>(request: &'a str, name: &str) -> &'a str {
+        let prefix = format!("{}: ", name.to_lowercase());
+
+        request
+            .lines()
+            .find(|line| line.to_lowercase().starts_with(&prefix))
+            .map(|line| &line[prefix.len()..])
+            .expect("missing header")
+    }
+
+    #[test]
+    fn test_put_object() {
+        let checksum = Checksum::new(vec![0u8; 32]);
+        let (endpoint, server) = serve("/", vec![("200 OK", String::new())]);
+
+        objects(&endpoint)
+            .put_object(&checksum, &mut &b"type Foo {}"[..], false)
+            .unwrap();
+
+        let requests = server.join().unwrap();
+        let request = &requests[0];
+
+        let path = format!("/bucket/some%20prefix/{}", HexSlice::new(&checksum));
+        assert!(request.starts_with(&format!("PUT {} HTTP/1.1\r\n", path)));
+        assert!(request.ends_with("type Foo {}"));
+
+        let host = format!("{}:{}", endpoint.host_str().unwrap(), endpoint.port().unwrap());
+        let payload_hash = sigv4::payload_hash(b"type Foo {}");
+        let amz_date = header(request, "x-amz-date");
+
+        // the signed path is encoded once while signing, like it is when sent.
+        let signed_path = format!("/bucket/some prefix/{}", HexSlice::new(&checksum));
+
+        let signed = SignedRequest {
+            method: "PUT",
+            path: &signed_path,
+            headers: vec![
+                ("host", host.as_str()),
+                ("x-amz-content-sha256", payload_hash.as_str()),
+                ("x-amz-date", amz_date),
+            ],
+            payload_hash: payload_hash.as_str(),
+        };
+
+        let expected =
+            sigv4::authorization(&credentials(), DEFAULT_REGION, "s3", amz_date, &signed);
+        assert_eq!(expected, header(request, "authorization"));
+    }
+
+    #[test]
+    fn test_get_object() {
+        let checksum = Checksum::new(vec![0u8; 32]);
+        let (endpoint, server) = serve(
+            "/",
+            vec![
+                ("200 OK", "type Foo {}".to_string()),
+                ("404 Not Found", String::new()),
+            ],
+        );
+
+        let mut objects = objects(&endpoint);
+
+        let object = objects.get_object(&checksum).unwrap().expect("expected object");
+        let mut content = String::new();
+        object.read().unwrap().read_to_string(&mut content).unwrap();
+        assert_eq!("type Foo {}", content);
+
+        assert!(objects.get_object(&checksum).unwrap().is_none());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /bucket/some%20prefix/"));
+        assert!(header(&requests[0], "authorization").starts_with("AWS4-HMAC-SHA256 "));
+    }
+
+    #[test]
+    fn test_has_object() {
+        let checksum = Checksum::new(vec![0u8; 32]);
+        let (endpoint, server) = serve(
+            "/",
+            vec![
+                ("200 OK", String::new()),
+                ("404 Not Found", String::new()),
+            ],
+        );
+
+        let mut objects = objects(&endpoint);
+
+        assert!(objects.has_object(&checksum).unwrap());
+        assert!(!objects.has_object(&checksum).unwrap());
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("HEAD /bucket/some%20prefix/"));
+        assert!(requests[1].starts_with("HEAD /bucket/some%20prefix/"));
+    }
+}
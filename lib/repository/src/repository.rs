@@ -1,14 +1,44 @@
 use super::Objects;
+use checksum::Checksum;
 use core::{Object, RpPackage, RpRequiredPackage, Version};
 use errors::*;
 use index::{Deployment, Index};
+use lock::{Lock, Locked};
 use resolver::{Resolved, ResolvedByPrefix, Resolver};
 use sha256::to_sha256;
+use signing::{SigningKey, verify};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
 use update::Update;
 
+/// Problems found when checking the integrity of a repository.
+#[derive(Debug, Default)]
+pub struct Fsck {
+    /// Objects whose content does not match their checksum, as expected and actual checksums.
+    pub corrupt: Vec<(Checksum, Checksum)>,
+    /// Deployments which refer to objects that do not exist.
+    pub dangling: Vec<(RpPackage, Deployment)>,
+}
+
+impl Fsck {
+    /// Check if no problems were found.
+    pub fn is_ok(&self) -> bool {
+        self.corrupt.is_empty() && self.dangling.is_empty()
+    }
+}
+
 pub struct Repository {
     index: Box<Index>,
     objects: Box<Objects>,
+    /// Lock to resolve packages through, and to record new resolutions in.
+    lock: Option<Rc<RefCell<Lock>>>,
+    /// Key to sign published deployments with.
+    signing_key: Option<SigningKey>,
+    /// Refuse to resolve deployments which are not signed by a trusted key.
+    require_signatures: bool,
+    /// Public keys that deployments are trusted to be signed with.
+    trusted_keys: Vec<String>,
 }
 
 impl Repository {
@@ -16,7 +46,136 @@ impl Repository {
         Repository {
             index: index,
             objects: objects,
+            lock: None,
+            signing_key: None,
+            require_signatures: false,
+            trusted_keys: Vec::new(),
+        }
+    }
+
+    /// Resolve packages through the given lock.
+    ///
+    /// Requirements which are already locked resolve to exactly the locked version, anything else
+    /// is resolved as usual and then added to the lock.
+    pub fn with_lock(self, lock: Rc<RefCell<Lock>>) -> Repository {
+        Repository {
+            lock: Some(lock),
+            ..self
+        }
+    }
+
+    /// Sign all published deployments with the given key.
+    pub fn with_signing_key(self, signing_key: SigningKey) -> Repository {
+        Repository {
+            signing_key: Some(signing_key),
+            ..self
+        }
+    }
+
+    /// Only resolve deployments which are signed by one of the given public keys.
+    pub fn with_required_signatures(self, trusted_keys: Vec<String>) -> Repository {
+        Repository {
+            require_signatures: true,
+            trusted_keys: trusted_keys,
+            ..self
+        }
+    }
+
+    /// Verify the signature of the given deployment, if signatures are required.
+    fn verify_deployment(&self, package: &RpPackage, deployment: &Deployment) -> Result<()> {
+        if !self.require_signatures {
+            return Ok(());
+        }
+
+        verify(&self.trusted_keys, package, deployment)
+    }
+
+    /// Drop all deployments which are not signed by a trusted key, if signatures are required.
+    ///
+    /// Fails if deployments were matched, but none of them are trusted.
+    fn trusted_deployments(
+        &self,
+        package: &RpPackage,
+        deployments: Vec<Deployment>,
+    ) -> Result<Vec<Deployment>> {
+        let mut out = Vec::new();
+        let mut last_error = None;
+
+        for deployment in deployments {
+            match self.verify_deployment(package, &deployment) {
+                Ok(()) => out.push(deployment),
+                Err(e) => {
+                    warn!("{}@{}: ignoring: {}", package, deployment.version, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if out.is_empty() => Err(e),
+            _ => Ok(out),
+        }
+    }
+
+    /// Describe why the given deployment should be avoided, if it has been deprecated or yanked.
+    fn deprecation(deployment: &Deployment) -> Option<String> {
+        if let Some(ref message) = deployment.deprecated {
+            Some(format!("deprecated: {}", message))
+        } else if deployment.yanked {
+            Some("version has been yanked".to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Verify that the content of the given object matches the checksum it was fetched by.
+    fn verify_object(checksum: &Checksum, object: &Box<Object>) -> Result<()> {
+        let actual = to_sha256(object.read()?)?;
+
+        if actual != *checksum {
+            return Err(ErrorKind::ChecksumMismatch(checksum.clone(), actual).into());
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the exact version that a requirement is locked to.
+    fn resolve_locked(
+        &mut self,
+        package: &RpRequiredPackage,
+        locked: &Locked,
+    ) -> Result<Resolved> {
+        let deployment = self.index
+            .get_deployments(&package.package, &locked.version)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                format!(
+                    "{}: locked version {} is not in the index",
+                    package,
+                    locked.version
+                )
+            })?;
+
+        if deployment.object != locked.object {
+            return Err(
+                ErrorKind::ChecksumMismatch(locked.object.clone(), deployment.object).into(),
+            );
         }
+
+        self.verify_deployment(&package.package, &deployment)?;
+
+        let object = self.objects.get_object(&locked.object)?.ok_or_else(|| {
+            format!("missing object: {}", locked.object)
+        })?;
+
+        Self::verify_object(&locked.object, &object)?;
+
+        Ok(Resolved {
+            version: Some(locked.version.clone()),
+            object: object,
+            deprecated: Self::deprecation(&deployment),
+        })
     }
 
     pub fn update(&self) -> Result<Vec<Update>> {
@@ -47,21 +206,91 @@ impl Repository {
         let object = object.as_ref();
         let checksum = to_sha256(object.read()?)?;
 
+        let signature = self.signing_key.as_ref().map(|key| {
+            key.sign(&checksum, package, version)
+        });
+
         self.objects.put_object(
             &checksum,
             &mut object.read()?,
             force,
         )?;
-        self.index.put_version(&checksum, package, version, force)?;
+
+        self.index.put_version(
+            &checksum,
+            package,
+            version,
+            signature.as_ref(),
+            force,
+        )?;
 
         Ok(())
     }
 
+    /// Yank a published version, so that it is only resolved if required exactly.
+    pub fn yank(
+        &self,
+        package: &RpPackage,
+        version: &Version,
+        message: Option<&str>,
+    ) -> Result<()> {
+        self.index.yank(package, version, message)
+    }
+
     /// Get all deployments in this repository.
     pub fn all(&self, package: &RpPackage) -> Result<Vec<Deployment>> {
         self.index.all(package)
     }
 
+    /// Get the deployments of a specific version of a package.
+    pub fn get_deployments(
+        &self,
+        package: &RpPackage,
+        version: &Version,
+    ) -> Result<Vec<Deployment>> {
+        self.index.get_deployments(package, version)
+    }
+
+    /// Get all packages in this repository.
+    pub fn packages(&self) -> Result<Vec<RpPackage>> {
+        self.index.packages()
+    }
+
+    /// Check the integrity of all objects, and that every deployment refers to an existing
+    /// object.
+    ///
+    /// Objects are only checked for existence, they are not fetched.
+    pub fn fsck(&mut self) -> Result<Fsck> {
+        let mut fsck = Fsck::default();
+
+        fsck.corrupt = self.objects.fsck()?;
+
+        for package in self.index.packages()? {
+            for deployment in self.index.all(&package)? {
+                if !self.objects.has_object(&deployment.object)? {
+                    fsck.dangling.push((package.clone(), deployment));
+                }
+            }
+        }
+
+        Ok(fsck)
+    }
+
+    /// Remove all objects which are not referred to by any deployment in the index.
+    ///
+    /// Yanked deployments are still referred to, since they might be locked.
+    pub fn gc(&mut self) -> Result<Vec<Checksum>> {
+        let mut keep = BTreeSet::new();
+
+        for package in self.index.packages()? {
+            for deployment in self.index.all(&package)? {
+                keep.insert(deployment.object);
+            }
+        }
+
+        self.objects.gc(&keep)
+    }
+
     /// Get the object for the specific deployment.
     pub fn get_object(&mut self, deployment: &Deployment) -> Result<Option<Box<Object>>> {
         self.objects.get_object(&deployment.object)
@@ -70,15 +299,44 @@ impl Repository {
 
 impl Resolver for Repository {
     fn resolve(&mut self, package: &RpRequiredPackage) -> Result<Vec<Resolved>> {
+        let lock = self.lock.clone();
+
+        let locked = lock.as_ref().and_then(|lock| lock.borrow().get(package).cloned());
+
+        if let Some(locked) = locked {
+            let resolved = self.resolve_locked(package, &locked).chain_err(|| {
+                format!("{}: does not match lock", package)
+            })?;
+
+            return Ok(vec![resolved]);
+        }
+
         let mut out = Vec::new();
 
         let deployments = self.index.resolve(&package.package, &package.version_req)?;
+        let deployments = self.trusted_deployments(&package.package, deployments)?;
+
+        if let (Some(lock), Some(deployment)) = (lock, deployments.iter().last()) {
+            lock.borrow_mut().insert(
+                package.clone(),
+                Locked {
+                    version: deployment.version.clone(),
+                    object: deployment.object.clone(),
+                },
+            );
+        }
 
         for deployment in deployments {
-            if let Some(path) = self.objects.get_object(&deployment.object)? {
+            if let Some(object) = self.objects.get_object(&deployment.object)? {
+                // the signature only covers the checksum, so the content has to be verified.
+                if self.require_signatures {
+                    Self::verify_object(&deployment.object, &object)?;
+                }
+
                 out.push(Resolved {
+                    deprecated: Self::deprecation(&deployment),
                     version: Some(deployment.version),
-                    object: path,
+                    object: object,
                 });
             } else {
                 return Err(format!("missing object: {}", deployment.object).into());
@@ -92,3 +350,206 @@ impl Resolver for Repository {
         Err("repository does not support resolve by prefix".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use core::{BytesObject, VersionReq};
+    use index::{FileIndex, init_file_index};
+    use objects::FileObjects;
+    use signing::{generate_key, read_key};
+    use std::sync::Arc;
+
+    fn repository(dir: &TempDir) -> Repository {
+        let index_path = dir.path().join("index");
+        init_file_index(&index_path).unwrap();
+
+        let index = Box::new(FileIndex::new(&index_path).unwrap());
+        let objects = Box::new(FileObjects::new(&dir.path().join("objects")));
+        Repository::new(index, objects)
+    }
+
+    fn publish(repository: &mut Repository, version: &str, content: &str) -> Checksum {
+        let bytes = Arc::new(content.as_bytes().to_vec());
+        let object: Box<Object> = Box::new(BytesObject::new("test".to_string(), bytes));
+        let version = Version::parse(version).unwrap();
+        let package = RpPackage::parse("io.reproto");
+        repository.publish(object, &package, &version, false).unwrap();
+        to_sha256(content.as_bytes()).unwrap()
+    }
+
+    fn signing_key(dir: &TempDir, name: &str) -> SigningKey {
+        let path = dir.path().join(name);
+        generate_key(&path).unwrap();
+        read_key(&path).unwrap()
+    }
+
+    fn required(version_req: &str) -> RpRequiredPackage {
+        RpRequiredPackage::new(
+            RpPackage::parse("io.reproto"),
+            VersionReq::parse(version_req).unwrap(),
+        )
+    }
+
+    fn versions(resolved: &[Resolved]) -> Vec<Version> {
+        resolved.iter().filter_map(|r| r.version.clone()).collect()
+    }
+
+    fn deprecated(resolved: &[Resolved]) -> Option<&str> {
+        resolved.iter().last().and_then(|r| r.deprecated.as_ref()).map(String::as_str)
+    }
+
+    fn error_chain(e: &Error) -> String {
+        let causes: Vec<_> = e.iter().map(ToString::to_string).collect();
+        causes.join(": ")
+    }
+
+    #[test]
+    fn test_lock_resolution() {
+        let dir = TempDir::new("reproto-repository").unwrap();
+        let lock = Rc::new(RefCell::new(Lock::new()));
+        let mut repository = repository(&dir).with_lock(lock.clone());
+
+        let first = publish(&mut repository, "1.0.0", "type Foo {}");
+        let resolved = repository.resolve(&required("^1")).unwrap();
+        assert_eq!(vec![Version::parse("1.0.0").unwrap()], versions(&resolved));
+
+        let expected = Locked {
+            version: Version::parse("1.0.0").unwrap(),
+            object: first,
+        };
+
+        assert!(lock.borrow().is_changed());
+        assert_eq!(Some(&expected), lock.borrow().get(&required("^1")));
+
+        // newer versions are ignored, since the requirement is locked.
+        publish(&mut repository, "1.1.0", "type Bar {}");
+        let resolved = repository.resolve(&required("^1")).unwrap();
+        assert_eq!(vec![Version::parse("1.0.0").unwrap()], versions(&resolved));
+    }
+
+    #[test]
+    fn test_lock_checksum_mismatch() {
+        let dir = TempDir::new("reproto-repository").unwrap();
+        let lock = Rc::new(RefCell::new(Lock::new()));
+        let mut repository = repository(&dir).with_lock(lock.clone());
+
+        publish(&mut repository, "1.0.0", "type Foo {}");
+
+        lock.borrow_mut().insert(
+            required("^1"),
+            Locked {
+                version: Version::parse("1.0.0").unwrap(),
+                object: to_sha256(&b"type Bar {}"[..]).unwrap(),
+            },
+        );
+
+        let e = repository.resolve(&required("^1")).err().expect("expected mismatch");
+        let chain = error_chain(&e);
+        assert!(chain.contains("does not match lock"), "{}", chain);
+        assert!(chain.contains("checksum mismatch"), "{}", chain);
+    }
+
+    #[test]
+    fn test_lock_missing_version() {
+        let dir = TempDir::new("reproto-repository").unwrap();
+        let lock = Rc::new(RefCell::new(Lock::new()));
+        let mut repository = repository(&dir).with_lock(lock.clone());
+
+        let object = publish(&mut repository, "1.0.0", "type Foo {}");
+
+        lock.borrow_mut().insert(
+            required("^1"),
+            Locked {
+                version: Version::parse("1.2.0").unwrap(),
+                object: object,
+            },
+        );
+
+        let e = repository.resolve(&required("^1")).err().expect("expected error");
+        assert!(error_chain(&e).contains("is not in the index"));
+    }
+
+    #[test]
+    fn test_required_signatures() {
+        let dir = TempDir::new("reproto-repository").unwrap();
+
+        let key = signing_key(&dir, "signing.key");
+        let trusted = vec![key.public_key()];
+        let other = signing_key(&dir, "other.key").public_key();
+
+        publish(&mut repository(&dir), "1.0.0", "type Foo {}");
+
+        let mut signed = repository(&dir).with_signing_key(key);
+        publish(&mut signed, "1.1.0", "type Bar {}");
+
+        // newer unsigned versions are ignored.
+        publish(&mut repository(&dir), "1.2.0", "type Baz {}");
+
+        let mut verifying = repository(&dir).with_required_signatures(trusted);
+
+        let resolved = verifying.resolve(&required("^1")).unwrap();
+        assert_eq!(vec![Version::parse("1.1.0").unwrap()], versions(&resolved));
+
+        // the only matching version is not signed.
+        assert!(verifying.resolve(&required("=1.0.0")).is_err());
+        // nothing matches, which is not a signature error.
+        assert!(verifying.resolve(&required("^2")).unwrap().is_empty());
+
+        let mut untrusted = repository(&dir).with_required_signatures(vec![other]);
+        assert!(untrusted.resolve(&required("^1")).is_err());
+
+        let resolved = repository(&dir).resolve(&required("^1")).unwrap();
+        assert_eq!(3, resolved.len());
+    }
+
+    #[test]
+    fn test_required_signatures_tampered_object() {
+        let dir = TempDir::new("reproto-repository").unwrap();
+
+        let key = signing_key(&dir, "signing.key");
+        let trusted = vec![key.public_key()];
+
+        let mut signed = repository(&dir).with_signing_key(key);
+        let checksum = publish(&mut signed, "1.0.0", "type Foo {}");
+
+        // replace the content behind the signed checksum.
+        let mut objects = FileObjects::new(&dir.path().join("objects"));
+        objects.put_object(&checksum, &mut &b"type Bar {}"[..], true).unwrap();
+
+        let mut verifying = repository(&dir).with_required_signatures(trusted);
+        let e = verifying.resolve(&required("^1")).err().expect("expected mismatch");
+        assert!(error_chain(&e).contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn test_lock_yanked() {
+        let dir = TempDir::new("reproto-repository").unwrap();
+        let lock = Rc::new(RefCell::new(Lock::new()));
+        let mut locked = repository(&dir).with_lock(lock.clone());
+
+        publish(&mut locked, "1.0.0", "type Foo {}");
+        publish(&mut locked, "1.1.0", "type Bar {}");
+        locked.resolve(&required("^1")).unwrap();
+
+        let package = RpPackage::parse("io.reproto");
+        let version = Version::parse("1.1.0").unwrap();
+        locked.yank(&package, &version, None).unwrap();
+
+        // locked versions keep resolving after they have been yanked.
+        let resolved = locked.resolve(&required("^1")).unwrap();
+        assert_eq!(vec![version.clone()], versions(&resolved));
+        assert_eq!(Some("version has been yanked"), deprecated(&resolved));
+
+        locked.yank(&package, &version, Some("broken")).unwrap();
+        let resolved = locked.resolve(&required("^1")).unwrap();
+        assert_eq!(Some("deprecated: broken"), deprecated(&resolved));
+
+        let resolved = repository(&dir).resolve(&required("^1")).unwrap();
+        assert_eq!(vec![Version::parse("1.0.0").unwrap()], versions(&resolved));
+        assert_eq!(None, deprecated(&resolved));
+    }
+}
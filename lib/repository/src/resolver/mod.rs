@@ -14,6 +14,8 @@ pub struct Resolved {
     pub version: Option<Version>,
     /// Object found.
     pub object: Box<Object>,
+    /// Why the version found should be avoided, if it has been deprecated or yanked.
+    pub deprecated: Option<String>,
 }
 
 /// A resolved package.
@@ -33,6 +35,7 @@ impl Resolved {
         Resolved {
             version: version,
             object: object,
+            deprecated: None,
         }
     }
 }
@@ -0,0 +1,214 @@
+//! Detached ed25519 signatures of deployments.
+//!
+//! A deployment is signed by signing the package, version, and object checksum that it consists
+//! of. Keys are identified by their hex encoded public key.
+
+use checksum::Checksum;
+use core::{RpPackage, Version};
+use errors::*;
+use hex::FromHex;
+use hex_slice::HexSlice;
+use index::Deployment;
+use ring::rand::SystemRandom;
+use ring::signature::{self, Ed25519KeyPair};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use untrusted;
+
+/// Length of an ed25519 public key.
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// A detached signature of a deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    /// Hex encoded public key of the key that made the signature.
+    pub key: String,
+    /// Hex encoded ed25519 signature.
+    pub signature: String,
+}
+
+/// A key used to sign deployments when publishing.
+pub struct SigningKey {
+    key_pair: Ed25519KeyPair,
+}
+
+impl SigningKey {
+    /// The hex encoded public key, which identifies this key.
+    pub fn public_key(&self) -> String {
+        HexSlice::new(self.key_pair.public_key_bytes()).to_string()
+    }
+
+    /// Sign the deployment of the given object.
+    pub fn sign(&self, checksum: &Checksum, package: &RpPackage, version: &Version) -> Signature {
+        let signature = self.key_pair.sign(&message(checksum, package, version));
+
+        Signature {
+            key: self.public_key(),
+            signature: HexSlice::new(signature.as_ref()).to_string(),
+        }
+    }
+}
+
+/// The message which is signed for a deployment.
+fn message(checksum: &Checksum, package: &RpPackage, version: &Version) -> Vec<u8> {
+    format!("{}@{}:{}", package, version, checksum).into_bytes()
+}
+
+/// Parse a hex encoded public key.
+pub fn parse_public_key(key: &str) -> Result<Vec<u8>> {
+    let bytes: Vec<u8> = FromHex::from_hex(key).map_err(|e| {
+        format!("bad public key: {}: {}", key, e)
+    })?;
+
+    if bytes.len() != PUBLIC_KEY_LEN {
+        return Err(
+            format!("bad public key: {}: expected {} bytes", key, PUBLIC_KEY_LEN).into(),
+        );
+    }
+
+    Ok(bytes)
+}
+
+/// Generate a new signing key, and write it to the given path.
+///
+/// Returns the public key of the generated key.
+pub fn generate_key(path: &Path) -> Result<String> {
+    if path.exists() {
+        return Err(format!("{}: already exists", path.display()).into());
+    }
+
+    let rng = SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(
+        |_| "failed to generate key",
+    )?;
+
+    let key = key_from_pkcs8(&pkcs8)?;
+
+    if let Some(parent) = path.parent() {
+        if !parent.is_dir() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut f = File::create(path)?;
+    restrict_permissions(&f)?;
+    writeln!(f, "{}", HexSlice::new(&pkcs8[..]))?;
+
+    Ok(key.public_key())
+}
+
+/// Read a signing key from the given path.
+pub fn read_key(path: &Path) -> Result<SigningKey> {
+    let mut f = File::open(path).map_err(|e| {
+        format!("failed to open {}: {}", path.display(), e)
+    })?;
+
+    let mut content = String::new();
+    f.read_to_string(&mut content)?;
+
+    let pkcs8: Vec<u8> = FromHex::from_hex(content.trim()).map_err(|e| {
+        format!("{}: bad key: {}", path.display(), e)
+    })?;
+
+    key_from_pkcs8(&pkcs8).chain_err(|| format!("{}: bad key", path.display()))
+}
+
+fn key_from_pkcs8(pkcs8: &[u8]) -> Result<SigningKey> {
+    let key_pair = Ed25519KeyPair::from_pkcs8(untrusted::Input::from(pkcs8))
+        .map_err(|_| "not an ed25519 key")?;
+
+    Ok(SigningKey { key_pair: key_pair })
+}
+
+#[cfg(unix)]
+fn restrict_permissions(f: &File) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    f.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_: &File) -> Result<()> {
+    Ok(())
+}
+
+/// Verify that a deployment is signed by one of the trusted keys.
+pub fn verify(trusted: &[String], package: &RpPackage, deployment: &Deployment) -> Result<()> {
+    let version = &deployment.version;
+
+    let signature = match deployment.signature {
+        Some(ref signature) => signature,
+        None => return Err(ErrorKind::Unsigned(package.clone(), version.clone()).into()),
+    };
+
+    if !trusted.iter().any(|key| *key == signature.key) {
+        return Err(
+            ErrorKind::UntrustedKey(package.clone(), version.clone(), signature.key.clone())
+                .into(),
+        );
+    }
+
+    let public_key = parse_public_key(&signature.key)?;
+
+    let bad_signature = || ErrorKind::BadSignature(package.clone(), version.clone());
+
+    let bytes: Vec<u8> = FromHex::from_hex(signature.signature.as_str()).map_err(
+        |_| bad_signature(),
+    )?;
+
+    let message = message(&deployment.object, package, version);
+
+    signature::verify(
+        &signature::ED25519,
+        untrusted::Input::from(&public_key),
+        untrusted::Input::from(&message),
+        untrusted::Input::from(&bytes),
+    ).map_err(|_| bad_signature())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_key() -> SigningKey {
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        key_from_pkcs8(&pkcs8).unwrap()
+    }
+
+    fn deployment(key: &SigningKey, package: &RpPackage) -> Deployment {
+        let version = Version::parse("1.0.0").unwrap();
+        let checksum = Checksum::new(vec![0u8; 32]);
+        let signature = key.sign(&checksum, package, &version);
+        Deployment::new(version, checksum).with_signature(signature)
+    }
+
+    #[test]
+    fn test_verify() {
+        let key = test_key();
+        let package = RpPackage::parse("io.reproto.toystore");
+        let deployment = deployment(&key, &package);
+
+        assert!(verify(&[key.public_key()], &package, &deployment).is_ok());
+        // not trusted
+        assert!(verify(&[], &package, &deployment).is_err());
+        // signed for a different package
+        let other = RpPackage::parse("io.reproto.other");
+        assert!(verify(&[key.public_key()], &other, &deployment).is_err());
+    }
+
+    #[test]
+    fn test_unsigned() {
+        let key = test_key();
+        let package = RpPackage::parse("io.reproto.toystore");
+        let deployment = Deployment::new(
+            Version::parse("1.0.0").unwrap(),
+            Checksum::new(vec![0u8; 32]),
+        );
+
+        assert!(verify(&[key.public_key()], &package, &deployment).is_err());
+    }
+}
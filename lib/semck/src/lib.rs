@@ -3,7 +3,7 @@ extern crate reproto_core;
 use self::Component::*;
 use self::Violation::*;
 use reproto_core::{ErrorPos, Loc, RpChannel, RpDecl, RpEndpoint, RpField, RpFile, RpName, RpReg,
-                   RpType, RpVariant, Version};
+                   RpType, RpValue, RpVariant, Version};
 use reproto_core::errors::*;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -50,6 +50,8 @@ pub enum Violation {
     AddRequiredField(Component, ErrorPos),
     /// Field modifier changed.
     FieldModifierChange(Component, ErrorPos, ErrorPos),
+    /// Default value of field changed.
+    FieldDefaultChange(Component, Option<RpValue>, ErrorPos, Option<RpValue>, ErrorPos),
     /// Endpoint added.
     AddEndpoint(Component, ErrorPos),
     /// Endpoint removed.
@@ -60,6 +62,41 @@ pub enum Violation {
     EndpointResponseChange(Component, Option<RpChannel>, ErrorPos, Option<RpChannel>, ErrorPos),
 }
 
+impl Violation {
+    /// Describe the change, and where it was made.
+    pub fn describe(&self) -> (String, &ErrorPos) {
+        match *self {
+            DeclRemoved(_, ref pos) => ("declaration removed".to_string(), pos),
+            DeclAdded(_, ref pos) => ("declaration added".to_string(), pos),
+            RemoveField(_, ref pos) => ("field removed".to_string(), pos),
+            RemoveVariant(_, ref pos) => ("variant removed".to_string(), pos),
+            AddField(_, ref pos) => ("field added".to_string(), pos),
+            AddVariant(_, ref pos) => ("variant added".to_string(), pos),
+            FieldTypeChange(_, ref from, _, ref to, ref pos) => {
+                (format!("type changed from `{}` to `{}`", from, to), pos)
+            }
+            FieldNameChange(_, ref from, _, ref to, ref pos) => {
+                (format!("name changed from `{}` to `{}`", from, to), pos)
+            }
+            VariantOrdinalChange(_, ref from, _, ref to, ref pos) => {
+                (format!("ordinal changed from `{}` to `{}`", from, to), pos)
+            }
+            FieldRequiredChange(_, _, ref pos) => ("field changed to be required".to_string(), pos),
+            AddRequiredField(_, ref pos) => ("required field added".to_string(), pos),
+            FieldModifierChange(_, _, ref pos) => ("field modifier changed".to_string(), pos),
+            FieldDefaultChange(_, _, _, _, ref pos) => ("default value changed".to_string(), pos),
+            AddEndpoint(_, ref pos) => ("endpoint added".to_string(), pos),
+            RemoveEndpoint(_, ref pos) => ("endpoint removed".to_string(), pos),
+            EndpointRequestChange(_, _, _, _, ref pos) => {
+                ("request type changed".to_string(), pos)
+            }
+            EndpointResponseChange(_, _, _, _, ref pos) => {
+                ("response type changed".to_string(), pos)
+            }
+        }
+    }
+}
+
 fn fields(reg: &RpReg) -> Vec<&Loc<RpField>> {
     use self::RpReg::*;
 
@@ -268,6 +305,20 @@ fn common_check_field(
         ));
     }
 
+    // changing the default changes the meaning of existing data.
+    if to_field.default != from_field.default {
+        let from_pos = from_field.default.as_ref().map(Loc::pos).unwrap_or(from_field.pos());
+        let to_pos = to_field.default.as_ref().map(Loc::pos).unwrap_or(to_field.pos());
+
+        violations.push(FieldDefaultChange(
+            component.clone(),
+            from_field.default.as_ref().map(|d| d.value().clone()),
+            from_pos.into(),
+            to_field.default.as_ref().map(|d| d.value().clone()),
+            to_pos.into(),
+        ));
+    }
+
     Ok(())
 }
 
@@ -467,6 +518,13 @@ fn check_patch(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
     }
 }
 
+/// Find all changes between two files, regardless of their versions.
+///
+/// Since no change at all is permitted in a patch release, these are all the patch violations.
+pub fn changes(from: &RpFile, to: &RpFile) -> Result<Vec<Violation>> {
+    check_patch(from, to)
+}
+
 pub fn check(from: (&Version, &RpFile), to: (&Version, &RpFile)) -> Result<Vec<Violation>> {
     let (from_version, from_file) = from;
     let (to_version, to_file) = to;
@@ -483,3 +541,131 @@ pub fn check(from: (&Version, &RpFile), to: (&Version, &RpFile)) -> Result<Vec<V
 
     Ok(vec![])
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate reproto_backend;
+
+    use self::reproto_backend::Environment;
+    use super::*;
+    use reproto_core::{RpPackage, RpVersionedPackage};
+    use std::io::Read;
+
+    fn file(content: &str) -> RpFile {
+        let package = RpVersionedPackage::new(RpPackage::parse("test"), None);
+        Environment::empty().load_str(content, &package).unwrap()
+    }
+
+    /// Names of the kinds of changes between the two specifications, sorted.
+    fn changed(from: &str, to: &str) -> Vec<String> {
+        let mut kinds: Vec<String> = changes(&file(from), &file(to))
+            .unwrap()
+            .iter()
+            .map(|v| format!("{:?}", v).split('(').next().unwrap().to_string())
+            .collect();
+
+        kinds.sort();
+        kinds
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let spec = "type Foo { a: string; }\nenum E as string { A; }\nservice S { get() -> Foo; }";
+        assert!(changed(spec, spec).is_empty());
+    }
+
+    #[test]
+    fn test_field_changes() {
+        let from = "type Foo { a: string; b: string; c?: string; d: u32 = 1; }";
+        let to = "type Foo { a: u32; c: string; d: u32 = 2; e?: string; }";
+
+        let expected = vec![
+            "AddField",
+            "FieldDefaultChange",
+            "FieldModifierChange",
+            "FieldTypeChange",
+            "RemoveField",
+        ];
+
+        assert_eq!(expected, changed(from, to));
+    }
+
+    #[test]
+    fn test_decl_changes() {
+        let expected = vec!["DeclAdded", "DeclRemoved"];
+        assert_eq!(expected, changed("type Foo {}", "type Bar {}"));
+    }
+
+    #[test]
+    fn test_variant_changes() {
+        let from = "enum E as string { A as \"a\"; B; }";
+        let to = "enum E as string { A as \"b\"; C; }";
+
+        let expected = vec!["AddVariant", "RemoveVariant", "VariantOrdinalChange"];
+        assert_eq!(expected, changed(from, to));
+    }
+
+    #[test]
+    fn test_endpoint_changes() {
+        let from = "type Foo {}\nservice S { get() -> Foo; put(Foo); }";
+        let to = "type Foo {}\nservice S { get(Foo) -> Foo; delete(); }";
+
+        let expected = vec!["AddEndpoint", "EndpointRequestChange", "RemoveEndpoint"];
+        assert_eq!(expected, changed(from, to));
+    }
+
+    /// Descriptions of the changes between the two specifications, and the source they point to.
+    fn described(from: &str, to: &str) -> Vec<(String, String)> {
+        let from = file(from);
+        let to = file(to);
+
+        let mut described: Vec<(String, String)> = changes(&from, &to)
+            .unwrap()
+            .iter()
+            .map(|v| {
+                let (message, pos) = v.describe();
+                let mut content = String::new();
+                pos.object.read().unwrap().read_to_string(&mut content).unwrap();
+                (message, content[pos.start..pos.end].to_string())
+            })
+            .collect();
+
+        described.sort();
+        described
+    }
+
+    #[test]
+    fn test_describe() {
+        let from = "type Foo { a: string; b: u32; }\nenum E as string { A as \"a\"; }";
+        let to = "type Foo { a: u32; }\nenum E as string { A as \"b\"; }";
+
+        let described = described(from, to);
+        let messages: Vec<&str> = described.iter().map(|d| d.0.as_str()).collect();
+
+        let expected = vec![
+            "field removed",
+            "ordinal changed from `\"a\"` to `\"b\"`",
+            "type changed from `string` to `u32`",
+        ];
+
+        assert_eq!(expected, messages);
+
+        // removals point into the old specification, other changes into the new one.
+        assert!(described[0].1.starts_with("b: u32"), "{:?}", described);
+        assert!(described[1].1.starts_with("A as \"b\""), "{:?}", described);
+        assert!(described[2].1.starts_with("a: u32"), "{:?}", described);
+    }
+
+    #[test]
+    fn test_changes_ignore_versions() {
+        let from = file("type Foo { a: string; }");
+        let to = file("type Foo { a?: string; b?: string; }");
+
+        let v1 = Version::parse("1.0.0").unwrap();
+        let v2 = Version::parse("2.0.0").unwrap();
+
+        // a major version permits any change, but they are still changes.
+        assert!(check((&v1, &from), (&v2, &to)).unwrap().is_empty());
+        assert_eq!(2, changes(&from, &to).unwrap().len());
+    }
+}
@@ -0,0 +1,234 @@
+//! Authentication and access control for requests.
+
+use config::Config;
+use reproto_core::RpPackage;
+
+/// Scopes that can be granted to a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Permits downloading objects.
+    Read,
+    /// Permits uploading objects.
+    Write,
+}
+
+/// The outcome of authorizing a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Granted,
+    /// No valid credentials were provided.
+    Unauthorized,
+    /// Credentials were provided, but do not grant access.
+    Forbidden,
+}
+
+/// Authorize requests, based on the bearer token provided with them.
+pub trait Authenticator: Send + Sync {
+    /// Check if the given token grants `scope`.
+    ///
+    /// `package` is the package being operated on, if the operation concerns a single package.
+    fn authorize(&self, token: Option<&str>, scope: Scope, package: Option<&RpPackage>) -> Access;
+}
+
+/// Authenticator that grants everything to everyone.
+pub struct NoAuthenticator;
+
+impl Authenticator for NoAuthenticator {
+    fn authorize(&self, _: Option<&str>, _: Scope, _: Option<&RpPackage>) -> Access {
+        Access::Granted
+    }
+}
+
+/// A configured bearer token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Token {
+    /// The secret token, as sent by clients.
+    pub token: String,
+    /// Scopes granted to the token.
+    pub scopes: Vec<Scope>,
+    /// Package prefixes that the token is restricted to.
+    ///
+    /// Only applies to operations on a single package, like publishing or yanking a version.
+    /// Objects are content-addressed and only become part of a package once published, so they
+    /// can be uploaded with any token that has the `write` scope.
+    ///
+    /// The token is not restricted to any packages if empty.
+    #[serde(default)]
+    pub packages: Vec<String>,
+}
+
+impl Token {
+    fn permits(&self, package: &RpPackage) -> bool {
+        if self.packages.is_empty() {
+            return true;
+        }
+
+        self.packages.iter().any(|prefix| {
+            let prefix = RpPackage::parse(prefix);
+            package.parts.starts_with(&prefix.parts)
+        })
+    }
+}
+
+/// Authenticator that grants access based on a static set of tokens.
+pub struct TokenAuthenticator {
+    tokens: Vec<Token>,
+    anonymous_read: bool,
+}
+
+impl TokenAuthenticator {
+    pub fn new(tokens: Vec<Token>, anonymous_read: bool) -> TokenAuthenticator {
+        TokenAuthenticator {
+            tokens: tokens,
+            anonymous_read: anonymous_read,
+        }
+    }
+}
+
+impl Authenticator for TokenAuthenticator {
+    fn authorize(&self, token: Option<&str>, scope: Scope, package: Option<&RpPackage>) -> Access {
+        if scope == Scope::Read && self.anonymous_read {
+            return Access::Granted;
+        }
+
+        let token = match token {
+            Some(token) => token,
+            None => return Access::Unauthorized,
+        };
+
+        let token = match self.tokens.iter().find(|t| constant_time_eq(&t.token, token)) {
+            Some(token) => token,
+            None => return Access::Unauthorized,
+        };
+
+        if !token.scopes.contains(&scope) {
+            return Access::Forbidden;
+        }
+
+        if let Some(package) = package {
+            if !token.permits(package) {
+                return Access::Forbidden;
+            }
+        }
+
+        Access::Granted
+    }
+}
+
+/// Compare two strings without leaking where they differ through timing.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Build the authenticator described by the configuration.
+///
+/// Authentication is disabled if no tokens are configured.
+pub fn authenticator_from_config(config: &Config) -> Box<Authenticator> {
+    if config.tokens.is_empty() {
+        return Box::new(NoAuthenticator);
+    }
+
+    Box::new(TokenAuthenticator::new(
+        config.tokens.clone(),
+        config.anonymous_read,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(scopes: Vec<Scope>, packages: Vec<&str>) -> Token {
+        Token {
+            token: "secret".to_string(),
+            scopes: scopes,
+            packages: packages.into_iter().map(ToOwned::to_owned).collect(),
+        }
+    }
+
+    #[test]
+    fn test_scopes() {
+        let auth = TokenAuthenticator::new(vec![token(vec![Scope::Read], vec![])], false);
+
+        assert_eq!(Access::Unauthorized, auth.authorize(None, Scope::Read, None));
+        assert_eq!(Access::Unauthorized, auth.authorize(Some("wrong"), Scope::Read, None));
+        assert_eq!(Access::Granted, auth.authorize(Some("secret"), Scope::Read, None));
+        assert_eq!(Access::Forbidden, auth.authorize(Some("secret"), Scope::Write, None));
+    }
+
+    #[test]
+    fn test_anonymous_read() {
+        let auth = TokenAuthenticator::new(vec![token(vec![Scope::Write], vec![])], true);
+
+        assert_eq!(Access::Granted, auth.authorize(None, Scope::Read, None));
+        assert_eq!(Access::Unauthorized, auth.authorize(None, Scope::Write, None));
+    }
+
+    #[test]
+    fn test_packages() {
+        let tokens = vec![token(vec![Scope::Write], vec!["io.reproto"])];
+        let auth = TokenAuthenticator::new(tokens, false);
+
+        let allowed = RpPackage::parse("io.reproto.toystore");
+        let denied = RpPackage::parse("io.other");
+
+        let access = auth.authorize(Some("secret"), Scope::Write, Some(&allowed));
+        assert_eq!(Access::Granted, access);
+
+        let access = auth.authorize(Some("secret"), Scope::Write, Some(&denied));
+        assert_eq!(Access::Forbidden, access);
+    }
+
+    #[test]
+    fn test_package_prefix_matching() {
+        let tokens = vec![token(vec![Scope::Write], vec!["io.reproto", "com.example.api"])];
+        let auth = TokenAuthenticator::new(tokens, false);
+
+        let check = |package: &str| {
+            let package = RpPackage::parse(package);
+            auth.authorize(Some("secret"), Scope::Write, Some(&package))
+        };
+
+        assert_eq!(Access::Granted, check("io.reproto"));
+        assert_eq!(Access::Granted, check("com.example.api.v1"));
+        // prefixes match whole package components.
+        assert_eq!(Access::Forbidden, check("io.reprotox"));
+        assert_eq!(Access::Forbidden, check("io"));
+        assert_eq!(Access::Forbidden, check("com.example"));
+    }
+
+    #[test]
+    fn test_packages_require_scope() {
+        let tokens = vec![token(vec![Scope::Read], vec!["io.reproto"])];
+        let auth = TokenAuthenticator::new(tokens, false);
+        let package = RpPackage::parse("io.reproto");
+
+        let access = auth.authorize(Some("secret"), Scope::Write, Some(&package));
+        assert_eq!(Access::Forbidden, access);
+    }
+
+    #[test]
+    fn test_packages_without_package() {
+        let tokens = vec![token(vec![Scope::Write], vec!["io.reproto"])];
+        let auth = TokenAuthenticator::new(tokens, false);
+
+        // operations that don't concern a single package, like uploading objects.
+        let access = auth.authorize(Some("secret"), Scope::Write, None);
+        assert_eq!(Access::Granted, access);
+    }
+
+    #[test]
+    fn test_no_authenticator() {
+        let package = RpPackage::parse("io.reproto");
+        let access = NoAuthenticator.authorize(None, Scope::Write, Some(&package));
+        assert_eq!(Access::Granted, access);
+    }
+}
@@ -1,3 +1,4 @@
+use super::auth::Token;
 use super::errors::*;
 use std::fs::File;
 use std::io::Read;
@@ -12,9 +13,19 @@ pub struct Config {
     /// Objects path.
     #[serde(default = "default_objects")]
     pub objects: PathBuf,
+    /// Index path.
+    #[serde(default = "default_index")]
+    pub index: PathBuf,
     /// Max file size permitted during upload.
     #[serde(default = "default_max_file_size")]
     pub max_file_size: u64,
+    /// Tokens which grant access to the server.
+    /// Authentication is disabled if no tokens are configured.
+    #[serde(default)]
+    pub tokens: Vec<Token>,
+    /// Permit reading without a token.
+    #[serde(default = "default_anonymous_read")]
+    pub anonymous_read: bool,
 }
 
 fn default_listen_address() -> String {
@@ -25,16 +36,27 @@ fn default_objects() -> PathBuf {
     Path::new("./objects").to_owned()
 }
 
+fn default_index() -> PathBuf {
+    Path::new("./index").to_owned()
+}
+
 fn default_max_file_size() -> u64 {
     10000000u64
 }
 
+fn default_anonymous_read() -> bool {
+    true
+}
+
 impl Default for Config {
     fn default() -> Config {
         Config {
             listen_address: default_listen_address(),
             objects: default_objects(),
+            index: default_index(),
             max_file_size: default_max_file_size(),
+            tokens: Vec::new(),
+            anonymous_read: default_anonymous_read(),
         }
     }
 }
@@ -13,6 +13,7 @@ error_chain!{
         AddParseError(::std::net::AddrParseError);
         Hyper(::hyper::Error);
         VarError(::std::env::VarError);
+        Json(::serde_json::Error);
     }
 
     errors {
@@ -20,6 +21,14 @@ error_chain!{
             description("posion error")
         }
 
+        Unauthorized {
+            description("unauthorized")
+        }
+
+        Forbidden {
+            description("forbidden")
+        }
+
         BadRequest(message: &'static str) {
             description("bad request")
             display("bad request: {}", message)
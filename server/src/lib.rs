@@ -11,10 +11,14 @@ extern crate futures_cpupool;
 extern crate hyper;
 extern crate reproto_repository;
 extern crate reproto_core;
+extern crate serde;
+extern crate serde_json;
 extern crate tempfile;
 extern crate toml;
+extern crate url;
 
 mod io;
+pub mod auth;
 pub mod errors;
 pub mod reproto_service;
 pub mod config;
@@ -8,7 +8,8 @@ extern crate reproto_repository;
 
 use futures_cpupool::CpuPool;
 use hyper::server::Http;
-use reproto_repository::objects_from_path;
+use reproto_repository::{index_from_path, init_file_index, objects_from_path};
+use reproto_server::auth::{Authenticator, authenticator_from_config};
 use reproto_server::errors::*;
 use reproto_server::reproto_service;
 use std::env;
@@ -37,18 +38,25 @@ fn entry() -> Result<()> {
     };
 
     let listen_address = config.listen_address.parse()?;
+    let authenticator: Arc<Authenticator> = Arc::from(authenticator_from_config(&config));
     let objects = config.objects;
+    let index = config.index;
     let max_file_size = config.max_file_size;
 
     let pool = Arc::new(CpuPool::new_num_cpus());
     let setup_pool = pool.clone();
     let objects = Arc::new(Mutex::new(objects_from_path(objects)?));
 
+    init_file_index(&index)?;
+    let index = Arc::new(Mutex::new(index_from_path(&index)?));
+
     let setup = move || {
         Ok(reproto_service::ReprotoService {
             max_file_size: max_file_size,
             pool: setup_pool.clone(),
             objects: objects.clone(),
+            index: index.clone(),
+            authenticator: authenticator.clone(),
         })
     };
 
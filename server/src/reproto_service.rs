@@ -1,22 +1,44 @@
+use auth::{Access, Authenticator, Scope};
 use errors::*;
 use errors::ErrorKind::*;
 use flate2::FlateReadExt;
+use futures::Stream;
 use futures::future::{Future, ok};
 use futures_cpupool::CpuPool;
 use hyper::{self, Method, StatusCode};
-use hyper::header::{ContentEncoding, ContentLength, ContentType, Encoding, Headers};
+use hyper::header::{Authorization, Bearer, ContentEncoding, ContentLength, ContentType, Encoding,
+                    Headers};
 use hyper::mime;
 use hyper::server::{Request, Response, Service};
 use io;
-use reproto_repository::{Checksum, FileObjects, Objects, to_checksum};
+use reproto_core::{RpPackage, Version, VersionReq};
+use reproto_repository::{Checksum, FileIndex, FileObjects, Index, Objects, Signature,
+                          to_checksum};
+use serde::Serialize;
+use serde_json;
 use std::fs::File;
 use std::io::{Seek, SeekFrom};
 use std::io::Read;
 use std::sync::{Arc, Mutex};
 use tempfile;
+use url::form_urlencoded;
 
 const CHECKSUM_MISMATCH: &'static str = "checksum mismatch";
 const BAD_OBJECT_ID: &'static str = "bad object id";
+const BAD_PACKAGE: &'static str = "bad package";
+const BAD_VERSION: &'static str = "bad version";
+const BAD_VERSION_REQ: &'static str = "bad version requirement";
+const ALREADY_PUBLISHED: &'static str = "already published";
+const MISSING_OBJECT: &'static str = "object does not exist";
+
+/// Body of a request to publish a new version.
+#[derive(Debug, Deserialize)]
+struct PutVersion {
+    object: Checksum,
+    /// Signature of the deployment, stored alongside it in the index.
+    #[serde(default)]
+    signature: Option<Signature>,
+}
 
 /// ## Read the contents of the file into a byte-vector
 fn read_contents<'a, R: AsMut<Read + 'a>>(mut reader: R) -> Result<Vec<u8>> {
@@ -29,6 +51,8 @@ pub struct ReprotoService {
     pub max_file_size: u64,
     pub pool: Arc<CpuPool>,
     pub objects: Arc<Mutex<FileObjects>>,
+    pub index: Arc<Mutex<FileIndex>>,
+    pub authenticator: Arc<Authenticator>,
 }
 
 type EncodingFn = fn(&File) -> Result<Box<Read>>;
@@ -59,14 +83,84 @@ impl ReprotoService {
         Self::no_encoding
     }
 
+    /// Check that the request is permitted to perform an operation with the given scope.
+    fn authorize(&self, req: &Request, scope: Scope, package: Option<&RpPackage>) -> Result<()> {
+        let token = req.headers().get::<Authorization<Bearer>>().map(
+            |h| h.0.token.as_str(),
+        );
+
+        match self.authenticator.authorize(token, scope, package) {
+            Access::Granted => Ok(()),
+            Access::Unauthorized => Err(Unauthorized.into()),
+            Access::Forbidden => Err(Forbidden.into()),
+        }
+    }
+
     fn not_found() -> Response {
         Response::new().with_status(StatusCode::NotFound)
     }
 
-    fn get_objects<'a, I>(&self, path: I) -> Result<Box<Future<Item = Response, Error = Error>>>
+    fn json_response<T: Serialize>(value: &T) -> Result<Response> {
+        let body = serde_json::to_vec(value)?;
+
+        Ok(
+            Response::new()
+                .with_status(StatusCode::Ok)
+                .with_header(ContentLength(body.len() as u64))
+                .with_header(ContentType::json())
+                .with_body(body),
+        )
+    }
+
+    /// Get the value of a query parameter.
+    fn query_param(req: &Request, name: &str) -> Option<String> {
+        let query = match req.query() {
+            Some(query) => query,
+            None => return None,
+        };
+
+        form_urlencoded::parse(query.as_bytes())
+            .find(|&(ref key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    fn parse_package(input: &str) -> Result<RpPackage> {
+        let package = RpPackage::parse(input);
+
+        if package.parts.iter().any(String::is_empty) {
+            return Err(BadRequest(BAD_PACKAGE).into());
+        }
+
+        Ok(package)
+    }
+
+    fn parse_version(input: &str) -> Result<Version> {
+        Ok(Version::parse(input).map_err(|_| BadRequest(BAD_VERSION))?)
+    }
+
+    /// Check that the request declares a content length which is not too large.
+    fn check_content_length(&self, req: &Request) -> Result<()> {
+        if let Some(len) = req.headers().get::<ContentLength>() {
+            if len.0 > self.max_file_size {
+                return Err(BadRequest("file too large").into());
+            }
+        } else {
+            return Err(BadRequest("missing content-length").into());
+        }
+
+        Ok(())
+    }
+
+    fn get_objects<'a, I>(
+        &self,
+        req: Request,
+        path: I,
+    ) -> Result<Box<Future<Item = Response, Error = Error>>>
     where
         I: IntoIterator<Item = &'a str>,
     {
+        self.authorize(&req, Scope::Read, None)?;
+
         let id = if let Some(id) = path.into_iter().next() {
             id
         } else {
@@ -157,6 +251,9 @@ impl ReprotoService {
     where
         I: IntoIterator<Item = &'a str>,
     {
+        // objects are content-addressed and not associated with any package.
+        self.authorize(&req, Scope::Write, None)?;
+
         let id = if let Some(id) = path.into_iter().next() {
             id
         } else {
@@ -167,13 +264,7 @@ impl ReprotoService {
             |_| BadRequest(BAD_OBJECT_ID),
         )?;
 
-        if let Some(len) = req.headers().get::<ContentLength>() {
-            if len.0 > self.max_file_size {
-                return Err(BadRequest("file too large").into());
-            }
-        } else {
-            return Err(BadRequest("missing content-length").into());
-        }
+        self.check_content_length(&req)?;
 
         let encoding = Self::pick_encoding(req.headers());
 
@@ -182,6 +273,178 @@ impl ReprotoService {
         Ok(self.put_uploaded_object(body, checksum, encoding))
     }
 
+    /// Query the index.
+    ///
+    /// * `/index` lists all packages.
+    /// * `/index/<package>` lists all deployments of a package, or the ones matching the
+    ///   `version_req` query parameter.
+    /// * `/index/<package>/<version>` lists the deployments of a single version.
+    fn get_index<'a, I>(
+        &self,
+        req: Request,
+        path: I,
+    ) -> Result<Box<Future<Item = Response, Error = Error>>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.authorize(&req, Scope::Read, None)?;
+
+        let parts: Vec<&str> = path.into_iter().filter(|p| !p.is_empty()).collect();
+
+        let package = match parts.get(0) {
+            Some(package) => Some(Self::parse_package(package)?),
+            None => None,
+        };
+
+        let version = match parts.get(1) {
+            Some(version) => Some(Self::parse_version(version)?),
+            None => None,
+        };
+
+        let version_req = match Self::query_param(&req, "version_req") {
+            Some(version_req) => {
+                Some(VersionReq::parse(&version_req).map_err(
+                    |_| BadRequest(BAD_VERSION_REQ),
+                )?)
+            }
+            None => None,
+        };
+
+        if parts.len() > 2 {
+            return Ok(Box::new(ok(Self::not_found())));
+        }
+
+        let index = self.index.clone();
+
+        // No async I/O, use pool
+        Ok(Box::new(self.pool.spawn_fn(move || {
+            let index = index.lock().map_err(|_| PoisonError)?;
+
+            match (package, version, version_req) {
+                (None, _, _) => Self::json_response(&index.packages()?),
+                (Some(package), Some(version), _) => {
+                    Self::json_response(&index.get_deployments(&package, &version)?)
+                }
+                (Some(package), None, Some(version_req)) => {
+                    Self::json_response(&index.resolve(&package, &version_req)?)
+                }
+                (Some(package), None, None) => Self::json_response(&index.all(&package)?),
+            }
+        })))
+    }
+
+    /// Publish a new version of a package, through `/index/<package>/<version>`.
+    fn put_index<'a, I>(
+        &self,
+        req: Request,
+        path: I,
+    ) -> Result<Box<Future<Item = Response, Error = Error>>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let parts: Vec<&str> = path.into_iter().filter(|p| !p.is_empty()).collect();
+
+        if parts.len() != 2 {
+            return Ok(Box::new(ok(Self::not_found())));
+        }
+
+        let package = Self::parse_package(parts[0])?;
+        let version = Self::parse_version(parts[1])?;
+
+        self.authorize(&req, Scope::Write, Some(&package))?;
+        self.check_content_length(&req)?;
+
+        let force = Self::query_param(&req, "force")
+            .map(|value| value == "true")
+            .unwrap_or(false);
+
+        let pool = self.pool.clone();
+        let index = self.index.clone();
+        let objects = self.objects.clone();
+
+        let put = req.body().concat2().map_err::<Error, _>(Into::into).and_then(
+            move |body| {
+                let body = body.to_vec();
+
+                pool.spawn_fn(move || {
+                    let put: PutVersion = serde_json::from_slice(&body).map_err(
+                        |_| BadRequest("bad request body"),
+                    )?;
+
+                    let exists = objects
+                        .lock()
+                        .map_err(|_| PoisonError)?
+                        .get_object(&put.object)?
+                        .is_some();
+
+                    if !exists {
+                        return Err(BadRequest(MISSING_OBJECT).into());
+                    }
+
+                    let index = index.lock().map_err(|_| PoisonError)?;
+
+                    if !force && !index.get_deployments(&package, &version)?.is_empty() {
+                        return Ok(Response::new().with_body(ALREADY_PUBLISHED).with_status(
+                            StatusCode::Conflict,
+                        ));
+                    }
+
+                    info!("Publishing: {}@{}: {}", package, version, put.object);
+
+                    index.put_version(
+                        &put.object,
+                        &package,
+                        &version,
+                        put.signature.as_ref(),
+                        force,
+                    )?;
+                    Ok(Response::new().with_status(StatusCode::Ok))
+                })
+            },
+        );
+
+        Ok(Box::new(put))
+    }
+
+    /// Yank a published version of a package, through `/index/<package>/<version>`.
+    ///
+    /// An optional deprecation message is provided through the `message` query parameter.
+    fn delete_index<'a, I>(
+        &self,
+        req: Request,
+        path: I,
+    ) -> Result<Box<Future<Item = Response, Error = Error>>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let parts: Vec<&str> = path.into_iter().filter(|p| !p.is_empty()).collect();
+
+        if parts.len() != 2 {
+            return Ok(Box::new(ok(Self::not_found())));
+        }
+
+        let package = Self::parse_package(parts[0])?;
+        let version = Self::parse_version(parts[1])?;
+
+        self.authorize(&req, Scope::Write, Some(&package))?;
+
+        let message = Self::query_param(&req, "message");
+        let index = self.index.clone();
+
+        Ok(Box::new(self.pool.spawn_fn(move || {
+            let index = index.lock().map_err(|_| PoisonError)?;
+
+            if index.get_deployments(&package, &version)?.is_empty() {
+                return Ok(Self::not_found());
+            }
+
+            info!("Yanking: {}@{}", package, version);
+
+            index.yank(&package, &version, message.as_ref().map(String::as_str))?;
+            Ok(Response::new().with_status(StatusCode::Ok))
+        })))
+    }
+
     fn inner_call<'a, I>(
         &self,
         req: Request,
@@ -194,8 +457,11 @@ impl ReprotoService {
 
         if let Some(part) = it.next() {
             match (req.method(), part) {
-                (&Method::Get, "objects") => return self.get_objects(it),
+                (&Method::Get, "objects") => return self.get_objects(req, it),
                 (&Method::Put, "objects") => return self.put_objects(req, it),
+                (&Method::Get, "index") => return self.get_index(req, it),
+                (&Method::Put, "index") => return self.put_index(req, it),
+                (&Method::Delete, "index") => return self.delete_index(req, it),
                 _ => return Ok(Box::new(ok(Self::not_found()))),
             }
         }
@@ -205,6 +471,12 @@ impl ReprotoService {
 
     fn handle_error(e: Error) -> Response {
         match *e.kind() {
+            Unauthorized => {
+                let mut response = Response::new().with_status(StatusCode::Unauthorized);
+                response.headers_mut().set_raw("WWW-Authenticate", "Bearer");
+                return response;
+            }
+            Forbidden => return Response::new().with_status(StatusCode::Forbidden),
             BadRequest(ref message) => {
                 return Response::new()
                     .with_status(StatusCode::BadRequest)
@@ -247,3 +519,161 @@ impl Service for ReprotoService {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use self::tempdir::TempDir;
+    use super::*;
+    use auth::NoAuthenticator;
+    use reproto_repository::{Deployment, init_file_index};
+
+    const CONTENT: &[u8] = b"type Foo {}";
+
+    struct Fixture {
+        _dir: TempDir,
+        service: ReprotoService,
+        object: Checksum,
+    }
+
+    fn fixture() -> Fixture {
+        let dir = TempDir::new("reproto-server").unwrap();
+
+        let index_path = dir.path().join("index");
+        init_file_index(&index_path).unwrap();
+
+        let mut objects = FileObjects::new(&dir.path().join("objects"));
+        let object = to_checksum(CONTENT).unwrap();
+        objects.put_object(&object, &mut &CONTENT[..], false).unwrap();
+
+        let service = ReprotoService {
+            max_file_size: 1000,
+            pool: Arc::new(CpuPool::new(1)),
+            objects: Arc::new(Mutex::new(objects)),
+            index: Arc::new(Mutex::new(FileIndex::new(&index_path).unwrap())),
+            authenticator: Arc::new(NoAuthenticator),
+        };
+
+        Fixture {
+            _dir: dir,
+            service: service,
+            object: object,
+        }
+    }
+
+    fn call(
+        service: &ReprotoService,
+        method: Method,
+        uri: &str,
+        body: Option<String>,
+    ) -> (StatusCode, Vec<u8>) {
+        let mut req = Request::new(method, uri.parse().unwrap());
+
+        if let Some(body) = body {
+            req.headers_mut().set(ContentLength(body.len() as u64));
+            req.set_body(body);
+        }
+
+        let res = service.call(req).wait().unwrap();
+        let status = res.status();
+        let body = res.body().concat2().wait().unwrap().to_vec();
+        (status, body)
+    }
+
+    fn get<T: ::serde::de::DeserializeOwned>(service: &ReprotoService, uri: &str) -> T {
+        let (status, body) = call(service, Method::Get, uri, None);
+        assert_eq!(StatusCode::Ok, status);
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn put_body(object: &Checksum) -> Option<String> {
+        Some(format!("{{\"object\": \"{}\"}}", object))
+    }
+
+    #[test]
+    fn test_put_and_get_index() {
+        let f = fixture();
+
+        let (status, _) = call(
+            &f.service,
+            Method::Put,
+            "/index/io.reproto/1.0.0",
+            put_body(&f.object),
+        );
+        assert_eq!(StatusCode::Ok, status);
+
+        let packages: Vec<RpPackage> = get(&f.service, "/index");
+        assert_eq!(vec![RpPackage::parse("io.reproto")], packages);
+
+        let all: Vec<Deployment> = get(&f.service, "/index/io.reproto");
+        assert_eq!(1, all.len());
+        assert_eq!(Version::parse("1.0.0").unwrap(), all[0].version);
+        assert_eq!(f.object, all[0].object);
+
+        let exact: Vec<Deployment> = get(&f.service, "/index/io.reproto/1.0.0");
+        assert_eq!(1, exact.len());
+
+        let missing: Vec<Deployment> = get(&f.service, "/index/io.reproto/2.0.0");
+        assert!(missing.is_empty());
+
+        let matching: Vec<Deployment> = get(&f.service, "/index/io.reproto?version_req=%5E1");
+        assert_eq!(1, matching.len());
+
+        let other: Vec<Deployment> = get(&f.service, "/index/io.reproto?version_req=%5E2");
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn test_put_index_conflict() {
+        let f = fixture();
+        let uri = "/index/io.reproto/1.0.0";
+
+        let (status, _) = call(&f.service, Method::Put, uri, put_body(&f.object));
+        assert_eq!(StatusCode::Ok, status);
+
+        let (status, _) = call(&f.service, Method::Put, uri, put_body(&f.object));
+        assert_eq!(StatusCode::Conflict, status);
+
+        let uri = "/index/io.reproto/1.0.0?force=true";
+        let (status, _) = call(&f.service, Method::Put, uri, put_body(&f.object));
+        assert_eq!(StatusCode::Ok, status);
+    }
+
+    #[test]
+    fn test_put_index_missing_object() {
+        let f = fixture();
+        let missing = to_checksum(&b"missing"[..]).unwrap();
+
+        let (status, body) = call(
+            &f.service,
+            Method::Put,
+            "/index/io.reproto/1.0.0",
+            put_body(&missing),
+        );
+
+        assert_eq!(StatusCode::BadRequest, status);
+        assert_eq!(MISSING_OBJECT.as_bytes(), &body[..]);
+
+        let packages: Vec<RpPackage> = get(&f.service, "/index");
+        assert!(packages.is_empty());
+    }
+
+    #[test]
+    fn test_bad_index_requests() {
+        let f = fixture();
+        let body = || put_body(&f.object);
+
+        let (status, _) = call(&f.service, Method::Put, "/index/io..reproto/1.0.0", body());
+        assert_eq!(StatusCode::BadRequest, status);
+
+        let (status, _) = call(&f.service, Method::Put, "/index/io.reproto/latest", body());
+        assert_eq!(StatusCode::BadRequest, status);
+
+        let (status, _) = call(&f.service, Method::Put, "/index/io.reproto/1.0.0", None);
+        assert_eq!(StatusCode::BadRequest, status);
+
+        let (status, _) = call(&f.service, Method::Get, "/index/io.reproto/1.0.0/extra", None);
+        assert_eq!(StatusCode::NotFound, status);
+    }
+}
@@ -55,6 +55,12 @@ fn main() {
         let mut ss = SyntaxSet::new();
         ss.load_plain_text_syntax();
         ss.load_syntaxes(syntaxes, true).expect("syntaxes to load");
+
+        // used to render the source of specifications.
+        if ss.find_syntax_by_extension("reproto").is_none() {
+            panic!("missing reproto syntax in: {}", root.join("syntaxes").display());
+        }
+
         println!("building: {}", path.display());
         dump_to_file(&ss, path).expect("syntaxes to pack");
     }